@@ -0,0 +1,253 @@
+//! On-disk cache of one-shot replies, so re-running `gx commit --ai` on the
+//! same staged diff or reopening reviewer suggestions does not repeat a slow
+//! agent call.
+//!
+//! Entries are keyed by an FNV-1a digest of the provider, model, rendered
+//! prompt and stdin payload (the same hashing as
+//! [`crate::git::review::state::storage_key`]) and live as one JSON file each
+//! in the user's cache dir (replies quote diffs, so never a shared temp dir).
+//! `[ai.cache]` sets the TTL and size cap; `gx --no-cache` bypasses the cache
+//! for one run and `gx ai cache clear` empties it.
+
+use crate::config::{self, AiCacheConfig};
+use crate::git::review::state::fnv1a_hex;
+use etcetera::BaseStrategy;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static BYPASS: AtomicBool = AtomicBool::new(false);
+
+/// Skip the cache (neither read nor write) for the rest of this process.
+pub fn bypass() {
+    BYPASS.store(true, Ordering::Relaxed);
+}
+
+/// Where cached replies are stored: `gx/ai` in the user's cache dir
+/// (`$XDG_CACHE_HOME`, else `~/.cache`), or `None` when there is no home
+/// directory to put it in.
+pub fn dir() -> Option<PathBuf> {
+    let strategy = etcetera::choose_base_strategy().ok()?;
+    Some(strategy.cache_dir().join("gx").join("ai"))
+}
+
+/// The cache this process uses, or `None` when `[ai.cache] enabled = false`
+/// or `--no-cache` was passed.
+pub fn current() -> Option<&'static Cache> {
+    static CURRENT: OnceLock<Option<Cache>> = OnceLock::new();
+    if BYPASS.load(Ordering::Relaxed) {
+        return None;
+    }
+    CURRENT
+        .get_or_init(|| {
+            let config = config::load().ok()?;
+            let settings = &config.ai.cache;
+            if !settings.enabled {
+                return None;
+            }
+            Some(Cache::new(dir()?, settings))
+        })
+        .as_ref()
+}
+
+/// The cache key for one request. `provider` identifies who answers (an agent
+/// name, or the HTTP endpoint); `stdin` is digested on its own first so a
+/// prompt that inlines its input and one that pipes it never share a key.
+pub fn key(provider: &str, model: &str, prompt: &str, stdin: Option<&str>) -> String {
+    let input = stdin.map(fnv1a_hex).unwrap_or_default();
+    fnv1a_hex(&format!("{provider}\0{model}\0{prompt}\0{input}"))
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Seconds since the Unix epoch.
+    created: u64,
+    reply: String,
+}
+
+pub struct Cache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_bytes: u64,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf, settings: &AiCacheConfig) -> Self {
+        Cache {
+            dir,
+            ttl: Duration::from_secs(settings.ttl_hours.saturating_mul(3600)),
+            max_bytes: settings.max_size_mb.saturating_mul(1024 * 1024),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    /// The stored reply for `key`, unless it is missing, unreadable or older
+    /// than the TTL (expired entries are deleted on the way).
+    pub fn get(&self, key: &str) -> Option<String> {
+        let path = self.path(key);
+        let text = std::fs::read_to_string(&path).ok()?;
+        let entry: Entry = serde_json::from_str(&text).ok()?;
+        if now_secs().saturating_sub(entry.created) >= self.ttl.as_secs() {
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        Some(entry.reply)
+    }
+
+    /// Store `reply` under `key`, then trim the cache back under its limits.
+    /// Failures are ignored: a cache that cannot be written is just a miss
+    /// next time.
+    pub fn put(&self, key: &str, reply: &str) {
+        if std::fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+        // Replies quote diffs; keep them private even if the cache dir is not.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.dir, std::fs::Permissions::from_mode(0o700));
+        }
+        let entry = Entry {
+            created: now_secs(),
+            reply: reply.to_string(),
+        };
+        if let Ok(json) = serde_json::to_string(&entry) {
+            let _ = std::fs::write(self.path(key), json);
+        }
+        self.trim();
+    }
+
+    /// Delete expired entries, then the oldest ones until the directory fits
+    /// in `max_bytes`.
+    fn trim(&self) {
+        let now = SystemTime::now();
+        let mut kept = Vec::new();
+        for (path, modified, size) in entries(&self.dir) {
+            let age = now.duration_since(modified).unwrap_or_default();
+            if age >= self.ttl {
+                let _ = std::fs::remove_file(&path);
+            } else {
+                kept.push((path, modified, size));
+            }
+        }
+
+        let mut total: u64 = kept.iter().map(|(_, _, size)| size).sum();
+        kept.sort_by_key(|(_, modified, _)| *modified);
+        for (path, _, size) in kept {
+            if total <= self.max_bytes {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                total -= size;
+            }
+        }
+    }
+}
+
+/// Remove every cached reply in `dir`, returning how many files and bytes
+/// were deleted.
+pub fn clear(dir: &Path) -> std::io::Result<(usize, u64)> {
+    let mut removed = (0, 0);
+    for (path, _, size) in entries(dir) {
+        std::fs::remove_file(&path)?;
+        removed.0 += 1;
+        removed.1 += size;
+    }
+    Ok(removed)
+}
+
+/// The `*.json` files in `dir` with their modification time and size.
+fn entries(dir: &Path) -> Vec<(PathBuf, SystemTime, u64)> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    read_dir
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            Some((e.path(), meta.modified().ok()?, meta.len()))
+        })
+        .collect()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(dir: &Path, ttl_hours: u64, max_size_mb: u64) -> Cache {
+        Cache::new(
+            dir.to_path_buf(),
+            &AiCacheConfig {
+                enabled: true,
+                ttl_hours,
+                max_size_mb,
+            },
+        )
+    }
+
+    #[test]
+    fn test_key_covers_every_input() {
+        let base = key("claude", "sonnet", "PROMPT", Some("DIFF"));
+        assert_eq!(base, key("claude", "sonnet", "PROMPT", Some("DIFF")));
+        assert_ne!(base, key("opencode", "sonnet", "PROMPT", Some("DIFF")));
+        assert_ne!(base, key("claude", "opus", "PROMPT", Some("DIFF")));
+        assert_ne!(base, key("claude", "sonnet", "PROMPT2", Some("DIFF")));
+        assert_ne!(base, key("claude", "sonnet", "PROMPT", Some("DIFF2")));
+        assert_ne!(base, key("claude", "sonnet", "PROMPT", None));
+    }
+
+    #[test]
+    fn test_get_round_trips_and_expires() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = cache(tmp.path(), 1, 1);
+        assert_eq!(cache.get("k"), None);
+        cache.put("k", "feat: add cache");
+        assert_eq!(cache.get("k").as_deref(), Some("feat: add cache"));
+
+        let stale = Entry {
+            created: now_secs() - 2 * 3600,
+            reply: "old".to_string(),
+        };
+        std::fs::write(cache.path("k"), serde_json::to_string(&stale).unwrap()).unwrap();
+        assert_eq!(cache.get("k"), None);
+        assert!(!cache.path("k").exists());
+    }
+
+    #[test]
+    fn test_trim_evicts_oldest_over_the_size_cap() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut cache = cache(tmp.path(), 24, 1);
+        let reply = "x".repeat(400);
+        for (i, name) in ["a", "b", "c"].iter().enumerate() {
+            cache.put(name, &reply);
+            let file = std::fs::File::options()
+                .write(true)
+                .open(cache.path(name))
+                .unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(60 * (3 - i as u64)))
+                .unwrap();
+        }
+        let size = std::fs::metadata(cache.path("a")).unwrap().len();
+        cache.max_bytes = size * 2;
+        cache.trim();
+        assert!(!cache.path("a").exists());
+        assert!(cache.path("b").exists());
+        assert!(cache.path("c").exists());
+
+        assert_eq!(clear(tmp.path()).unwrap(), (2, size * 2));
+        assert_eq!(cache.get("c"), None);
+    }
+}
@@ -0,0 +1,507 @@
+//! Diff preparation for AI prompts. A raw patch can be dominated by lockfile
+//! churn, vendored code, or one enormous generated hunk, which either blows the
+//! agent's context or drowns the change that matters. [`prepare`] turns a
+//! unified patch into a budgeted one: noise files are dropped, huge hunks are
+//! collapsed to their stats, whole files are omitted once the byte budget is
+//! spent, and a summary header lists every file and what was left out so the
+//! model knows the picture is partial.
+//!
+//! The input is the plain `git diff` text every caller already produces, so
+//! the same layer serves the commit message, PR, and reviewer prompts.
+
+use crate::config::AiConfig;
+
+/// Lockfiles, matched on the file name.
+const LOCKFILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "Gemfile.lock",
+    "composer.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "uv.lock",
+    "go.sum",
+    "flake.lock",
+    "mix.lock",
+    "pubspec.lock",
+    "Podfile.lock",
+];
+
+/// Directory components that mark vendored or installed third-party code.
+const VENDOR_DIRS: &[&str] = &["vendor", "node_modules", "third_party", "third-party"];
+
+/// File-name suffixes of minified or generated artifacts.
+const GENERATED_SUFFIXES: &[&str] = &[".min.js", ".min.css", ".map", ".snap", ".pb.go"];
+
+/// Rough bytes-per-token ratio used to turn the configured token budget into
+/// bytes; close enough for code and English to size a prompt.
+pub const BYTES_PER_TOKEN: usize = 4;
+
+/// Limits for [`prepare`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffBudget {
+    /// Upper bound for the whole prepared text, header included.
+    pub max_bytes: usize,
+    /// Hunks longer than this many lines are collapsed to their stats.
+    pub max_hunk_lines: usize,
+}
+
+impl DiffBudget {
+    pub fn from_config(ai: &AiConfig) -> Self {
+        DiffBudget {
+            max_bytes: ai.diff_budget_tokens.saturating_mul(BYTES_PER_TOKEN),
+            max_hunk_lines: ai.diff_max_hunk_lines,
+        }
+    }
+}
+
+/// Why a file's content is missing from the prepared diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Omission {
+    Lockfile,
+    Vendored,
+    Generated,
+    Binary,
+    OverBudget,
+}
+
+impl Omission {
+    fn label(self) -> &'static str {
+        match self {
+            Omission::Lockfile => "lockfile",
+            Omission::Vendored => "vendored",
+            Omission::Generated => "generated",
+            Omission::Binary => "binary",
+            Omission::OverBudget => "over budget",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Hunk {
+    header: String,
+    body: Vec<String>,
+    added: usize,
+    removed: usize,
+}
+
+#[derive(Debug)]
+struct FileDiff {
+    path: String,
+    /// `diff --git`, `index`, `---`/`+++` and similar lines before the first hunk.
+    preamble: Vec<String>,
+    hunks: Vec<Hunk>,
+    binary: bool,
+}
+
+impl FileDiff {
+    fn added(&self) -> usize {
+        self.hunks.iter().map(|h| h.added).sum()
+    }
+
+    fn removed(&self) -> usize {
+        self.hunks.iter().map(|h| h.removed).sum()
+    }
+}
+
+/// One file's row in the summary header.
+struct Entry {
+    path: String,
+    added: usize,
+    removed: usize,
+    omitted: Option<Omission>,
+    collapsed: usize,
+}
+
+/// Prepare `diff` for a prompt within `budget`. An empty diff stays empty.
+pub fn prepare(diff: &str, budget: &DiffBudget) -> String {
+    let files = parse(diff);
+    if files.is_empty() {
+        return String::new();
+    }
+
+    let mut entries = Vec::with_capacity(files.len());
+    let mut bodies = Vec::with_capacity(files.len());
+    for file in &files {
+        let omitted = classify(file);
+        let (body, collapsed) = match omitted {
+            Some(_) => (String::new(), 0),
+            None => render(file, budget.max_hunk_lines),
+        };
+        entries.push(Entry {
+            path: file.path.clone(),
+            added: file.added(),
+            removed: file.removed(),
+            omitted,
+            collapsed,
+        });
+        bodies.push(body);
+    }
+
+    // Reserve room for the header as if every file could end up over budget,
+    // then keep files in order while their content still fits.
+    let reserve: usize = header_reserve(&entries);
+    let mut remaining = budget.max_bytes.saturating_sub(reserve);
+    for (entry, body) in entries.iter_mut().zip(bodies.iter_mut()) {
+        if entry.omitted.is_some() {
+            continue;
+        }
+        if body.len() <= remaining {
+            remaining -= body.len();
+        } else {
+            entry.omitted = Some(Omission::OverBudget);
+            body.clear();
+        }
+    }
+
+    let mut out = header(&entries, budget.max_bytes);
+    for body in bodies.iter().filter(|b| !b.is_empty()) {
+        out.push('\n');
+        out.push_str(body);
+    }
+    out
+}
+
+/// Join `files` one per line within `budget`, ending with a note on how many
+/// were left out. For prompts that carry a file list rather than a patch (the
+/// reviewer suggestion), which would otherwise grow without bound on huge PRs.
+pub fn prepare_file_list(files: &[String], budget: &DiffBudget) -> String {
+    let mut out = String::new();
+    for (i, file) in files.iter().enumerate() {
+        // Leave room for the trailing note.
+        if out.len() + file.len() + 64 > budget.max_bytes {
+            out.push_str(&format!(
+                "[{} more file(s) omitted: over budget]\n",
+                files.len() - i
+            ));
+            break;
+        }
+        out.push_str(file);
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
+/// The paths `diff` touches, in order (for prompt `{files}` variables).
+pub fn changed_files(diff: &str) -> Vec<String> {
+    parse(diff).into_iter().map(|file| file.path).collect()
+}
+
+fn parse(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff {
+                path: path_from_git_header(rest),
+                preamble: vec![line.to_string()],
+                hunks: Vec::new(),
+                binary: false,
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            file.hunks.push(Hunk {
+                header: line.to_string(),
+                body: Vec::new(),
+                added: 0,
+                removed: 0,
+            });
+        } else if let Some(hunk) = file.hunks.last_mut() {
+            match line.as_bytes().first() {
+                Some(b'+') => hunk.added += 1,
+                Some(b'-') => hunk.removed += 1,
+                _ => {}
+            }
+            hunk.body.push(line.to_string());
+        } else {
+            if line.starts_with("Binary files ") || line == "GIT binary patch" {
+                file.binary = true;
+            }
+            if let Some(path) = line.strip_prefix("+++ b/") {
+                file.path = path.to_string();
+            }
+            file.preamble.push(line.to_string());
+        }
+    }
+
+    files
+}
+
+/// `a/<old> b/<new>` → `<new>`. Paths with spaces are ambiguous here, so the
+/// `+++ b/` line overrides this when present.
+fn path_from_git_header(rest: &str) -> String {
+    match rest.rfind(" b/") {
+        Some(idx) => rest[idx + 3..].to_string(),
+        None => rest.to_string(),
+    }
+}
+
+/// Whether `path` names a dependency lockfile (`Cargo.lock`, `yarn.lock`, …).
+pub fn is_lockfile(path: &str) -> bool {
+    LOCKFILES.contains(&path.rsplit('/').next().unwrap_or(path))
+}
+
+fn classify(file: &FileDiff) -> Option<Omission> {
+    let path = file.path.as_str();
+    let name = path.rsplit('/').next().unwrap_or(path);
+
+    if file.binary {
+        Some(Omission::Binary)
+    } else if is_lockfile(path) {
+        Some(Omission::Lockfile)
+    } else if path
+        .split('/')
+        .rev()
+        .skip(1)
+        .any(|dir| VENDOR_DIRS.contains(&dir))
+    {
+        Some(Omission::Vendored)
+    } else if GENERATED_SUFFIXES.iter().any(|s| name.ends_with(s)) {
+        Some(Omission::Generated)
+    } else {
+        None
+    }
+}
+
+/// Render a kept file, collapsing hunks over `max_hunk_lines`. Returns the text
+/// and how many hunks were collapsed.
+fn render(file: &FileDiff, max_hunk_lines: usize) -> (String, usize) {
+    let mut out = String::new();
+    let mut collapsed = 0;
+    for line in &file.preamble {
+        out.push_str(line);
+        out.push('\n');
+    }
+    for hunk in &file.hunks {
+        out.push_str(&hunk.header);
+        out.push('\n');
+        if hunk.body.len() > max_hunk_lines {
+            collapsed += 1;
+            out.push_str(&format!(
+                "[hunk collapsed: {} lines, +{} -{}]\n",
+                hunk.body.len(),
+                hunk.added,
+                hunk.removed
+            ));
+        } else {
+            for line in &hunk.body {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    (out, collapsed)
+}
+
+fn entry_line(entry: &Entry) -> String {
+    let mut line = format!("  {} (+{} -{})", entry.path, entry.added, entry.removed);
+    if let Some(omission) = entry.omitted {
+        line.push_str(&format!(" [omitted: {}]", omission.label()));
+    } else if entry.collapsed > 0 {
+        line.push_str(&format!(" [{} hunk(s) collapsed]", entry.collapsed));
+    }
+    line.push('\n');
+    line
+}
+
+/// Appended to the header when anything is left out of the diff below it.
+const PARTIAL_NOTE: &str = "Files marked omitted and hunks marked collapsed are NOT shown below; only their stats are known.\n";
+
+/// The summary header. When listing every file would exceed `limit` (its
+/// content is then all omitted), the list stops early with a count of the rest.
+fn header(entries: &[Entry], limit: usize) -> String {
+    let mut out = summary_line(entries);
+    let partial = entries
+        .iter()
+        .any(|e| e.omitted.is_some() || e.collapsed > 0);
+    let lines: Vec<String> = entries.iter().map(entry_line).collect();
+    let full = out.len() + lines.iter().map(String::len).sum::<usize>();
+    let note = if partial { PARTIAL_NOTE } else { "" };
+
+    if full + note.len() <= limit {
+        lines.iter().for_each(|line| out.push_str(line));
+    } else {
+        for (i, line) in lines.iter().enumerate() {
+            let more = format!("  [{} more file(s) not listed]\n", lines.len() - i);
+            if out.len() + line.len() + more.len() + note.len() > limit {
+                out.push_str(&more);
+                break;
+            }
+            out.push_str(line);
+        }
+    }
+    out.push_str(note);
+    out
+}
+
+fn summary_line(entries: &[Entry]) -> String {
+    let added: usize = entries.iter().map(|e| e.added).sum();
+    let removed: usize = entries.iter().map(|e| e.removed).sum();
+    format!(
+        "Diff summary: {} file(s) changed, +{added} -{removed}\n",
+        entries.len()
+    )
+}
+
+/// Bytes the header can take once files start going over budget: each file's
+/// row with the longer of its own tag and the over-budget one, plus the note
+/// and one newline per kept file body.
+fn header_reserve(entries: &[Entry]) -> usize {
+    let rows: usize = entries
+        .iter()
+        .map(|e| {
+            let over = Entry {
+                path: e.path.clone(),
+                added: e.added,
+                removed: e.removed,
+                omitted: Some(Omission::OverBudget),
+                collapsed: 0,
+            };
+            entry_line(e).len().max(entry_line(&over).len())
+        })
+        .sum();
+    summary_line(entries).len() + rows + PARTIAL_NOTE.len() + entries.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: DiffBudget = DiffBudget {
+        max_bytes: 100_000,
+        max_hunk_lines: 50,
+    };
+
+    fn file_diff(path: &str, lines: usize) -> String {
+        let mut diff = format!(
+            "diff --git a/{path} b/{path}\nindex 111..222 100644\n--- a/{path}\n+++ b/{path}\n@@ -1,1 +1,{lines} @@\n"
+        );
+        for i in 0..lines {
+            diff.push_str(&format!("+line {i}\n"));
+        }
+        diff
+    }
+
+    #[test]
+    fn test_empty_diff_stays_empty() {
+        assert_eq!(prepare("", &BUDGET), "");
+    }
+
+    #[test]
+    fn test_small_diff_kept_with_summary() {
+        let diff = file_diff("src/main.rs", 3);
+        let out = prepare(&diff, &BUDGET);
+        assert!(out.starts_with("Diff summary: 1 file(s) changed, +3 -0\n  src/main.rs (+3 -0)\n"));
+        assert!(out.contains("+line 2\n"));
+        assert!(!out.contains("NOT shown"));
+    }
+
+    #[test]
+    fn test_lockfiles_vendored_and_generated_are_dropped() {
+        let diff = [
+            file_diff("Cargo.lock", 5),
+            file_diff("web/node_modules/x/index.js", 5),
+            file_diff("static/app.min.js", 5),
+            file_diff("src/lib.rs", 2),
+        ]
+        .concat();
+        let out = prepare(&diff, &BUDGET);
+        assert!(out.contains("  Cargo.lock (+5 -0) [omitted: lockfile]\n"));
+        assert!(out.contains("[omitted: vendored]"));
+        assert!(out.contains("  static/app.min.js (+5 -0) [omitted: generated]\n"));
+        assert!(out.contains("diff --git a/src/lib.rs"));
+        assert!(!out.contains("diff --git a/Cargo.lock"));
+        assert!(out.contains("NOT shown"));
+    }
+
+    #[test]
+    fn test_binary_files_are_dropped() {
+        let diff = "diff --git a/logo.png b/logo.png\nindex 1..2 100644\nBinary files a/logo.png and b/logo.png differ\n";
+        let out = prepare(diff, &BUDGET);
+        assert!(out.contains("  logo.png (+0 -0) [omitted: binary]\n"));
+        assert!(!out.contains("Binary files"));
+    }
+
+    #[test]
+    fn test_large_hunks_collapse_to_stats() {
+        let out = prepare(&file_diff("src/big.rs", 80), &BUDGET);
+        assert!(out.contains("  src/big.rs (+80 -0) [1 hunk(s) collapsed]\n"));
+        assert!(out.contains("[hunk collapsed: 80 lines, +80 -0]\n"));
+        assert!(!out.contains("+line 0\n"));
+    }
+
+    #[test]
+    fn test_budget_omits_files_that_do_not_fit() {
+        let diff = [file_diff("src/a.rs", 40), file_diff("src/b.rs", 40)].concat();
+        let tight = DiffBudget {
+            max_bytes: 900,
+            max_hunk_lines: 50,
+        };
+        let out = prepare(&diff, &tight);
+        assert!(out.len() <= tight.max_bytes);
+        assert!(out.contains("diff --git a/src/a.rs"));
+        assert!(out.contains("  src/b.rs (+40 -0) [omitted: over budget]\n"));
+        assert!(!out.contains("diff --git a/src/b.rs"));
+    }
+
+    #[test]
+    fn test_output_never_exceeds_budget() {
+        let many: String = (0..200)
+            .map(|i| file_diff(&format!("src/module_{i}/file.rs"), 3))
+            .collect();
+        let big = [file_diff("src/big.rs", 80), file_diff("src/a.rs", 40)].concat();
+        for diff in [many.as_str(), big.as_str()] {
+            for max_bytes in [300, 900, 2_000, 5_000] {
+                let budget = DiffBudget {
+                    max_bytes,
+                    max_hunk_lines: 50,
+                };
+                let out = prepare(diff, &budget);
+                assert!(out.len() <= max_bytes, "{} > {max_bytes}", out.len());
+                assert!(out.starts_with("Diff summary: "));
+            }
+        }
+
+        let out = prepare(
+            &many,
+            &DiffBudget {
+                max_bytes: 2_000,
+                max_hunk_lines: 50,
+            },
+        );
+        assert!(out.contains("more file(s) not listed]\n"));
+        assert!(out.ends_with("NOT shown below; only their stats are known.\n"));
+    }
+
+    #[test]
+    fn test_file_list_truncates_with_note() {
+        let files: Vec<String> = (0..100).map(|i| format!("src/file_{i}.rs")).collect();
+        let all = prepare_file_list(&files[..3], &BUDGET);
+        assert_eq!(all, "src/file_0.rs\nsrc/file_1.rs\nsrc/file_2.rs");
+
+        let tight = DiffBudget {
+            max_bytes: 200,
+            max_hunk_lines: 50,
+        };
+        let out = prepare_file_list(&files, &tight);
+        assert!(out.len() <= tight.max_bytes);
+        assert!(out.starts_with("src/file_0.rs\n"));
+        assert!(out.ends_with("more file(s) omitted: over budget]"));
+    }
+
+    #[test]
+    fn test_budget_from_config_converts_tokens() {
+        let budget = DiffBudget::from_config(&AiConfig::default());
+        assert_eq!(budget.max_bytes, 24_000 * BYTES_PER_TOKEN);
+        assert_eq!(budget.max_hunk_lines, 400);
+    }
+}
@@ -0,0 +1,308 @@
+//! The built-in `[ai.http]` provider: one chat-completions request against an
+//! OpenAI-compatible endpoint, for users who would rather point gx at OpenAI
+//! or a local llama.cpp / ollama server than install an agent CLI.
+//!
+//! Only the request/response subset gx needs is spoken: a system + user
+//! message pair in, `choices[0].message.content` (or the streamed
+//! `choices[0].delta.content` chunks) out.
+
+use super::AiError;
+use crate::config::HttpProviderConfig;
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader};
+use std::time::Duration;
+
+/// Send `prompt` (plus `input`, e.g. a diff, as the user message) to the
+/// configured endpoint and return the assistant's reply, trimmed. `model` is
+/// the `[ai] model` fallback used when `[ai.http] model` is empty.
+pub fn complete(
+    config: &HttpProviderConfig,
+    model: &str,
+    prompt: &str,
+    input: Option<&str>,
+) -> Result<String, AiError> {
+    send(config, model, prompt, input, |name| {
+        std::env::var(name).ok()
+    })
+}
+
+/// [`complete`], reading the API key through `env` rather than the process
+/// environment.
+fn send(
+    config: &HttpProviderConfig,
+    model: &str,
+    prompt: &str,
+    input: Option<&str>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<String, AiError> {
+    let model = if config.model.is_empty() {
+        model
+    } else {
+        &config.model
+    };
+    let url = format!("{}/chat/completions", config.base_url.trim_end_matches('/'));
+    let body = request_body(model, prompt, input, config.stream).to_string();
+
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(config.timeout_secs)))
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let mut request = agent.post(&url).header("Content-Type", "application/json");
+    if !config.api_key_env.is_empty() {
+        let key = env(&config.api_key_env).ok_or_else(|| {
+            AiError::Http(format!(
+                "{} (from [ai.http] api_key_env) is not set",
+                config.api_key_env
+            ))
+        })?;
+        request = request.header("Authorization", &format!("Bearer {key}"));
+    }
+
+    let mut response = request
+        .send(&body)
+        .map_err(|e| AiError::Http(format!("request to {url} failed: {e}")))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.body_mut().read_to_string().unwrap_or_default();
+        return Err(AiError::Http(format!(
+            "{url} returned {status}: {}",
+            error_detail(&text)
+        )));
+    }
+
+    let message = if config.stream {
+        read_stream(BufReader::new(response.body_mut().as_reader()))?
+    } else {
+        let text = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| AiError::Io(format!("reading the response from {url}: {e}")))?;
+        parse_completion(&text)?
+    };
+
+    let message = message.trim().to_string();
+    if message.is_empty() {
+        return Err(AiError::Empty(url));
+    }
+    Ok(message)
+}
+
+fn request_body(model: &str, prompt: &str, input: Option<&str>, stream: bool) -> Value {
+    let messages = match input {
+        Some(input) => json!([
+            { "role": "system", "content": prompt },
+            { "role": "user", "content": input },
+        ]),
+        None => json!([{ "role": "user", "content": prompt }]),
+    };
+    json!({ "model": model, "messages": messages, "stream": stream })
+}
+
+fn parse_completion(text: &str) -> Result<String, AiError> {
+    let value: Value = serde_json::from_str(text)
+        .map_err(|e| AiError::Http(format!("response is not JSON: {e}")))?;
+    value["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| AiError::Http("response has no choices[0].message.content".to_string()))
+}
+
+/// Concatenate the `delta.content` of every `data:` event until `[DONE]` (or
+/// the end of the body, which some local servers use instead).
+fn read_stream(reader: impl BufRead) -> Result<String, AiError> {
+    let mut message = String::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| AiError::Io(format!("reading the stream: {e}")))?;
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            continue;
+        };
+        if data == "[DONE]" {
+            break;
+        }
+        let chunk: Value = serde_json::from_str(data)
+            .map_err(|e| AiError::Http(format!("stream chunk is not JSON: {e}")))?;
+        if let Some(content) = chunk["choices"][0]["delta"]["content"].as_str() {
+            message.push_str(content);
+        }
+    }
+    Ok(message)
+}
+
+/// The `error.message` of an OpenAI-style error body, else the raw body.
+fn error_detail(text: &str) -> String {
+    serde_json::from_str::<Value>(text)
+        .ok()
+        .and_then(|v| v["error"]["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| text.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// A one-shot local stand-in for the endpoint: accepts a single connection,
+    /// hands the raw request back through the channel, and answers with
+    /// `status` and `body`.
+    fn serve_once(
+        status: &str,
+        content_type: &str,
+        body: &str,
+    ) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Read headers, then as much body as Content-Length announces.
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            tx.send(String::from_utf8_lossy(&request).to_string())
+                .unwrap();
+        });
+        (base_url, rx)
+    }
+
+    fn config(base_url: String) -> HttpProviderConfig {
+        HttpProviderConfig {
+            base_url,
+            timeout_secs: 5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_complete_returns_message_content() {
+        let (base_url, rx) = serve_once(
+            "200 OK",
+            "application/json",
+            r#"{"choices":[{"message":{"role":"assistant","content":"  feat: add login\n"}}]}"#,
+        );
+        let out = complete(&config(base_url), "llama3", "PROMPT", Some("DIFF")).unwrap();
+        assert_eq!(out, "feat: add login");
+
+        let request = rx.recv().unwrap();
+        assert!(request.starts_with("POST /v1/chat/completions "));
+        assert!(!request.to_ascii_lowercase().contains("authorization:"));
+        let body: Value = serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body["model"], "llama3");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][0]["content"], "PROMPT");
+        assert_eq!(body["messages"][1]["content"], "DIFF");
+    }
+
+    #[test]
+    fn test_complete_prefers_configured_model_and_sends_key() {
+        let (base_url, rx) = serve_once(
+            "200 OK",
+            "application/json",
+            r#"{"choices":[{"message":{"content":"ok"}}]}"#,
+        );
+        let cfg = HttpProviderConfig {
+            model: "gpt-mini".to_string(),
+            api_key_env: "GX_TEST_HTTP_PROVIDER_KEY".to_string(),
+            ..config(base_url)
+        };
+        let env = |name: &str| (name == "GX_TEST_HTTP_PROVIDER_KEY").then(|| "sk-test".to_string());
+        assert_eq!(send(&cfg, "ignored", "PROMPT", None, env).unwrap(), "ok");
+
+        let request = rx.recv().unwrap();
+        assert!(request.contains("Bearer sk-test"));
+        assert!(request.contains(r#""model":"gpt-mini""#));
+        assert!(request.contains(r#""messages":[{"content":"PROMPT","role":"user"}]"#));
+    }
+
+    #[test]
+    fn test_complete_reads_streamed_chunks() {
+        let events = concat!(
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"fix: \"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"handle empty diff\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let (base_url, rx) = serve_once("200 OK", "text/event-stream", events);
+        let cfg = HttpProviderConfig {
+            stream: true,
+            ..config(base_url)
+        };
+        assert_eq!(
+            complete(&cfg, "m", "PROMPT", None).unwrap(),
+            "fix: handle empty diff"
+        );
+        assert!(rx.recv().unwrap().contains(r#""stream":true"#));
+    }
+
+    #[test]
+    fn test_complete_surfaces_error_message() {
+        let (base_url, _rx) = serve_once(
+            "401 Unauthorized",
+            "application/json",
+            r#"{"error":{"message":"bad key"}}"#,
+        );
+        let err = complete(&config(base_url), "m", "PROMPT", None).unwrap_err();
+        let text = err.to_string();
+        assert!(text.contains("401"), "{text}");
+        assert!(text.contains("bad key"), "{text}");
+    }
+
+    #[test]
+    fn test_complete_empty_content_is_an_error() {
+        let (base_url, _rx) = serve_once(
+            "200 OK",
+            "application/json",
+            r#"{"choices":[{"message":{"content":"   "}}]}"#,
+        );
+        let err = complete(&config(base_url), "m", "PROMPT", None).unwrap_err();
+        assert!(matches!(err, AiError::Empty(_)));
+    }
+
+    #[test]
+    fn test_complete_missing_key_env_errors_before_sending() {
+        let cfg = HttpProviderConfig {
+            api_key_env: "GX_TEST_HTTP_PROVIDER_UNSET_KEY".to_string(),
+            ..config("http://127.0.0.1:9".to_string())
+        };
+        let err = send(&cfg, "m", "PROMPT", None, |_| None).unwrap_err();
+        assert!(err.to_string().contains("GX_TEST_HTTP_PROVIDER_UNSET_KEY"));
+    }
+
+    #[test]
+    fn test_unreachable_server_is_an_http_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let err = complete(&config(format!("http://{addr}/v1")), "m", "P", None).unwrap_err();
+        assert!(matches!(err, AiError::Http(_)));
+    }
+}
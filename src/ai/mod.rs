@@ -0,0 +1,506 @@
+//! Shared invocation of the configured AI agent (the opencode / claude presets
+//! or any `[ai.agents.<name>]` CLI definition).
+//!
+//! Both `gx commit --ai` and the PR dashboard (reviewer suggestion and the
+//! troubleshoot launch) drive the same agents, so the command-building and
+//! process plumbing live here instead of being duplicated per command.
+//! [`diff`] shrinks patches to a prompt budget before they reach an agent,
+//! [`prompts`] holds the overridable prompt templates, [`http`] is the
+//! built-in OpenAI-compatible alternative to an agent CLI, and [`cache`] keeps
+//! replies on disk so identical requests are answered without a new call.
+
+pub mod cache;
+pub mod diff;
+pub mod http;
+pub mod prompts;
+
+use crate::config::{Agent, AiConfig, HTTP_AGENT, HttpProviderConfig, PromptInput};
+use miette::Diagnostic;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Stdio};
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum AiError {
+    #[error("{0}")]
+    #[diagnostic(
+        code(gx::ai::spawn_failed),
+        help("Ensure the configured AI agent is installed and available in your PATH")
+    )]
+    Spawn(String),
+
+    #[error("{0}")]
+    #[diagnostic(code(gx::ai::agent_failed))]
+    Failed(String),
+
+    #[error("{0} returned an empty response")]
+    #[diagnostic(code(gx::ai::empty_response))]
+    Empty(String),
+
+    #[error("{0}")]
+    #[diagnostic(code(gx::ai::io_error))]
+    Io(String),
+
+    #[error("{0}")]
+    #[diagnostic(
+        code(gx::ai::http_failed),
+        help("Check [ai.http] base_url, model, and api_key_env, and that the server is running")
+    )]
+    Http(String),
+}
+
+/// Which gx feature is asking for a completion; each can pick its own provider
+/// via `[ai] commit_agent` / `reviewer_agent` / `explain_agent` /
+/// `workspace_agent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// Commit messages, split plans, and changelog summaries.
+    Commit,
+    Reviewer,
+    /// The "explain" action in the log viewer and review TUI.
+    Explain,
+    /// AI-suggested workspace names.
+    Workspace,
+}
+
+/// What answers a one-shot (non-interactive) prompt: an agent CLI, or the
+/// built-in `[ai.http]` endpoint.
+#[derive(Debug, Clone)]
+pub enum Provider {
+    Agent(Agent),
+    Http(HttpProviderConfig),
+}
+
+impl Provider {
+    /// Resolve the provider configured for `feature`, falling back to `[ai]
+    /// agent` when the feature key is empty. `"http"` selects the built-in
+    /// provider unless an `[ai.agents.http]` definition shadows it.
+    pub fn for_feature(config: &AiConfig, feature: Feature) -> Result<Self, String> {
+        let name = match feature {
+            Feature::Commit => &config.commit_agent,
+            Feature::Reviewer => &config.reviewer_agent,
+            Feature::Explain => &config.explain_agent,
+            Feature::Workspace => &config.workspace_agent,
+        };
+        let name = if name.is_empty() { &config.agent } else { name };
+        if name == HTTP_AGENT && !config.agents.contains_key(HTTP_AGENT) {
+            return Ok(Provider::Http(config.http.clone()));
+        }
+        config.resolve_agent(name).map(Provider::Agent)
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Provider::Agent(agent) => agent.as_str(),
+            Provider::Http(_) => HTTP_AGENT,
+        }
+    }
+
+    /// Run `prompt` (with `stdin` as its input) and return the trimmed reply,
+    /// answering from [`cache`] when the same request was made recently.
+    pub fn run_capturing(
+        &self,
+        model: &str,
+        prompt: &str,
+        stdin: Option<&str>,
+    ) -> Result<String, AiError> {
+        self.run_capturing_if(model, prompt, stdin, |_| true)
+    }
+
+    /// [`Self::run_capturing`] for callers that parse the reply: it is cached
+    /// only when `accept` passes, and a cached reply that fails `accept` is
+    /// asked for again, so a rejected answer (an invalid split plan, say) is
+    /// never replayed.
+    pub fn run_capturing_if(
+        &self,
+        model: &str,
+        prompt: &str,
+        stdin: Option<&str>,
+        accept: impl Fn(&str) -> bool,
+    ) -> Result<String, AiError> {
+        let cached =
+            cache::current().map(|c| (c, cache::key(&self.cache_id(), model, prompt, stdin)));
+        if let Some((c, key)) = &cached
+            && let Some(reply) = c.get(key)
+            && accept(&reply)
+        {
+            return Ok(reply);
+        }
+
+        let reply = match self {
+            Provider::Agent(agent) => run_capturing(agent, model, prompt, stdin),
+            Provider::Http(config) => http::complete(config, model, prompt, stdin),
+        }?;
+        if let Some((c, key)) = &cached
+            && accept(&reply)
+        {
+            c.put(key, &reply);
+        }
+        Ok(reply)
+    }
+
+    /// Who answers, for the cache key: the agent name with the command line it
+    /// resolves to (two custom agents can share a name across configs), or the
+    /// endpoint and model override of the HTTP provider.
+    fn cache_id(&self) -> String {
+        match self {
+            Provider::Agent(agent) => {
+                let definition = agent.definition();
+                format!(
+                    "{}\0{}\0{}\0{:?}",
+                    agent.as_str(),
+                    definition.command,
+                    definition.args.join("\0"),
+                    definition.prompt_input
+                )
+            }
+            Provider::Http(config) => {
+                format!("{HTTP_AGENT}\0{}\0{}", config.base_url, config.model)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Provider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Build the `(command, args)` needed to run `prompt` with the given agent and
+/// model, from the agent's [`AgentDefinition`] template. The prompt is a
+/// parameter so the same builder serves commit messages, reviewer suggestions,
+/// and investigate prompts. For a `prompt_input = "stdin"` agent the prompt is
+/// left out of argv; [`run_capturing`] writes it to stdin instead.
+pub fn agent_command(agent: &Agent, model: &str, prompt: &str) -> (String, Vec<String>) {
+    let definition = agent.definition();
+    let prompt = match definition.prompt_input {
+        PromptInput::Argv => Some(prompt),
+        PromptInput::Stdin => None,
+    };
+    (
+        definition.command,
+        expand_args(&definition.args, model, prompt),
+    )
+}
+
+/// Like [`agent_command`], but for an interactive session: the definition's
+/// `interactive_args` (falling back to `args`), with the prompt always passed
+/// on argv since the agent owns the terminal's stdin.
+pub fn interactive_command(agent: &Agent, model: &str, prompt: &str) -> (String, Vec<String>) {
+    let definition = agent.definition();
+    let templates = definition
+        .interactive_args
+        .as_ref()
+        .unwrap_or(&definition.args);
+    (
+        definition.command.clone(),
+        expand_args(templates, model, Some(prompt)),
+    )
+}
+
+/// Substitute `{model}` and `{prompt}` into each template argument. With a
+/// prompt, it is appended when no argument mentions `{prompt}`; without one
+/// (stdin mode), every argument mentioning `{prompt}` is dropped, so
+/// `--prompt={prompt}` doesn't become an empty `--prompt=`. `{model}` is replaced
+/// first so a prompt that happens to contain "{model}" stays verbatim.
+fn expand_args(templates: &[String], model: &str, prompt: Option<&str>) -> Vec<String> {
+    let mentions_prompt = templates.iter().any(|t| t.contains("{prompt}"));
+    let mut args: Vec<String> = templates
+        .iter()
+        .filter(|t| prompt.is_some() || !t.contains("{prompt}"))
+        .map(|t| {
+            t.replace("{model}", model)
+                .replace("{prompt}", prompt.unwrap_or(""))
+        })
+        .collect();
+    if let Some(prompt) = prompt
+        && !mentions_prompt
+    {
+        args.push(prompt.to_string());
+    }
+    args
+}
+
+/// Run the agent with `prompt`, optionally piping `stdin`, and capture stdout.
+///
+/// Both stdout and stderr are piped so the agent process can never write onto a
+/// caller's alternate screen (the PR dashboard renders a TUI to stderr while
+/// this runs on a background thread); stderr is surfaced only inside error
+/// messages.
+pub fn run_capturing(
+    agent: &Agent,
+    model: &str,
+    prompt: &str,
+    stdin: Option<&str>,
+) -> Result<String, AiError> {
+    let (command, args) = agent_command(agent, model, prompt);
+    // A stdin-mode agent reads the prompt first, then any piped input.
+    let payload = match agent.definition().prompt_input {
+        PromptInput::Argv => stdin.map(str::to_string),
+        PromptInput::Stdin => Some(match stdin {
+            Some(input) => format!("{prompt}\n\n{input}"),
+            None => prompt.to_string(),
+        }),
+    };
+    let stdin = payload.as_deref();
+
+    let mut child = Command::new(&command)
+        .args(&args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AiError::Spawn(format!("Failed to spawn {command}: {e}")))?;
+
+    // Feed stdin from a separate thread so a large prompt can't deadlock: the
+    // agent may emit stdout/stderr while we're still writing, and once those
+    // ~64KB pipe buffers fill it blocks on write. `wait_with_output` below drains
+    // both output pipes concurrently, so writing stdin off-thread keeps both
+    // sides flowing. The handle drops when the thread ends, closing stdin so the
+    // agent sees EOF.
+    let writer = match stdin {
+        Some(input) => {
+            let mut handle = child
+                .stdin
+                .take()
+                .ok_or_else(|| AiError::Failed(format!("{command}: failed to open stdin")))?;
+            let data = input.as_bytes().to_vec();
+            // Write errors (e.g. the agent closed stdin early) are intentionally
+            // ignored here; the exit status and captured stderr below reflect the
+            // real outcome.
+            Some(std::thread::spawn(move || {
+                let _ = handle.write_all(&data);
+            }))
+        }
+        None => None,
+    };
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| AiError::Io(format!("I/O error talking to {command}: {e}")))?;
+
+    if let Some(writer) = writer {
+        let _ = writer.join();
+    }
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let detail = if stderr.is_empty() {
+            "agent exited with an error".to_string()
+        } else {
+            stderr
+        };
+        return Err(AiError::Failed(format!("{command} failed: {detail}")));
+    }
+
+    let message = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if message.is_empty() {
+        return Err(AiError::Empty(command));
+    }
+
+    Ok(message)
+}
+
+/// Launch the agent interactively (inherited stdio) in `cwd`, for a hands-on
+/// session such as the PR troubleshoot flow. The caller must have torn down any
+/// TUI first, since the agent takes over the terminal.
+pub fn launch_interactive(
+    agent: &Agent,
+    model: &str,
+    prompt: &str,
+    cwd: &Path,
+) -> Result<ExitStatus, AiError> {
+    let (command, args) = interactive_command(agent, model, prompt);
+
+    Command::new(&command)
+        .args(&args)
+        .current_dir(cwd)
+        .status()
+        .map_err(|e| AiError::Spawn(format!("Failed to spawn {command}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AgentDefinition;
+
+    #[test]
+    fn test_agent_command_opencode_uses_prompt() {
+        let (command, args) =
+            agent_command(&Agent::OpenCode, "opencode/big-pickle", "do the thing");
+        assert_eq!(command, "opencode");
+        assert_eq!(
+            args,
+            vec![
+                "run".to_string(),
+                "do the thing".to_string(),
+                "--model".to_string(),
+                "opencode/big-pickle".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_agent_command_claude_uses_prompt() {
+        let (command, args) = agent_command(&Agent::Claude, "haiku", "review this PR");
+        assert_eq!(command, "claude");
+        assert_eq!(
+            args,
+            vec![
+                "-p".to_string(),
+                "review this PR".to_string(),
+                "--model".to_string(),
+                "haiku".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_agent_command_substitutes_prompt_verbatim() {
+        // The prompt is a parameter, not a baked-in constant: whatever is passed
+        // appears verbatim as the run argument.
+        let (_, opencode_args) = agent_command(&Agent::OpenCode, "m", "PROMPT-A");
+        let (_, claude_args) = agent_command(&Agent::Claude, "m", "PROMPT-A");
+        assert!(opencode_args.contains(&"PROMPT-A".to_string()));
+        assert!(claude_args.contains(&"PROMPT-A".to_string()));
+    }
+
+    fn custom(args: &[&str], prompt_input: PromptInput, interactive: Option<&[&str]>) -> Agent {
+        let strings = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        Agent::Custom {
+            name: "custom".to_string(),
+            definition: AgentDefinition {
+                command: "llm".to_string(),
+                args: strings(args),
+                prompt_input,
+                interactive_args: interactive.map(strings),
+            },
+        }
+    }
+
+    #[test]
+    fn test_agent_command_custom_substitutes_placeholders() {
+        let agent = custom(
+            &["-m", "{model}", "--system", "{prompt}"],
+            PromptInput::Argv,
+            None,
+        );
+        let (command, args) = agent_command(&agent, "gpt-x", "say {model}");
+        assert_eq!(command, "llm");
+        // `{model}` inside the prompt text is left alone.
+        assert_eq!(args, vec!["-m", "gpt-x", "--system", "say {model}"]);
+    }
+
+    #[test]
+    fn test_agent_command_appends_prompt_without_placeholder() {
+        let agent = custom(&["ask"], PromptInput::Argv, None);
+        let (_, args) = agent_command(&agent, "m", "hi");
+        assert_eq!(args, vec!["ask", "hi"]);
+    }
+
+    #[test]
+    fn test_agent_command_stdin_mode_keeps_prompt_off_argv() {
+        let agent = custom(&["run", "{model}", "{prompt}"], PromptInput::Stdin, None);
+        let (_, args) = agent_command(&agent, "llama3", "hi");
+        assert_eq!(args, vec!["run", "llama3"]);
+
+        let composite = custom(&["run", "--prompt={prompt}"], PromptInput::Stdin, None);
+        let (_, args) = agent_command(&composite, "llama3", "hi");
+        assert_eq!(args, vec!["run"]);
+    }
+
+    #[test]
+    fn test_interactive_command_prefers_interactive_args() {
+        let agent = custom(&["-p", "{prompt}"], PromptInput::Argv, Some(&["{prompt}"]));
+        let (_, args) = interactive_command(&agent, "m", "fix it");
+        assert_eq!(args, vec!["fix it"]);
+
+        // Stdin agents still get the prompt on argv interactively.
+        let stdin_agent = custom(&["run", "{model}"], PromptInput::Stdin, None);
+        let (_, args) = interactive_command(&stdin_agent, "llama3", "fix it");
+        assert_eq!(args, vec!["run", "llama3", "fix it"]);
+    }
+
+    #[test]
+    fn test_run_capturing_stdin_mode_pipes_prompt_then_input() {
+        // `cat` echoes its stdin, so the captured output is the payload.
+        let agent = Agent::Custom {
+            name: "cat".to_string(),
+            definition: AgentDefinition {
+                command: "cat".to_string(),
+                args: Vec::new(),
+                prompt_input: PromptInput::Stdin,
+                interactive_args: None,
+            },
+        };
+        let out = run_capturing(&agent, "", "PROMPT", Some("DIFF")).unwrap();
+        assert_eq!(out, "PROMPT\n\nDIFF");
+    }
+
+    #[test]
+    fn test_provider_for_feature_falls_back_and_selects_http() {
+        let mut config = AiConfig::default();
+        let commit = Provider::for_feature(&config, Feature::Commit).unwrap();
+        assert_eq!(commit.name(), config.agent);
+
+        config.commit_agent = HTTP_AGENT.to_string();
+        config.reviewer_agent = "claude".to_string();
+        assert!(matches!(
+            Provider::for_feature(&config, Feature::Commit).unwrap(),
+            Provider::Http(_)
+        ));
+        // Workspace names don't follow commit_agent.
+        assert_eq!(
+            Provider::for_feature(&config, Feature::Workspace)
+                .unwrap()
+                .name(),
+            config.agent
+        );
+        assert!(matches!(
+            Provider::for_feature(&config, Feature::Reviewer).unwrap(),
+            Provider::Agent(Agent::Claude)
+        ));
+    }
+
+    #[test]
+    fn test_cache_id_covers_the_custom_agent_command() {
+        let id = |agent: Agent| Provider::Agent(agent).cache_id();
+        let base = id(custom(&["-m", "{model}"], PromptInput::Argv, None));
+        assert_eq!(
+            base,
+            id(custom(&["-m", "{model}"], PromptInput::Argv, None))
+        );
+        assert_ne!(base, id(custom(&["--fast"], PromptInput::Argv, None)));
+        assert_ne!(
+            base,
+            id(custom(&["-m", "{model}"], PromptInput::Stdin, None))
+        );
+    }
+
+    #[test]
+    fn test_provider_for_feature_custom_agent_shadows_http() {
+        let mut config = AiConfig {
+            commit_agent: HTTP_AGENT.to_string(),
+            ..Default::default()
+        };
+        config.agents.insert(
+            HTTP_AGENT.to_string(),
+            AgentDefinition {
+                command: "curl".to_string(),
+                args: Vec::new(),
+                prompt_input: PromptInput::Stdin,
+                interactive_args: None,
+            },
+        );
+        assert!(matches!(
+            Provider::for_feature(&config, Feature::Commit).unwrap(),
+            Provider::Agent(Agent::Custom { .. })
+        ));
+    }
+}
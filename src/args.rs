@@ -31,6 +31,52 @@ impl ShellKind {
     }
 }
 
+/// Output formats for `gx changelog`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChangelogFormat {
+    Markdown,
+    Json,
+}
+
+/// Output formats for `gx review export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReviewFormat {
+    Json,
+    Sarif,
+    Rdjsonl,
+    Markdown,
+}
+
+/// The steps of `gx land`, in the order they run. `--from` resumes at one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum LandStep {
+    Update,
+    Test,
+    Push,
+    Merge,
+    Remove,
+}
+
+impl LandStep {
+    pub const ALL: [LandStep; 5] = [
+        LandStep::Update,
+        LandStep::Test,
+        LandStep::Push,
+        LandStep::Merge,
+        LandStep::Remove,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LandStep::Update => "update",
+            LandStep::Test => "test",
+            LandStep::Push => "push",
+            LandStep::Merge => "merge",
+            LandStep::Remove => "remove",
+        }
+    }
+}
+
 /// Kinds of dynamic completion candidates the generated shell helpers can
 /// request via the hidden `gx __complete <kind>` invocation.
 ///
@@ -53,6 +99,10 @@ pub enum CompleteKind {
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Neither reuse nor store cached AI replies for this run
+    #[arg(long, global = true)]
+    pub no_cache: bool,
 }
 
 #[derive(Subcommand)]
@@ -84,8 +134,11 @@ pub enum Commands {
     },
 
     /// Create a commit
-    #[command(alias = "c")]
+    #[command(alias = "c", args_conflicts_with_subcommands = true)]
     Commit {
+        #[command(subcommand)]
+        action: Option<CommitCommands>,
+
         /// Commit message (opens editor if omitted)
         message: Option<String>,
 
@@ -100,6 +153,18 @@ pub enum Commands {
         /// Generate commit message using AI
         #[arg(long)]
         ai: bool,
+
+        /// Let AI split all uncommitted changes into a series of commits
+        #[arg(long, conflicts_with_all = ["message", "amend", "no_edit", "ai"])]
+        ai_split: bool,
+
+        /// Skip linting the message against the commit rules
+        #[arg(long)]
+        no_lint: bool,
+
+        /// Skip the secret and large-file scan of the staged changes
+        #[arg(long)]
+        no_verify_secrets: bool,
     },
 
     /// Push commits to remote
@@ -112,6 +177,51 @@ pub enum Commands {
         /// Force push without lease (dangerous)
         #[arg(long)]
         force_dangerously: bool,
+
+        /// Push to a protected branch without asking
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Skip the secret and large-file scan of the outgoing commits
+        #[arg(long)]
+        no_verify_secrets: bool,
+    },
+
+    /// Land a branch: rebase, test, push, merge its PR, and remove the workspace
+    Land {
+        /// Workspace, pull request ('#123' or URL), or branch (defaults to the current workspace)
+        target: Option<String>,
+
+        /// Print the steps without running them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Resume at this step, skipping the ones before it
+        #[arg(long, value_enum, default_value = "update")]
+        from: LandStep,
+
+        /// Push to a protected branch without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Fetch, fast-forward the default branch, and update the current branch
+    #[command(alias = "pull")]
+    Sync,
+
+    /// Scan changes for secrets, workspace-local files, and large blobs
+    Scan {
+        /// Scan the commits in a range (e.g. origin/main..HEAD, or one commit)
+        #[arg(conflicts_with_all = ["uncommitted", "outgoing"])]
+        range: Option<String>,
+
+        /// Scan all uncommitted changes, untracked files included
+        #[arg(long, conflicts_with = "outgoing")]
+        uncommitted: bool,
+
+        /// Scan the commits `gx push` would send
+        #[arg(long)]
+        outgoing: bool,
     },
 
     /// Stash changes
@@ -129,6 +239,25 @@ pub enum Commands {
         limit: Option<usize>,
     },
 
+    /// Changelog of a commit range, grouped by conventional-commit type
+    Changelog {
+        /// Range to describe, e.g. v1.2.0..HEAD (a single ref means <ref>..HEAD)
+        #[arg(required_unless_present = "since_last_tag")]
+        range: Option<String>,
+
+        /// Describe everything since the most recent tag (up to HEAD)
+        #[arg(long, conflicts_with = "range")]
+        since_last_tag: bool,
+
+        /// Have the AI agent write a summary on top
+        #[arg(long)]
+        ai: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ChangelogFormat::Markdown)]
+        format: ChangelogFormat,
+    },
+
     /// Manage workspaces (git worktrees)
     #[command(alias = "ws")]
     Workspace {
@@ -144,14 +273,35 @@ pub enum Commands {
     },
 
     /// Review a diff in a TUI and leave line comments for a coding agent
-    #[command(alias = "rev")]
+    #[command(alias = "rev", args_conflicts_with_subcommands = true)]
     Review {
-        /// Branch, commit, or A..B range to review (defaults to branch-vs-base)
+        #[command(subcommand)]
+        action: Option<ReviewCommands>,
+
+        /// Branch, commit, A..B range, or pull request (#123 or URL) to review (defaults to branch-vs-base)
         target: Option<String>,
 
         /// Base for the default branch-vs-base range (defaults to origin's default branch)
         #[arg(long)]
         base: Option<String>,
+
+        /// Review only the staged changes (index vs HEAD)
+        #[arg(long, conflicts_with_all = ["target", "base", "unstaged"])]
+        staged: bool,
+
+        /// Review only the unstaged changes (working tree vs index)
+        #[arg(long, conflicts_with_all = ["target", "base"])]
+        unstaged: bool,
+
+        /// Write the saved review's agent prompt to FILE ('-' for stdout) instead of opening the TUI
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>,
+    },
+
+    /// Manage AI prompt templates and the reply cache
+    Ai {
+        #[command(subcommand)]
+        action: AiCommands,
     },
 
     /// Configure repo-specific workspace setup
@@ -235,6 +385,130 @@ pub enum StashCommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum CommitCommands {
+    /// Check commit messages against the commit rules (for CI)
+    Lint {
+        /// Commit or A..B range to lint (defaults to HEAD)
+        range: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReviewCommands {
+    /// Apply the review's suggested changes to the working tree
+    ApplySuggestions {
+        /// Branch, commit, A..B range, or pull request the review was left on (defaults to branch-vs-base)
+        target: Option<String>,
+
+        /// Base for the default branch-vs-base range (defaults to origin's default branch)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// The review of the staged changes (index vs HEAD)
+        #[arg(long, conflicts_with_all = ["target", "base", "unstaged"])]
+        staged: bool,
+
+        /// The review of the unstaged changes (working tree vs index)
+        #[arg(long, conflicts_with_all = ["target", "base"])]
+        unstaged: bool,
+
+        /// Apply every suggestion without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Print the saved review for scripts and CI tools
+    Export {
+        /// Branch, commit, A..B range, or pull request the review was left on (defaults to branch-vs-base)
+        target: Option<String>,
+
+        /// Base for the default branch-vs-base range (defaults to origin's default branch)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// The review of the staged changes (index vs HEAD)
+        #[arg(long, conflicts_with_all = ["target", "base", "unstaged"])]
+        staged: bool,
+
+        /// The review of the unstaged changes (working tree vs index)
+        #[arg(long, conflicts_with_all = ["target", "base"])]
+        unstaged: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ReviewFormat::Json)]
+        format: ReviewFormat,
+    },
+
+    /// Add the comments from an exported JSON review to the saved review
+    Import {
+        /// The JSON file to read (`-` for stdin)
+        file: String,
+
+        /// Branch, commit, A..B range, or pull request to import the comments into (defaults to branch-vs-base)
+        target: Option<String>,
+
+        /// Base for the default branch-vs-base range (defaults to origin's default branch)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// Import into the review of the staged changes (index vs HEAD)
+        #[arg(long, conflicts_with_all = ["target", "base", "unstaged"])]
+        staged: bool,
+
+        /// Import into the review of the unstaged changes (working tree vs index)
+        #[arg(long, conflicts_with_all = ["target", "base"])]
+        unstaged: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AiCommands {
+    /// List, show, or eject the prompt templates the AI features use
+    Prompts {
+        #[command(subcommand)]
+        action: Option<PromptCommands>,
+    },
+
+    /// Manage the on-disk cache of AI replies
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Delete every cached reply
+    Clear,
+}
+
+#[derive(Subcommand)]
+pub enum PromptCommands {
+    /// List templates with their source and variables (default action)
+    List,
+
+    /// Print the effective text of a template
+    Show {
+        /// Template name, as listed by `gx ai prompts list`
+        name: String,
+    },
+
+    /// Copy a built-in template out for editing
+    Eject {
+        /// Template name, as listed by `gx ai prompts list`
+        name: String,
+
+        /// Eject into the repo's .gx/prompts instead of the user config directory
+        #[arg(long)]
+        repo: bool,
+
+        /// Overwrite an existing template file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum PrCommands {
     /// Print open PRs grouped by state (non-interactive)
@@ -248,16 +522,35 @@ pub enum WorkspaceCommands {
     New {
         /// Name of the workspace (also used as the branch name by default;
         /// '/' is replaced with '-' in the directory name)
-        name: String,
+        #[arg(
+            required_unless_present_any = ["describe", "from_diff"],
+            conflicts_with_all = ["describe", "from_diff"]
+        )]
+        name: Option<String>,
 
         /// Base branch/commit/tag to create the new branch from (defaults to
         /// the matching remote branch, then origin's default branch, then HEAD)
+        #[arg(conflicts_with_all = ["describe", "from_diff"])]
         base: Option<String>,
 
         /// Branch to check out in the workspace (created if it doesn't exist)
         #[arg(short, long)]
         branch: Option<String>,
 
+        /// Let the AI agent name the workspace after this task description
+        #[arg(long, value_name = "TEXT", conflicts_with_all = ["from_diff", "branch"])]
+        describe: Option<String>,
+
+        /// Let the AI agent name the workspace after the current uncommitted
+        /// changes
+        #[arg(long, conflicts_with = "branch")]
+        from_diff: bool,
+
+        /// Base to create the AI-named workspace's branch from (with
+        /// --describe or --from-diff; same defaults as the base argument)
+        #[arg(long, value_name = "BASE", conflicts_with = "name")]
+        onto: Option<String>,
+
         /// Skip copying setup files (e.g. .env) into the new workspace
         #[arg(long)]
         no_setup: bool,
@@ -443,15 +736,44 @@ impl Commands {
             Commands::Status => commands::status::run(),
             Commands::Add { interactive, paths } => commands::add::run(interactive, paths),
             Commands::Commit {
+                action: Some(CommitCommands::Lint { range }),
+                ..
+            } => commands::commit::run_lint(range),
+            Commands::Commit {
+                action: None,
+                ai_split: true,
+                no_lint,
+                no_verify_secrets,
+                ..
+            } => commands::commit_split::run(no_lint, no_verify_secrets),
+            Commands::Commit {
+                action: None,
                 message,
                 amend,
                 no_edit,
                 ai,
-            } => commands::commit::run(message, amend, no_edit, ai),
+                no_lint,
+                no_verify_secrets,
+                ai_split: false,
+            } => commands::commit::run(message, amend, no_edit, ai, no_lint, no_verify_secrets),
             Commands::Push {
                 force,
                 force_dangerously,
-            } => commands::push::run(force, force_dangerously),
+                yes,
+                no_verify_secrets,
+            } => commands::push::run(force, force_dangerously, yes, no_verify_secrets),
+            Commands::Land {
+                target,
+                dry_run,
+                from,
+                yes,
+            } => commands::land::run(target, from, dry_run, yes),
+            Commands::Sync => commands::sync::run(),
+            Commands::Scan {
+                range,
+                uncommitted,
+                outgoing,
+            } => commands::scan::run(range, uncommitted, outgoing),
             Commands::Stash { action } => match action {
                 None => commands::stash::run_interactive(),
                 Some(StashCommands::Push { message, untracked }) => {
@@ -468,6 +790,12 @@ impl Commands {
                 }
             },
             Commands::Log { limit } => commands::log::run(limit),
+            Commands::Changelog {
+                range,
+                since_last_tag,
+                ai,
+                format,
+            } => commands::changelog::run(range, since_last_tag, ai, format),
             Commands::Onboarding => commands::onboarding::run(),
             Commands::Workspace { action } => match action {
                 None => commands::workspace::run_interactive(),
@@ -475,6 +803,9 @@ impl Commands {
                     name,
                     base,
                     branch,
+                    describe,
+                    from_diff,
+                    onto,
                     no_setup,
                     no_cd,
                     no_fetch,
@@ -482,10 +813,13 @@ impl Commands {
                     no_hooks,
                     detach,
                     track,
-                }) => commands::workspace::run_new(
-                    name,
-                    commands::workspace::NewWorkspaceOptions {
-                        base,
+                }) => {
+                    let source = match describe {
+                        Some(text) => Some(commands::workspace::NameSource::Describe(text)),
+                        None => from_diff.then_some(commands::workspace::NameSource::Diff),
+                    };
+                    let opts = commands::workspace::NewWorkspaceOptions {
+                        base: base.or(onto),
                         branch,
                         no_setup,
                         no_cd,
@@ -494,8 +828,12 @@ impl Commands {
                         no_hooks,
                         detach,
                         track,
-                    },
-                ),
+                    };
+                    match source {
+                        Some(source) => commands::workspace::run_new_suggested(source, opts),
+                        None => commands::workspace::run_new(name.unwrap_or_default(), opts),
+                    }
+                }
                 Some(WorkspaceCommands::Go { query }) => commands::workspace::run_go(query),
                 Some(WorkspaceCommands::List) => commands::workspace::run_list(),
                 Some(WorkspaceCommands::Update { query, base }) => {
@@ -548,7 +886,59 @@ impl Commands {
                 None => commands::pr::run_interactive(),
                 Some(PrCommands::List) => commands::pr::run_list(),
             },
-            Commands::Review { target, base } => commands::review::run(target, base),
+            Commands::Review {
+                action:
+                    Some(ReviewCommands::ApplySuggestions {
+                        target,
+                        base,
+                        staged,
+                        unstaged,
+                        yes,
+                    }),
+                ..
+            } => commands::review::run_apply_suggestions(target, base, staged, unstaged, yes),
+            Commands::Review {
+                action:
+                    Some(ReviewCommands::Export {
+                        target,
+                        base,
+                        staged,
+                        unstaged,
+                        format,
+                    }),
+                ..
+            } => commands::review::run_export(target, base, staged, unstaged, format),
+            Commands::Review {
+                action:
+                    Some(ReviewCommands::Import {
+                        file,
+                        target,
+                        base,
+                        staged,
+                        unstaged,
+                    }),
+                ..
+            } => commands::review::run_import(file, target, base, staged, unstaged),
+            Commands::Review {
+                action: None,
+                target,
+                base,
+                staged,
+                unstaged,
+                output,
+            } => commands::review::run(target, base, staged, unstaged, output),
+            Commands::Ai { action } => match action {
+                AiCommands::Prompts { action } => match action {
+                    None | Some(PromptCommands::List) => commands::ai::run_prompts_list(),
+                    Some(PromptCommands::Show { name }) => commands::ai::run_prompts_show(name),
+                    Some(PromptCommands::Eject { name, repo, force }) => {
+                        commands::ai::run_prompts_eject(name, repo, force)
+                    }
+                },
+                AiCommands::Cache { action } => match action {
+                    CacheCommands::Clear => commands::ai::run_cache_clear(),
+                },
+            },
             Commands::Setup {
                 shell,
                 completions,
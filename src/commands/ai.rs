@@ -0,0 +1,122 @@
+//! `gx ai` housekeeping: inspecting and ejecting the prompt templates the AI
+//! features render (see [`crate::ai::prompts`]), and clearing the reply
+//! cache (see [`crate::ai::cache`]).
+
+use crate::ai::cache;
+use crate::ai::prompts::{self, Template};
+use miette::{Diagnostic, Result};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum AiCommandError {
+    #[error("Unknown prompt template: {0}")]
+    #[diagnostic(
+        code(gx::ai::unknown_template),
+        help("Use 'gx ai prompts list' to see the available templates")
+    )]
+    UnknownTemplate(String),
+
+    #[error("{} already exists", .0.display())]
+    #[diagnostic(
+        code(gx::ai::template_exists),
+        help("Edit it in place, or pass --force to overwrite it with the built-in")
+    )]
+    AlreadyExists(PathBuf),
+
+    #[error("Not inside a git repository")]
+    #[diagnostic(
+        code(gx::ai::not_in_repo),
+        help("Run without --repo to eject into your user prompt directory")
+    )]
+    NotInRepo,
+
+    #[error("Failed to write {}: {message}", path.display())]
+    #[diagnostic(code(gx::ai::io_error))]
+    Io { path: PathBuf, message: String },
+
+    #[error("Failed to clear the AI cache in {}: {message}", path.display())]
+    #[diagnostic(code(gx::ai::cache_clear_failed))]
+    CacheClear { path: PathBuf, message: String },
+}
+
+fn parse_template(name: &str) -> Result<Template> {
+    Template::from_name(name)
+        .ok_or_else(|| AiCommandError::UnknownTemplate(name.to_string()).into())
+}
+
+/// `gx ai prompts [list]`: every template with where its effective text
+/// comes from and the variables it is rendered with.
+pub fn run_prompts_list() -> Result<()> {
+    let main_root = prompts::current_main_root();
+    for template in Template::ALL {
+        let (_, source) = prompts::resolve(template, main_root.as_deref())?;
+        let variables = template
+            .variables()
+            .iter()
+            .map(|v| format!("{{{v}}}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!("{:<13} {}", template.name(), template.description());
+        println!("{:<13} source: {source}", "");
+        println!("{:<13} variables: {variables}", "");
+    }
+    Ok(())
+}
+
+/// `gx ai prompts show <name>`: the effective template text, unrendered.
+pub fn run_prompts_show(name: String) -> Result<()> {
+    let template = parse_template(&name)?;
+    let (text, source) = prompts::resolve(template, prompts::current_main_root().as_deref())?;
+    eprintln!("# {} ({source})", template.name());
+    print!("{text}");
+    if !text.ends_with('\n') {
+        println!();
+    }
+    Ok(())
+}
+
+/// `gx ai prompts eject <name>`: write the built-in template to the user
+/// prompt directory (or the repo's `.gx/prompts` with `--repo`) to edit.
+pub fn run_prompts_eject(name: String, repo: bool, force: bool) -> Result<()> {
+    let template = parse_template(&name)?;
+    let dir = if repo {
+        let main_root = prompts::current_main_root().ok_or(AiCommandError::NotInRepo)?;
+        prompts::repo_dir(&main_root)
+    } else {
+        prompts::user_dir()?
+    };
+    let path = dir.join(format!("{}.md", template.name()));
+    if path.exists() && !force {
+        return Err(AiCommandError::AlreadyExists(path).into());
+    }
+
+    let io_error = |e: std::io::Error| AiCommandError::Io {
+        path: path.clone(),
+        message: e.to_string(),
+    };
+    std::fs::create_dir_all(&dir).map_err(io_error)?;
+    std::fs::write(&path, template.builtin()).map_err(io_error)?;
+
+    println!("Ejected {} prompt to {}", template.name(), path.display());
+    Ok(())
+}
+
+/// `gx ai cache clear`: delete every cached reply.
+pub fn run_cache_clear() -> Result<()> {
+    let Some(dir) = cache::dir() else {
+        println!("No cache directory (no home directory found); nothing to clear");
+        return Ok(());
+    };
+    let (files, bytes) = cache::clear(&dir).map_err(|e| AiCommandError::CacheClear {
+        path: dir.clone(),
+        message: e.to_string(),
+    })?;
+    println!(
+        "Removed {files} cached {} ({} KiB) from {}",
+        if files == 1 { "reply" } else { "replies" },
+        bytes.div_ceil(1024),
+        dir.display()
+    );
+    Ok(())
+}
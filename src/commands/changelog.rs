@@ -0,0 +1,449 @@
+//! `gx changelog <from>..<to>`: the commits in a range grouped by
+//! conventional-commit type, with PR references linked, as Markdown or JSON.
+//! `--ai` asks the configured agent for a human summary on top.
+//!
+//! Grouping is pure over the commit list (see [`build`]) so it is unit-tested
+//! without a repository; walking the range and resolving tags is the only git
+//! I/O.
+
+use crate::ai::prompts::{self, Template};
+use crate::ai::{Feature, Provider};
+use crate::args::ChangelogFormat;
+use crate::commit_lint;
+use crate::config;
+use crate::git::{self, GitError, log::RangeCommit};
+use miette::{Diagnostic, IntoDiagnostic, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::sync::LazyLock;
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum ChangelogError {
+    #[error("Git error: {0}")]
+    #[diagnostic(code(gx::changelog::git_error))]
+    GitError(#[from] GitError),
+
+    #[error("Invalid range: {0}")]
+    #[diagnostic(
+        code(gx::changelog::invalid_range),
+        help("Pass a range like 'v1.2.0..HEAD' (or a single ref, meaning '<ref>..HEAD')")
+    )]
+    InvalidRange(String),
+
+    #[error("No tag found before HEAD")]
+    #[diagnostic(
+        code(gx::changelog::no_tag),
+        help("Pass an explicit range instead, e.g. 'gx changelog <from>..HEAD'")
+    )]
+    NoTag,
+
+    #[error("AI summary failed: {0}")]
+    #[diagnostic(code(gx::changelog::ai_failed))]
+    AiError(String),
+}
+
+/// Sections in output order: conventional type → heading. Anything else
+/// (unknown types and non-conventional subjects) lands in "Other Changes".
+const SECTIONS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+    ("test", "Tests"),
+    ("build", "Build"),
+    ("ci", "CI"),
+    ("style", "Style"),
+    ("chore", "Chores"),
+];
+
+const OTHER: (&str, &str) = ("other", "Other Changes");
+
+/// `#123` at the start of the message or after whitespace / an opening
+/// bracket — GitHub's squash-merge `(#123)` suffix and `Closes #123` alike.
+static PR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[\s(\[])#(\d+)\b").expect("PR regex is valid"));
+
+/// The ` (#123)` GitHub appends to squash-merge subjects.
+static SQUASH_SUFFIX_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*\(#\d+\)$").expect("suffix regex is valid"));
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PullRef {
+    pub number: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Entry {
+    pub oid: String,
+    pub short_id: String,
+    /// The conventional type, or `None` for a non-conventional subject.
+    #[serde(rename = "type")]
+    pub ty: Option<String>,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+    pub subject: String,
+    pub author: String,
+    pub pull_requests: Vec<PullRef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Group {
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub title: String,
+    pub commits: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Changelog {
+    pub from: Option<String>,
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub groups: Vec<Group>,
+}
+
+impl Changelog {
+    /// `from..to`, or just `to` for an open-ended range.
+    fn range_label(&self) -> String {
+        match &self.from {
+            Some(from) => format!("{from}..{}", self.to),
+            None => self.to.clone(),
+        }
+    }
+
+    fn breaking(&self) -> impl Iterator<Item = &Entry> {
+        self.groups
+            .iter()
+            .flat_map(|g| &g.commits)
+            .filter(|e| e.breaking)
+    }
+}
+
+pub fn run(
+    range: Option<String>,
+    since_last_tag: bool,
+    ai: bool,
+    format: ChangelogFormat,
+) -> Result<()> {
+    let (from, to) = if since_last_tag {
+        let tag = git::log::last_tag_before("HEAD")
+            .map_err(ChangelogError::GitError)?
+            .ok_or(ChangelogError::NoTag)?;
+        (Some(tag), "HEAD".to_string())
+    } else {
+        parse_range(range.as_deref().unwrap_or_default())?
+    };
+
+    let commits = git::log::get_range(from.as_deref(), &to).map_err(ChangelogError::GitError)?;
+    let pr_base = git::github::origin_owner_repo()
+        .ok()
+        .flatten()
+        .map(|(owner, repo)| format!("https://github.com/{owner}/{repo}/pull"));
+    let mut changelog = build(from, to, &commits, pr_base.as_deref());
+
+    if ai && !changelog.groups.is_empty() {
+        changelog.summary = Some(summarize(&changelog)?);
+    }
+
+    match format {
+        ChangelogFormat::Markdown => print!("{}", render_markdown(&changelog)),
+        ChangelogFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&changelog).into_diagnostic()?
+        ),
+    }
+    Ok(())
+}
+
+/// `A..B` → `(Some(A), B)`; a single ref `A` means `A..HEAD`, and an empty
+/// side means `HEAD`, as in `git log`.
+fn parse_range(range: &str) -> Result<(Option<String>, String), ChangelogError> {
+    let range = range.trim();
+    if range.is_empty() {
+        return Err(ChangelogError::InvalidRange(
+            "no range given (or pass --since-last-tag)".to_string(),
+        ));
+    }
+    let or_head = |side: &str| {
+        if side.is_empty() {
+            "HEAD".to_string()
+        } else {
+            side.to_string()
+        }
+    };
+    match range.split_once("..") {
+        Some((_, to)) if to.starts_with('.') => Err(ChangelogError::InvalidRange(format!(
+            "'{range}': symmetric-difference ranges (A...B) are not supported"
+        ))),
+        Some((from, to)) => Ok((Some(or_head(from)), or_head(to))),
+        None => Ok((Some(range.to_string()), "HEAD".to_string())),
+    }
+}
+
+/// Group `commits` (newest first) into the [`SECTIONS`] order. Merge commits
+/// are skipped: their branch's commits are listed individually.
+fn build(
+    from: Option<String>,
+    to: String,
+    commits: &[RangeCommit],
+    pr_base: Option<&str>,
+) -> Changelog {
+    let mut groups: Vec<Group> = SECTIONS
+        .iter()
+        .chain([&OTHER])
+        .map(|(ty, title)| Group {
+            ty: ty.to_string(),
+            title: title.to_string(),
+            commits: Vec::new(),
+        })
+        .collect();
+
+    for commit in commits.iter().filter(|c| !c.is_merge) {
+        let entry = entry(commit, pr_base);
+        let section = entry
+            .ty
+            .as_deref()
+            .and_then(|ty| SECTIONS.iter().position(|(name, _)| *name == ty))
+            .unwrap_or(SECTIONS.len());
+        groups[section].commits.push(entry);
+    }
+    groups.retain(|g| !g.commits.is_empty());
+
+    Changelog {
+        from,
+        to,
+        summary: None,
+        groups,
+    }
+}
+
+fn entry(commit: &RangeCommit, pr_base: Option<&str>) -> Entry {
+    let parsed = commit_lint::parse_subject(&commit.summary);
+    let breaking = parsed.as_ref().is_some_and(|s| s.breaking)
+        || commit.message.lines().any(|line| {
+            line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+        });
+
+    let mut pull_requests: Vec<PullRef> = Vec::new();
+    for caps in PR_RE.captures_iter(&commit.message) {
+        let Ok(number) = caps[1].parse() else {
+            continue;
+        };
+        if pull_requests.iter().all(|pr| pr.number != number) {
+            pull_requests.push(PullRef {
+                number,
+                url: pr_base.map(|base| format!("{base}/{number}")),
+            });
+        }
+    }
+
+    // The PR reference GitHub appends to squash-merge subjects is shown as a
+    // link instead, so strip it from the description.
+    let description = parsed
+        .as_ref()
+        .map(|s| s.description)
+        .unwrap_or(&commit.summary);
+    let description = SQUASH_SUFFIX_RE.replace(description, "").to_string();
+
+    Entry {
+        oid: commit.oid.to_string(),
+        short_id: commit.short_id.clone(),
+        ty: parsed.as_ref().map(|s| s.ty.to_string()),
+        scope: parsed.as_ref().and_then(|s| s.scope.map(str::to_string)),
+        breaking,
+        description,
+        subject: commit.summary.clone(),
+        author: commit.author_name.clone(),
+        pull_requests,
+    }
+}
+
+fn render_entry(entry: &Entry) -> String {
+    let mut line = String::from("- ");
+    if let Some(scope) = &entry.scope {
+        line.push_str(&format!("**{scope}:** "));
+    }
+    line.push_str(&entry.description);
+    for pr in &entry.pull_requests {
+        match &pr.url {
+            Some(url) => line.push_str(&format!(" ([#{}]({url}))", pr.number)),
+            None => line.push_str(&format!(" (#{})", pr.number)),
+        }
+    }
+    line.push_str(&format!(" (`{}`)", entry.short_id));
+    line
+}
+
+fn render_markdown(changelog: &Changelog) -> String {
+    let mut out = format!("## {}\n\n", changelog.range_label());
+    if let Some(summary) = &changelog.summary {
+        out.push_str(summary.trim());
+        out.push_str("\n\n");
+    }
+    if changelog.groups.is_empty() {
+        out.push_str("No changes.\n");
+        return out;
+    }
+
+    let breaking: Vec<&Entry> = changelog.breaking().collect();
+    if !breaking.is_empty() {
+        out.push_str("### Breaking Changes\n\n");
+        for entry in breaking {
+            out.push_str(&render_entry(entry));
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    // Breaking entries are listed once, above, rather than again by type.
+    for group in &changelog.groups {
+        let entries: Vec<&Entry> = group.commits.iter().filter(|e| !e.breaking).collect();
+        if entries.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("### {}\n\n", group.title));
+        for entry in entries {
+            out.push_str(&render_entry(entry));
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// Ask the commit provider for a summary of the grouped changelog.
+fn summarize(changelog: &Changelog) -> Result<String> {
+    let config = config::load()?;
+    let provider =
+        Provider::for_feature(&config.ai, Feature::Commit).map_err(ChangelogError::AiError)?;
+    let template = prompts::load(Template::Changelog)?;
+    let commits = render_markdown(changelog);
+    let range = changelog.range_label();
+    let prompt = prompts::render(&template, &[("range", &range), ("commits", &commits)]);
+    let input = (!prompts::mentions(&template, "commits")).then_some(commits);
+
+    eprintln!("Asking {provider} for a summary…");
+    provider
+        .run_capturing(&config.ai.model, &prompt, input.as_deref())
+        .map_err(|e| ChangelogError::AiError(e.to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(n: u8, message: &str) -> RangeCommit {
+        RangeCommit {
+            oid: git2::Oid::from_bytes(&[n; 20]).unwrap(),
+            short_id: format!("{n:07}"),
+            summary: message.lines().next().unwrap_or("").to_string(),
+            message: message.to_string(),
+            author_name: "Ada".to_string(),
+            is_merge: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            parse_range("v1.0..v1.1").unwrap(),
+            (Some("v1.0".to_string()), "v1.1".to_string())
+        );
+        assert_eq!(
+            parse_range("v1.0").unwrap(),
+            (Some("v1.0".to_string()), "HEAD".to_string())
+        );
+        assert_eq!(
+            parse_range("v1.0..").unwrap(),
+            (Some("v1.0".to_string()), "HEAD".to_string())
+        );
+        assert!(parse_range("a...b").is_err());
+        assert!(parse_range(" ").is_err());
+    }
+
+    #[test]
+    fn test_build_groups_by_type_in_section_order() {
+        let commits = [
+            commit(1, "chore: bump deps"),
+            commit(2, "fix(api): handle empty body (#42)"),
+            commit(3, "Update README"),
+            commit(4, "feat!: drop v1 endpoints\n\nCloses #7"),
+            commit(5, "wip: odd type"),
+        ];
+        let log = build(
+            Some("v1".to_string()),
+            "HEAD".to_string(),
+            &commits,
+            Some("https://github.com/o/r/pull"),
+        );
+        let order: Vec<&str> = log.groups.iter().map(|g| g.ty.as_str()).collect();
+        assert_eq!(order, ["feat", "fix", "chore", "other"]);
+        assert_eq!(log.groups[3].commits.len(), 2);
+
+        let fix = &log.groups[1].commits[0];
+        assert_eq!(fix.description, "handle empty body");
+        assert_eq!(fix.scope.as_deref(), Some("api"));
+        assert_eq!(
+            fix.pull_requests,
+            vec![PullRef {
+                number: 42,
+                url: Some("https://github.com/o/r/pull/42".to_string()),
+            }]
+        );
+
+        let feat = &log.groups[0].commits[0];
+        assert!(feat.breaking);
+        assert_eq!(feat.pull_requests[0].number, 7);
+    }
+
+    #[test]
+    fn test_build_skips_merges_and_detects_breaking_footer() {
+        let mut merge = commit(1, "Merge pull request #9 from o/feature");
+        merge.is_merge = true;
+        let commits = [
+            merge,
+            commit(2, "refactor: move config\n\nBREAKING CHANGE: new path"),
+        ];
+        let log = build(None, "HEAD".to_string(), &commits, None);
+        assert_eq!(log.groups.len(), 1);
+        assert!(log.groups[0].commits[0].breaking);
+        assert!(log.groups[0].commits[0].pull_requests.is_empty());
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let commits = [
+            commit(1, "fix(api): handle empty body (#42)"),
+            commit(2, "feat!: drop v1"),
+            commit(3, "feat: add v2"),
+        ];
+        let mut log = build(
+            Some("v1".to_string()),
+            "v2".to_string(),
+            &commits,
+            Some("https://github.com/o/r/pull"),
+        );
+        log.summary = Some("Faster and leaner.\n".to_string());
+        assert_eq!(
+            render_markdown(&log),
+            "## v1..v2\n\n\
+             Faster and leaner.\n\n\
+             ### Breaking Changes\n\n\
+             - drop v1 (`0000002`)\n\n\
+             ### Features\n\n\
+             - add v2 (`0000003`)\n\n\
+             ### Bug Fixes\n\n\
+             - **api:** handle empty body ([#42](https://github.com/o/r/pull/42)) (`0000001`)\n"
+        );
+
+        let empty = build(None, "HEAD".to_string(), &[], None);
+        assert_eq!(render_markdown(&empty), "## HEAD\n\nNo changes.\n");
+    }
+}
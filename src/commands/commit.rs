@@ -1,30 +1,16 @@
 use crate::ai;
+use crate::ai::prompts::{self, Template};
+use crate::commands::workspace::main_worktree_root;
+use crate::commit_lint::{self, CommitRules, Violation};
 use crate::config;
 use crate::git;
 use crate::git::GitError;
 use crate::git::commit::CommitOptions;
+use crate::secret_scan;
 use crate::ui;
 use miette::{Diagnostic, Result};
 use thiserror::Error;
 
-const COMMIT_MESSAGE_PROMPT: &str = r#"Analyze this git diff and generate a conventional commit message following these rules:
-
-- feat: NEW functionality or feature added
-- fix: BUG fixes or corrections
-- refactor: code restructuring WITHOUT behavior changes
-- docs: documentation changes ONLY
-- style: formatting, whitespace, missing semicolons (no code logic change)
-- test: adding or updating tests
-- perf: performance improvements
-- chore: dependency updates, build config, tooling
-- ci: CI/CD pipeline changes
-- build: build system or external dependency changes
-
-Carefully analyze what the diff actually does. Most changes are NOT features.
-
-Output format: <type>: <imperative description>
-Output ONLY the commit message, nothing else."#;
-
 #[derive(Error, Debug, Diagnostic)]
 pub enum CommitError {
     #[error("Git error: {0}")]
@@ -55,9 +41,30 @@ pub enum CommitError {
         help("Ensure the configured AI agent is installed and available in your PATH")
     )]
     AiError(String),
+
+    #[error("Commit message violates the commit rules")]
+    #[diagnostic(
+        code(gx::commit::lint_failed),
+        help("Fix the message, or pass --no-lint to commit it anyway")
+    )]
+    LintFailed,
+
+    #[error("{failing} of {total} commits violate the commit rules")]
+    #[diagnostic(
+        code(gx::commit::lint_range_failed),
+        help("Reword them with 'git rebase -i'")
+    )]
+    LintRangeFailed { failing: usize, total: usize },
 }
 
-pub fn run(message: Option<String>, amend: bool, no_edit: bool, ai: bool) -> Result<()> {
+pub fn run(
+    message: Option<String>,
+    amend: bool,
+    no_edit: bool,
+    ai: bool,
+    no_lint: bool,
+    no_verify_secrets: bool,
+) -> Result<()> {
     if no_edit && !amend {
         return Err(CommitError::NoEditWithoutAmend.into());
     }
@@ -82,8 +89,40 @@ pub fn run(message: Option<String>, amend: bool, no_edit: bool, ai: bool) -> Res
         }
     }
 
+    if !no_verify_secrets && config::load()?.scan.enabled {
+        let staged = git::additions::staged().map_err(CommitError::GitError)?;
+        secret_scan::check(&staged)?;
+    }
+
+    let rules = load_rules()?;
+    let lint = rules.lint && !no_lint;
+
     if ai {
-        return run_ai_commit(amend);
+        return run_ai_commit(amend, &rules, lint);
+    }
+
+    // gx has to own the message when it lints it, or when a template seeds the
+    // editor for a fresh commit; otherwise git handles it exactly as before.
+    let seeds_template = rules.template.is_some() && message.is_none() && !amend;
+    if lint || seeds_template {
+        let message = match message {
+            Some(message) => message,
+            None if no_edit => git::commit::head_message().map_err(CommitError::GitError)?,
+            None => {
+                let initial = if amend {
+                    git::commit::head_message().map_err(CommitError::GitError)?
+                } else {
+                    rules.template.clone().unwrap_or_default()
+                };
+                edit(&initial)?
+            }
+        };
+        let message = if lint {
+            lint_until_clean(message, &rules)?
+        } else {
+            message
+        };
+        return commit_with_message(&message, amend);
     }
 
     let options = CommitOptions {
@@ -97,7 +136,7 @@ pub fn run(message: Option<String>, amend: bool, no_edit: bool, ai: bool) -> Res
     Ok(())
 }
 
-fn run_ai_commit(amend: bool) -> Result<()> {
+fn run_ai_commit(amend: bool, rules: &CommitRules, lint: bool) -> Result<()> {
     // On --amend the staging step is skipped, so the index matches HEAD and the
     // staged diff would be empty; diff against HEAD's parent instead so the AI
     // sees the content of the commit being amended.
@@ -113,15 +152,32 @@ fn run_ai_commit(amend: bool) -> Result<()> {
     }
 
     let config = config::load()?;
-    let agent = config.ai.get_agent().map_err(CommitError::AiError)?;
+    let provider =
+        ai::Provider::for_feature(&config.ai, ai::Feature::Commit).map_err(CommitError::AiError)?;
     let model = &config.ai.model;
+    let diff = ai::diff::prepare(&diff, &ai::diff::DiffBudget::from_config(&config.ai));
 
-    let ai_message = ai::run_capturing(&agent, model, COMMIT_MESSAGE_PROMPT, Some(&diff))
+    let (prompt, input) = commit_prompt(rules, &diff)?;
+    let ai_message = provider
+        .run_capturing(model, &prompt, input.as_deref())
         .map_err(|e| CommitError::AiError(e.to_string()))?;
 
     println!("AI generated commit message:\n");
     println!("  {}\n", ai_message);
 
+    if lint {
+        // A violating suggestion goes straight to the re-edit loop; a clean one
+        // can still be rejected and rewritten, then is linted again.
+        let message = if !commit_lint::lint(&ai_message, rules).is_empty() {
+            lint_until_clean(ai_message, rules)?
+        } else if ui::confirm::run("Use this commit message?")? {
+            ai_message
+        } else {
+            lint_until_clean(edit(&ai_message)?, rules)?
+        };
+        return commit_with_message(&message, amend);
+    }
+
     let confirmed = ui::confirm::run("Use this commit message?")?;
 
     if confirmed {
@@ -138,3 +194,115 @@ fn run_ai_commit(amend: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// `gx commit lint [<range>]`: check every non-merge commit in `range` (a
+/// single commit or `A..B`, default `HEAD`) against the repo's commit rules,
+/// regardless of whether `gx commit` enforces them. Fails when any commit
+/// violates a rule, so CI can run it directly.
+pub fn run_lint(range: Option<String>) -> Result<()> {
+    let rules = load_rules()?;
+    let range = range.unwrap_or_else(|| "HEAD".to_string());
+    let commits = git::log::commit_messages(&range).map_err(CommitError::GitError)?;
+
+    let mut failing = 0;
+    for commit in &commits {
+        let violations = commit_lint::lint(&commit.message, &rules);
+        if violations.is_empty() {
+            continue;
+        }
+        failing += 1;
+        let subject = commit.message.lines().next().unwrap_or("");
+        println!("{} {}", commit.short_id, subject);
+        for violation in &violations {
+            println!("  - {violation}");
+        }
+    }
+
+    if failing > 0 {
+        return Err(CommitError::LintRangeFailed {
+            failing,
+            total: commits.len(),
+        }
+        .into());
+    }
+
+    eprintln!("{} commit(s) pass the commit rules", commits.len());
+    Ok(())
+}
+
+/// Render the `commit` prompt template. The diff is inlined when the template
+/// asks for `{diff}`, and otherwise returned to be piped as the agent's input.
+fn commit_prompt(rules: &CommitRules, diff: &str) -> Result<(String, Option<String>)> {
+    let template = prompts::load(Template::Commit)?;
+
+    let rules = commit_lint::prompt_rules(rules);
+    let files = ai::diff::changed_files(diff).join("\n");
+    let branch = git::branch::get_current_branch()
+        .map(|b| b.name)
+        .unwrap_or_default();
+    let prompt = prompts::render(
+        &template,
+        &[
+            ("rules", &rules),
+            ("diff", diff),
+            ("files", &files),
+            ("branch", &branch),
+        ],
+    );
+    let input = (!prompts::mentions(&template, "diff")).then(|| diff.to_string());
+    Ok((prompt, input))
+}
+
+pub(super) fn load_rules() -> Result<CommitRules> {
+    let worktrees = git::worktree::list().map_err(CommitError::GitError)?;
+    let main_root = main_worktree_root(&worktrees)?;
+    commit_lint::resolve_for_repo(&main_root)
+}
+
+/// Open the editor on `initial`; an emptied message aborts, as in git.
+fn edit(initial: &str) -> Result<String> {
+    let message = git::commit::edit_message(initial).map_err(CommitError::GitError)?;
+    if message.is_empty() {
+        return Err(CommitError::Aborted.into());
+    }
+    Ok(message)
+}
+
+/// Re-lint `message` until it is clean, offering the editor (with the
+/// violations as comment lines) after each failed pass. Declining fails with
+/// [`CommitError::LintFailed`].
+pub(super) fn lint_until_clean(mut message: String, rules: &CommitRules) -> Result<String> {
+    loop {
+        let violations = commit_lint::lint(&message, rules);
+        if violations.is_empty() {
+            return Ok(message);
+        }
+
+        eprintln!("Commit message violates the commit rules:");
+        for violation in &violations {
+            eprintln!("  - {violation}");
+        }
+        if !ui::confirm::run("Edit the message?")? {
+            return Err(CommitError::LintFailed.into());
+        }
+        message = edit(&with_violation_comments(&message, &violations))?;
+    }
+}
+
+fn with_violation_comments(message: &str, violations: &[Violation]) -> String {
+    let mut text = format!("{message}\n\n# gx commit lint:\n");
+    for violation in violations {
+        text.push_str(&format!("#   - {violation}\n"));
+    }
+    text
+}
+
+pub(super) fn commit_with_message(message: &str, amend: bool) -> Result<()> {
+    let options = CommitOptions {
+        message: Some(message),
+        amend,
+        no_edit: false,
+    };
+    git::commit::create_commit(options).map_err(CommitError::GitError)?;
+    Ok(())
+}
@@ -0,0 +1,332 @@
+//! `gx commit --ai-split`: ask the configured agent to group every uncommitted
+//! hunk into a series of commits, let the user adjust the plan in a TUI, then
+//! create the commits in order.
+//!
+//! The agent's reply is untrusted: it is parsed and validated as a whole
+//! (unknown or duplicated hunk ids, empty commits or messages) before anything
+//! is staged, and a bad reply fails without touching the index.
+
+use super::commit::{CommitError, commit_with_message, lint_until_clean, load_rules};
+use crate::ai::diff::DiffBudget;
+use crate::ai::prompts::{self, Template};
+use crate::ai::{Feature, Provider};
+use crate::commit_lint::{self, CommitRules};
+use crate::config;
+use crate::git;
+use crate::git::split::{PlannedCommit, SplitPlan, WorkingChanges};
+use crate::output;
+use crate::secret_scan;
+use crate::ui;
+use miette::{Diagnostic, IntoDiagnostic, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum SplitError {
+    #[error("The agent's split plan was rejected: {0}")]
+    #[diagnostic(
+        code(gx::commit::invalid_split_plan),
+        help("Nothing was staged or committed. Run it again, or commit by hand")
+    )]
+    InvalidPlan(String),
+
+    #[error("Split stopped after {done} of {total} commit(s): {message}")]
+    #[diagnostic(
+        code(gx::commit::split_interrupted),
+        help(
+            "The remaining changes are still in the working tree; the next group is staged. \
+             Fix the problem and commit the rest with 'gx commit'"
+        )
+    )]
+    Interrupted {
+        done: usize,
+        total: usize,
+        message: String,
+    },
+}
+
+pub fn run(no_lint: bool, no_verify_secrets: bool) -> Result<()> {
+    let changes = WorkingChanges::load().map_err(CommitError::GitError)?;
+    if changes.is_empty() {
+        return Err(CommitError::NothingToCommit.into());
+    }
+
+    let config = config::load()?;
+    if !no_verify_secrets && config.scan.enabled {
+        let uncommitted = git::additions::uncommitted().map_err(CommitError::GitError)?;
+        secret_scan::check(&uncommitted)?;
+    }
+
+    let rules = load_rules()?;
+    let lint = rules.lint && !no_lint;
+    let provider =
+        Provider::for_feature(&config.ai, Feature::Commit).map_err(CommitError::AiError)?;
+
+    let (prompt, input) = split_prompt(&changes, &rules, &DiffBudget::from_config(&config.ai))?;
+    eprintln!(
+        "Asking {provider} to split {} hunk(s)…",
+        changes.hunks.len()
+    );
+    let hunks = changes.hunks.len();
+    let reply = provider
+        .run_capturing_if(&config.ai.model, &prompt, input.as_deref(), |reply| {
+            parse_plan(reply, hunks).is_ok()
+        })
+        .map_err(|e| CommitError::AiError(e.to_string()))?;
+    let plan = parse_plan(&reply, hunks).map_err(SplitError::InvalidPlan)?;
+
+    let plan = ui::terminal::with_terminal(|t| ui::split_plan::run(t, &changes, plan))
+        .into_diagnostic()??;
+    let Some(mut plan) = plan else {
+        output::cancelled();
+        return Ok(());
+    };
+    plan.commits.retain(|c| !c.hunks.is_empty());
+    if plan.commits.is_empty() {
+        output::cancelled();
+        return Ok(());
+    }
+
+    if lint {
+        for commit in &mut plan.commits {
+            commit.message = lint_until_clean(std::mem::take(&mut commit.message), &rules)?;
+        }
+    }
+
+    create_commits(&changes, &plan.commits)?;
+    if !plan.unassigned.is_empty() {
+        eprintln!(
+            "{} hunk(s) left uncommitted in the working tree",
+            plan.unassigned.len()
+        );
+    }
+    Ok(())
+}
+
+/// Stage and commit each planned group in order, starting from an index reset
+/// to `HEAD` so nothing previously staged leaks into the first commit.
+fn create_commits(changes: &WorkingChanges, commits: &[PlannedCommit]) -> Result<()> {
+    git::split::reset_index().map_err(CommitError::GitError)?;
+
+    let total = commits.len();
+    let mut applied = HashSet::new();
+    for (done, commit) in commits.iter().enumerate() {
+        applied.extend(commit.hunks.iter().copied());
+        let result = changes
+            .stage(&applied, &commit.hunks)
+            .map_err(|e| e.to_string())
+            .and_then(|()| commit_with_message(&commit.message, false).map_err(|e| e.to_string()));
+        if let Err(message) = result {
+            return Err(SplitError::Interrupted {
+                done,
+                total,
+                message,
+            }
+            .into());
+        }
+    }
+    eprintln!("Created {total} commit(s)");
+    Ok(())
+}
+
+/// Render the `split` template. The hunk listing is inlined for `{hunks}`, and
+/// otherwise returned to be piped as the agent's input.
+fn split_prompt(
+    changes: &WorkingChanges,
+    rules: &CommitRules,
+    budget: &DiffBudget,
+) -> Result<(String, Option<String>)> {
+    let template = prompts::load(Template::Split)?;
+
+    let rules = commit_lint::prompt_rules(rules);
+    let hunks = hunk_listing(changes, budget);
+    let files = changes
+        .files
+        .iter()
+        .map(|f| f.diff.path.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let branch = git::branch::get_current_branch()
+        .map(|b| b.name)
+        .unwrap_or_default();
+    let prompt = prompts::render(
+        &template,
+        &[
+            ("rules", &rules),
+            ("hunks", &hunks),
+            ("files", &files),
+            ("branch", &branch),
+        ],
+    );
+    let input = (!prompts::mentions(&template, "hunks")).then_some(hunks);
+    Ok((prompt, input))
+}
+
+/// Every hunk as `[hN] <label> (+a -r)` followed by its body. Bodies longer
+/// than the hunk-line cap are cut short, and once the byte budget is spent the
+/// remaining hunks are listed by header only — every id must still be visible
+/// for the plan to cover it.
+fn hunk_listing(changes: &WorkingChanges, budget: &DiffBudget) -> String {
+    let mut out = String::new();
+    for id in 0..changes.hunks.len() {
+        let (added, removed) = changes.stats(id);
+        out.push_str(&format!(
+            "[h{}] {} (+{added} -{removed})\n",
+            id + 1,
+            changes.label(id)
+        ));
+        if out.len() >= budget.max_bytes {
+            continue;
+        }
+
+        let lines = changes.patch_lines(id);
+        for line in lines.iter().take(budget.max_hunk_lines) {
+            out.push_str(line);
+            out.push('\n');
+        }
+        if lines.len() > budget.max_hunk_lines {
+            out.push_str(&format!(
+                "… {} more line(s)\n",
+                lines.len() - budget.max_hunk_lines
+            ));
+        }
+        out.push('\n');
+    }
+    if out.len() >= budget.max_bytes {
+        out.push_str("(Later hunk bodies omitted to fit the prompt budget.)\n");
+    }
+    out
+}
+
+/// Parse and validate the agent's reply against `hunk_count` hunks (ids
+/// `h1..=hN` in the prompt, 0-based in the plan). The JSON object may be
+/// wrapped in prose or a code fence. Hunks no commit claims are returned as
+/// unassigned rather than rejected; every other problem rejects the plan.
+fn parse_plan(reply: &str, hunk_count: usize) -> Result<SplitPlan, String> {
+    let json = match (reply.find('{'), reply.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => return Err("the reply contains no JSON object".to_string()),
+    };
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| format!("the reply is not valid JSON: {e}"))?;
+    let Some(raw_commits) = value["commits"].as_array().filter(|c| !c.is_empty()) else {
+        return Err("the reply has no \"commits\" list".to_string());
+    };
+
+    let mut problems = Vec::new();
+    let mut owner: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut commits = Vec::new();
+    for (n, raw) in raw_commits.iter().enumerate() {
+        let number = n + 1;
+        let message = raw["message"].as_str().unwrap_or("").trim().to_string();
+        if message.is_empty() {
+            problems.push(format!("commit {number} has no message"));
+        }
+
+        let mut hunks = Vec::new();
+        for id in raw["hunks"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+        {
+            let Some(id) = parse_hunk_id(id).filter(|id| (1..=hunk_count).contains(id)) else {
+                problems.push(format!("commit {number} names unknown hunk {id}"));
+                continue;
+            };
+            match owner.insert(id, number) {
+                Some(other) if other != number => {
+                    problems.push(format!("hunk h{id} is in commits {other} and {number}"))
+                }
+                Some(_) => {}
+                None => hunks.push(id - 1),
+            }
+        }
+        if hunks.is_empty() {
+            problems.push(format!("commit {number} has no hunks"));
+        }
+        hunks.sort_unstable();
+        commits.push(PlannedCommit { message, hunks });
+    }
+
+    if !problems.is_empty() {
+        return Err(problems.join("; "));
+    }
+    let unassigned = (1..=hunk_count)
+        .filter(|id| !owner.contains_key(id))
+        .map(|id| id - 1)
+        .collect();
+    Ok(SplitPlan {
+        commits,
+        unassigned,
+    })
+}
+
+/// `"h3"`, `"3"`, or `3` → 3.
+fn parse_hunk_id(value: &Value) -> Option<usize> {
+    match value {
+        Value::Number(n) => n.as_u64().map(|n| n as usize),
+        Value::String(s) => {
+            let s = s.trim();
+            s.strip_prefix(['h', 'H']).unwrap_or(s).parse().ok()
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plan_accepts_fenced_json_and_id_forms() {
+        let reply = "Here you go:\n```json\n{\"commits\": [\
+            {\"message\": \"feat: add login\", \"hunks\": [\"h1\", 3]},\
+            {\"message\": \"docs: mention login\", \"hunks\": [\"2\"]}]}\n```";
+        let plan = parse_plan(reply, 4).unwrap();
+        assert_eq!(
+            plan.commits,
+            vec![
+                PlannedCommit {
+                    message: "feat: add login".to_string(),
+                    hunks: vec![0, 2],
+                },
+                PlannedCommit {
+                    message: "docs: mention login".to_string(),
+                    hunks: vec![1],
+                },
+            ]
+        );
+        // h4 was not claimed: it stays uncommitted instead of failing the plan.
+        assert_eq!(plan.unassigned, vec![3]);
+    }
+
+    #[test]
+    fn test_parse_plan_rejects_overlap_and_unknown_ids() {
+        let reply = r#"{"commits": [
+            {"message": "a", "hunks": ["h1", "h2"]},
+            {"message": "b", "hunks": ["h2", "h9", "x"]}
+        ]}"#;
+        let err = parse_plan(reply, 3).unwrap_err();
+        assert!(err.contains("hunk h2 is in commits 1 and 2"), "{err}");
+        assert!(err.contains("unknown hunk \"h9\""), "{err}");
+        assert!(err.contains("unknown hunk \"x\""), "{err}");
+    }
+
+    #[test]
+    fn test_parse_plan_rejects_empty_commits_and_messages() {
+        let err = parse_plan(r#"{"commits": [{"message": " ", "hunks": []}]}"#, 1).unwrap_err();
+        assert!(err.contains("commit 1 has no message"), "{err}");
+        assert!(err.contains("commit 1 has no hunks"), "{err}");
+
+        assert!(parse_plan("no json here", 1).is_err());
+        assert!(parse_plan(r#"{"commits": []}"#, 1).is_err());
+        assert!(parse_plan(r#"{"commits": [oops]}"#, 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_plan_zero_id_is_unknown() {
+        let err = parse_plan(r#"{"commits": [{"message": "a", "hunks": [0]}]}"#, 1).unwrap_err();
+        assert!(err.contains("unknown hunk 0"), "{err}");
+    }
+}
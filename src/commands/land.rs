@@ -0,0 +1,405 @@
+//! `gx land`: finish an approved branch end to end. Rebases its workspace
+//! onto the default branch, runs the configured test command, pushes with
+//! lease, merges the pull request with `[pr] merge_method`, then removes the
+//! workspace and deletes the branch. Stops at the first failing step and
+//! prints the `--from` invocation that resumes there.
+
+use crate::args::LandStep;
+use crate::commands::workspace::{
+    WorkspaceError, load_worktrees, main_worktree_root, remove_worktrees, resolve_target,
+    update_worktrees,
+};
+use crate::commands::{push, scan, workspace_clean};
+use crate::config::{self, Config};
+use crate::git::pr_actions::{self, MergeMethod};
+use crate::git::pull_request::{self, PullRequestState};
+use crate::git::push::PushOptions;
+use crate::git::worktree::Worktree;
+use crate::git::{self, GitError, github};
+use crate::repo_config::{self, HookVars};
+use crate::{repo_setup, secret_scan};
+use miette::{Diagnostic, IntoDiagnostic, Result, miette};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum LandError {
+    #[error("Git error: {0}")]
+    #[diagnostic(code(gx::land::git_error), help("Are you in a git repository?"))]
+    GitError(#[from] GitError),
+
+    #[error("No workspace has '{0}' checked out")]
+    #[diagnostic(
+        code(gx::land::no_workspace),
+        help("Create one with 'gx workspace new {0}', or land from the workspace itself")
+    )]
+    NoWorkspace(String),
+
+    #[error("Refusing to land '{0}', the default branch")]
+    #[diagnostic(
+        code(gx::land::default_branch),
+        help("Land the feature branch it is merged from instead")
+    )]
+    DefaultBranch(String),
+
+    #[error("Refusing to land '{0}' from the main worktree")]
+    #[diagnostic(
+        code(gx::land::main_worktree),
+        help("Land '{0}' from a workspace of its own, with another branch checked out here")
+    )]
+    MainWorktree(String),
+
+    #[error("Push to protected branch '{0}' cancelled")]
+    #[diagnostic(code(gx::land::push_cancelled))]
+    PushCancelled(String),
+
+    #[error("'origin' does not point at GitHub; cannot merge a pull request")]
+    #[diagnostic(code(gx::land::no_github))]
+    NoGitHub,
+
+    #[error("No pull request found for '{0}'")]
+    #[diagnostic(
+        code(gx::land::no_pull_request),
+        help("Open one with 'gh pr create', then resume with --from merge")
+    )]
+    NoPullRequest(String),
+
+    #[error("Pull request #{number} is {state}")]
+    #[diagnostic(code(gx::land::pull_request_state), help("{help}"))]
+    PullRequestState {
+        number: usize,
+        state: &'static str,
+        help: &'static str,
+    },
+
+    #[error("Landing stopped at the {step} step: {message}")]
+    #[diagnostic(
+        code(gx::land::step_failed),
+        help("Fix the problem, then resume with '{resume}'")
+    )]
+    StepFailed {
+        step: &'static str,
+        message: String,
+        resume: String,
+    },
+}
+
+/// Everything the steps need, resolved once up front.
+struct Landing {
+    worktree: Worktree,
+    all_worktrees: Vec<Worktree>,
+    branch: String,
+    main_root: PathBuf,
+    /// Pull request number when the target was given as one.
+    pr_number: Option<usize>,
+    test_command: Option<String>,
+    merge_method: MergeMethod,
+    /// Push to a protected branch without asking.
+    yes: bool,
+}
+
+pub fn run(target: Option<String>, from: LandStep, dry_run: bool, yes: bool) -> Result<()> {
+    let cfg = config::load()?;
+    let all_worktrees = load_worktrees()?;
+    let (worktree, pr_number) = resolve(target.as_deref(), &all_worktrees)?;
+    let branch = worktree
+        .branch
+        .clone()
+        .ok_or_else(|| WorkspaceError::DetachedHead(worktree.name.clone()))?;
+    // Landing removes the workspace and merges the branch away, which makes
+    // no sense for the main checkout or the branch everything lands on.
+    if worktree.is_main {
+        return Err(LandError::MainWorktree(branch).into());
+    }
+    let default_branch = git::branch::default_remote_branch().map_err(LandError::GitError)?;
+    if default_branch
+        .as_deref()
+        .is_some_and(|remote| workspace_clean::strip_remote_prefix(remote) == branch)
+    {
+        return Err(LandError::DefaultBranch(branch).into());
+    }
+    let main_root = main_worktree_root(&all_worktrees)?;
+    let landing = Landing {
+        test_command: test_command(&cfg, &main_root)?,
+        merge_method: MergeMethod::from_str(&cfg.pr.merge_method).unwrap_or_default(),
+        worktree,
+        all_worktrees,
+        branch,
+        main_root,
+        pr_number,
+        yes,
+    };
+
+    // The push and merge act on the checked-out branch, so run from inside
+    // the workspace being landed rather than wherever the shell is.
+    std::env::set_current_dir(&landing.worktree.path).into_diagnostic()?;
+
+    let steps: Vec<LandStep> = LandStep::ALL.into_iter().filter(|s| *s >= from).collect();
+    if dry_run {
+        println!(
+            "Landing '{}' from workspace '{}':",
+            landing.branch, landing.worktree.name
+        );
+        for step in &steps {
+            println!("  {:<7} {}", step.as_str(), describe(*step, &landing));
+        }
+        return Ok(());
+    }
+
+    for step in steps {
+        eprintln!("==> {}: {}", step.as_str(), describe(step, &landing));
+        run_step(step, &landing, &cfg).map_err(|e| LandError::StepFailed {
+            step: step.as_str(),
+            message: e.to_string(),
+            resume: resume_command(target.as_deref(), step),
+        })?;
+    }
+    eprintln!("Landed '{}'", landing.branch);
+    Ok(())
+}
+
+/// The workspace to land: the current one, the one matching a query, or the
+/// one checked out on a pull request's (or GitHub branch URL's) branch.
+fn resolve(target: Option<&str>, worktrees: &[Worktree]) -> Result<(Worktree, Option<usize>)> {
+    let Some(query) = target else {
+        let current = worktrees
+            .iter()
+            .find(|w| w.is_current)
+            .cloned()
+            .ok_or(LandError::GitError(GitError::NotInRepo))?;
+        return Ok((current, None));
+    };
+    let Some(gh_ref) = github::parse_ref(query) else {
+        return Ok((resolve_target(query, worktrees)?, None));
+    };
+
+    let number = match gh_ref {
+        github::GitHubRef::Pull { number, .. } | github::GitHubRef::PullShort { number } => {
+            Some(number as usize)
+        }
+        github::GitHubRef::Tree { .. } => None,
+    };
+    let branch = github::resolve_branch(&gh_ref)?;
+    let worktree = worktrees
+        .iter()
+        .find(|w| w.branch.as_deref() == Some(branch.as_str()))
+        .cloned()
+        .ok_or(LandError::NoWorkspace(branch))?;
+    Ok((worktree, number))
+}
+
+/// The repo's `[land] test_command` (local override, then shared), else the
+/// global one. Blank means no test step.
+fn test_command(cfg: &Config, main_root: &Path) -> Result<Option<String>> {
+    let (shared, local) = repo_config::load_repo_layers(main_root)?;
+    let repo = [local, shared]
+        .into_iter()
+        .flatten()
+        .find_map(|layer| layer.land.and_then(|land| land.test_command));
+    let command = repo.unwrap_or_else(|| cfg.land.test_command.clone());
+    Ok((!command.trim().is_empty()).then_some(command))
+}
+
+/// One line saying what `step` will do, for the plan and the progress output.
+fn describe(step: LandStep, landing: &Landing) -> String {
+    let branch = &landing.branch;
+    match step {
+        LandStep::Update => {
+            let base = git::branch::default_remote_branch()
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| "the default branch".to_string());
+            format!("fetch and rebase '{branch}' onto {base}")
+        }
+        LandStep::Test => match &landing.test_command {
+            Some(command) => format!("run `{command}`"),
+            None => "skip (no [land] test_command configured)".to_string(),
+        },
+        LandStep::Push => format!("push '{branch}' with --force-with-lease"),
+        LandStep::Merge => {
+            let method = landing.merge_method.label();
+            match landing.pr_number {
+                Some(number) => format!("{method}-merge pull request #{number}"),
+                None => format!("{method}-merge the pull request for '{branch}'"),
+            }
+        }
+        LandStep::Remove => format!(
+            "remove workspace '{}' and delete '{branch}'",
+            landing.worktree.name
+        ),
+    }
+}
+
+fn run_step(step: LandStep, landing: &Landing, cfg: &Config) -> Result<()> {
+    match step {
+        LandStep::Update => update_worktrees(std::slice::from_ref(&landing.worktree), None),
+        LandStep::Test => match &landing.test_command {
+            Some(command) => run_tests(command, landing),
+            None => Ok(()),
+        },
+        LandStep::Push => push_branch(&landing.branch, cfg, landing.yes),
+        LandStep::Merge => merge(landing),
+        LandStep::Remove => {
+            // Leave the directory that is about to be deleted.
+            std::env::set_current_dir(&landing.main_root).into_diagnostic()?;
+            remove_worktrees(
+                std::slice::from_ref(&landing.worktree),
+                &landing.all_worktrees,
+                false,
+                true,
+                true,
+                &HashSet::new(),
+            )
+        }
+    }
+}
+
+/// Run the test command via `sh -c` in the workspace, with the same `GX_*`
+/// variables workspace hooks get. Its output goes to stderr.
+fn run_tests(command: &str, landing: &Landing) -> Result<()> {
+    let vars = HookVars {
+        workspace: landing.worktree.name.clone(),
+        workspace_path: landing.worktree.path.clone(),
+        main_root: landing.main_root.clone(),
+        branch: landing.branch.clone(),
+    };
+    let command = repo_config::expand_hook(command, &vars);
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(&command)
+        .current_dir(&landing.worktree.path)
+        .env("GX_WORKSPACE", &vars.workspace)
+        .env("GX_WORKSPACE_PATH", &vars.workspace_path)
+        .env("GX_MAIN_ROOT", &vars.main_root)
+        .env("GX_BRANCH", &vars.branch)
+        .stdin(Stdio::inherit())
+        .stdout(repo_setup::stderr_stdio()?)
+        .stderr(Stdio::inherit())
+        .status()
+        .into_diagnostic()?;
+    if !status.success() {
+        return Err(match status.code() {
+            Some(code) => miette!("`{command}` exited with status {code}"),
+            None => miette!("`{command}` was terminated by a signal"),
+        });
+    }
+    Ok(())
+}
+
+/// Push the rebased branch with lease (or with `-u` when it has no upstream
+/// yet), after the same protected-branch check and secret scan `gx push` runs.
+fn push_branch(branch: &str, cfg: &Config, yes: bool) -> Result<()> {
+    let upstream = git::push::upstream(branch).map_err(LandError::GitError)?;
+    let has_upstream = upstream.is_some();
+    let target = upstream.as_ref().map_or(branch, |u| &u.branch);
+    if push::protected_branches(cfg)?.is_protected(target)
+        && !push::confirm_protected(target, has_upstream, cfg.push.strict, yes)?
+    {
+        return Err(LandError::PushCancelled(target.to_string()).into());
+    }
+    if cfg.scan.enabled {
+        let outgoing = scan::outgoing_additions().map_err(LandError::GitError)?;
+        secret_scan::check(&outgoing)?;
+    }
+    let set_upstream = if has_upstream {
+        None
+    } else {
+        Some(
+            git::push::default_push_remote(branch)
+                .map_err(LandError::GitError)?
+                .ok_or_else(|| miette!("'{branch}' has no upstream and no remote to push to"))?,
+        )
+    };
+    git::push::push(PushOptions {
+        force: has_upstream,
+        force_dangerously: false,
+        set_upstream,
+    })
+    .map_err(LandError::GitError)?;
+    Ok(())
+}
+
+fn merge(landing: &Landing) -> Result<()> {
+    let (owner, repo) = github::origin_owner_repo()?.ok_or(LandError::NoGitHub)?;
+    let pr = pull_request::find_for_branch(&landing.branch)
+        .map_err(|_| miette!("could not look up pull requests with gh"))?
+        .filter(|pr| landing.pr_number.is_none_or(|n| n == pr.number))
+        .ok_or_else(|| LandError::NoPullRequest(landing.branch.clone()))?;
+
+    match pr.state {
+        PullRequestState::Open => {}
+        PullRequestState::Merged => {
+            eprintln!("Pull request #{} is already merged", pr.number);
+            return Ok(());
+        }
+        PullRequestState::Draft => {
+            return Err(LandError::PullRequestState {
+                number: pr.number,
+                state: "still a draft",
+                help: "Mark it ready for review with 'gh pr ready', then resume with --from merge",
+            }
+            .into());
+        }
+        PullRequestState::Closed => {
+            return Err(LandError::PullRequestState {
+                number: pr.number,
+                state: "closed",
+                help: "Reopen it on GitHub, then resume with --from merge",
+            }
+            .into());
+        }
+    }
+
+    pr_actions::merge(&owner, &repo, pr.number as u64, landing.merge_method)?;
+    eprintln!(
+        "Merged pull request #{} ({}): {}",
+        pr.number,
+        landing.merge_method.label(),
+        pr.url
+    );
+    Ok(())
+}
+
+/// The command that retries `failed` and everything after it.
+fn resume_command(target: Option<&str>, failed: LandStep) -> String {
+    let target = match target {
+        // Unquoted, `#123` would start a shell comment.
+        Some(t) if t.starts_with('#') => format!(" '{t}'"),
+        Some(t) => format!(" {t}"),
+        None => String::new(),
+    };
+    format!("gx land{target} --from {}", failed.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_command_quotes_pr_shorthand() {
+        assert_eq!(
+            resume_command(Some("#42"), LandStep::Merge),
+            "gx land '#42' --from merge"
+        );
+        assert_eq!(
+            resume_command(Some("feat-login"), LandStep::Test),
+            "gx land feat-login --from test"
+        );
+        assert_eq!(resume_command(None, LandStep::Push), "gx land --from push");
+    }
+
+    #[test]
+    fn test_steps_run_in_order() {
+        let from_push: Vec<_> = LandStep::ALL
+            .into_iter()
+            .filter(|s| *s >= LandStep::Push)
+            .collect();
+        assert_eq!(
+            from_push,
+            vec![LandStep::Push, LandStep::Merge, LandStep::Remove]
+        );
+    }
+}
@@ -27,7 +27,8 @@ pub fn run(limit: Option<usize>) -> Result<()> {
         return Ok(());
     }
 
-    let result = ui::terminal::with_terminal(|t| ui::log_viewer::run(t, &log))
+    let mut explainer = ui::explain::Explainer::load();
+    let result = ui::terminal::with_terminal(|t| ui::log_viewer::run(t, &log, &mut explainer))
         .map_err(|e| LogError::TuiError(e.to_string()))?;
 
     match result? {
@@ -1,14 +1,20 @@
 pub mod add;
+pub mod ai;
+pub mod changelog;
 pub mod checkout;
 pub mod commit;
+pub mod commit_split;
 pub mod external;
+pub mod land;
 pub mod log;
 pub mod onboarding;
 pub mod pr;
 pub mod push;
 pub mod review;
+pub mod scan;
 pub mod setup;
 pub mod stash;
 pub mod status;
+pub mod sync;
 pub mod workspace;
 pub mod workspace_clean;
@@ -120,6 +120,8 @@ fn save_shared(
             setup_script,
             ..Default::default()
         },
+        commit: None,
+        land: None,
     };
 
     let shared_path = gx_dir.join(repo_config::SHARED_FILE);
@@ -1,23 +1,316 @@
-use crate::git;
+use crate::commands::scan;
+use crate::commands::workspace::main_worktree_root;
+use crate::commands::workspace_clean::{self, ProtectionSet};
+use crate::config::{self, Config};
 use crate::git::GitError;
-use crate::git::push::PushOptions;
+use crate::git::pull_request::{self, PullRequestState};
+use crate::git::push::{PushOptions, Upstream};
+use crate::git::{self, gh};
+use crate::output;
+use crate::repo_config;
+use crate::secret_scan;
+use crate::{browser, clipboard, ui};
 use miette::{Diagnostic, Result};
+use std::io::IsTerminal;
 use thiserror::Error;
 
+/// How many discarded commits a force push lists before summarizing.
+const MAX_LISTED: usize = 10;
+
 #[derive(Error, Debug, Diagnostic)]
 pub enum PushError {
     #[error("Git error: {0}")]
     #[diagnostic(code(gx::push::git_error), help("Are you in a git repository?"))]
     GitError(#[from] GitError),
+
+    #[error("HEAD is detached; there is no branch to push")]
+    #[diagnostic(
+        code(gx::push::detached),
+        help("Create a branch first with 'gx checkout -b <name>'")
+    )]
+    Detached,
+
+    #[error("'{0}' has no upstream and no remote to push it to")]
+    #[diagnostic(
+        code(gx::push::no_remote),
+        help("Add one with 'git remote add origin <url>'")
+    )]
+    NoRemote(String),
+
+    #[error("Refusing to {action} protected branch '{branch}'")]
+    #[diagnostic(code(gx::push::protected), help("{help}"))]
+    Protected {
+        action: &'static str,
+        branch: String,
+        help: &'static str,
+    },
 }
 
-pub fn run(force: bool, force_dangerously: bool) -> Result<()> {
+pub fn run(force: bool, force_dangerously: bool, yes: bool, no_verify_secrets: bool) -> Result<()> {
+    let config = config::load()?;
+    let branch = git::branch::get_current_branch().map_err(PushError::GitError)?;
+    if branch.is_detached {
+        return Err(PushError::Detached.into());
+    }
+    let forcing = force || force_dangerously;
+
+    let upstream = git::push::upstream(&branch.name).map_err(PushError::GitError)?;
+    let set_upstream =
+        match git::branch::get_ahead_behind(&branch.name).map_err(PushError::GitError)? {
+            Some((ahead, behind)) => {
+                report_ahead_behind(ahead, behind, forcing);
+                None
+            }
+            None => {
+                let remote = git::push::default_push_remote(&branch.name)
+                    .map_err(PushError::GitError)?
+                    .ok_or_else(|| PushError::NoRemote(branch.name.clone()))?;
+                Some(remote)
+            }
+        };
+
+    if forcing && let Some(upstream) = &upstream {
+        report_force_effects(upstream, force_dangerously);
+    }
+
+    let target = upstream
+        .as_ref()
+        .map_or(branch.name.as_str(), |u| &u.branch);
+    if protected_branches(&config)?.is_protected(target)
+        && !confirm_protected(target, forcing, config.push.strict, yes)?
+    {
+        output::cancelled();
+        return Ok(());
+    }
+    if !no_verify_secrets && config.scan.enabled {
+        let outgoing = scan::outgoing_additions().map_err(PushError::GitError)?;
+        secret_scan::check(&outgoing)?;
+    }
+    if let Some(remote) = &set_upstream {
+        eprintln!(
+            "'{}' has no upstream; pushing with -u {remote} {}",
+            branch.name, branch.name
+        );
+    }
+
     let options = PushOptions {
         force,
         force_dangerously,
+        set_upstream,
+    };
+    let outcome = git::push::push(options).map_err(PushError::GitError)?;
+
+    report_pull_request(&branch.name, outcome.pr_hint.as_deref())
+}
+
+fn report_ahead_behind(ahead: usize, behind: usize, forcing: bool) {
+    match (ahead, behind) {
+        (0, 0) => eprintln!("Up to date with upstream"),
+        (ahead, 0) => eprintln!("{ahead} {} to push", commits(ahead)),
+        (ahead, behind) => {
+            eprintln!("{ahead} ahead, {behind} behind upstream");
+            if !forcing {
+                output::warn(format!(
+                    "upstream has {behind} {} you don't; the push will likely be rejected \
+                     (pull first, or --force to overwrite)",
+                    commits(behind)
+                ));
+            }
+        }
+    }
+}
+
+/// Before a force push: warn when the remote moved since the last fetch (the
+/// lease target is stale), and list the commits the push would remove from
+/// the remote branch.
+fn report_force_effects(upstream: &Upstream, dangerously: bool) {
+    let tracking = format!("{}/{}", upstream.remote, upstream.branch);
+    let tip = match git::push::remote_tip(&upstream.remote, &upstream.branch) {
+        Ok(tip) => tip,
+        Err(e) => {
+            output::warn(format!("could not reach {}: {e}", upstream.remote));
+            None
+        }
     };
 
-    git::push::push(options).map_err(PushError::GitError)?;
+    if let Some(tip) = &tip
+        && *tip != upstream.tracking_oid
+    {
+        output::warn(format!(
+            "{tracking} is stale: the remote is at {} but was last fetched at {}",
+            short(tip),
+            short(&upstream.tracking_oid)
+        ));
+        if dangerously {
+            output::warn("--force-dangerously will overwrite commits you have never fetched");
+        } else {
+            output::warn("the lease will reject this push; fetch and review the new commits first");
+        }
+    }
+
+    let remote_oid = tip.as_deref().unwrap_or(&upstream.tracking_oid);
+    match git::push::commits_lost_by_force(remote_oid) {
+        Ok(Some(lost)) if !lost.is_empty() => {
+            eprintln!(
+                "Force push discards {} {} from {tracking}:",
+                lost.len(),
+                commits(lost.len())
+            );
+            for line in lost.iter().take(MAX_LISTED) {
+                eprintln!("  {line}");
+            }
+            if lost.len() > MAX_LISTED {
+                eprintln!("  … and {} more", lost.len() - MAX_LISTED);
+            }
+        }
+        // Unfetched remote commits were already reported as a stale lease.
+        Ok(_) => {}
+        Err(e) => output::warn(format!(
+            "could not list the commits a force push discards: {e}"
+        )),
+    }
+}
+
+/// The branches `gx push` confirms (or, with `[push] strict`, refuses) pushes
+/// to: the global and `.gx` protected lists, the repo's default branch,
+/// `main` and `master`. Unlike cleanup, the current branch and branches
+/// checked out in worktrees are not protected here. A `.gx.toml` that fails to
+/// load is an error rather than a silently smaller set.
+pub(crate) fn protected_branches(cfg: &Config) -> Result<ProtectionSet> {
+    let worktrees = git::worktree::list().map_err(PushError::GitError)?;
+    let policy = repo_config::resolve_for_repo(&main_worktree_root(&worktrees)?)?;
+
+    let mut configured = cfg.workspace.protected_branches.clone();
+    configured.extend(policy.protected_branches);
+    let default_branch = policy.default_branch.or_else(|| {
+        git::branch::default_remote_branch()
+            .ok()
+            .flatten()
+            .map(|remote| workspace_clean::strip_remote_prefix(&remote).to_string())
+    });
+
+    Ok(workspace_clean::build_protection_set(
+        &configured,
+        default_branch.as_deref(),
+        None,
+        Vec::new(),
+    ))
+}
+
+/// Confirm a push to the protected `branch`. Refuses outright under `[push]
+/// strict`, or when there is no terminal to ask on and `--yes` was not given.
+pub(crate) fn confirm_protected(
+    branch: &str,
+    forcing: bool,
+    strict: bool,
+    yes: bool,
+) -> Result<bool> {
+    let action = if forcing { "force-push" } else { "push to" };
+    if strict {
+        return Err(PushError::Protected {
+            action,
+            branch: branch.to_string(),
+            help: "[push] strict is set; push through a pull request instead",
+        }
+        .into());
+    }
+    if yes {
+        return Ok(true);
+    }
+    if !(std::io::stdin().is_terminal() && std::io::stderr().is_terminal()) {
+        return Err(PushError::Protected {
+            action,
+            branch: branch.to_string(),
+            help: "Pass --yes to confirm without a terminal",
+        }
+        .into());
+    }
+    let message = if forcing {
+        format!("'{branch}' is protected. Force-push and rewrite its history?")
+    } else {
+        format!("'{branch}' is protected. Push to it anyway?")
+    };
+    ui::confirm::run_on_stderr(&message)
+}
+
+fn short(oid: &str) -> &str {
+    &oid[..oid.len().min(7)]
+}
+
+fn commits(n: usize) -> &'static str {
+    if n == 1 { "commit" } else { "commits" }
+}
+
+/// Print the branch's pull request, or the remote's "create one" link, and
+/// offer to open, copy or create it when attached to a terminal. A failed
+/// lookup (no `gh`, offline, not a GitHub repo) only drops the PR line and the
+/// create choice. Nothing is reported for the default branch.
+fn report_pull_request(branch: &str, hint: Option<&str>) -> Result<()> {
+    let default = git::branch::default_remote_branch().ok().flatten();
+    if default.is_some_and(|d| d.split_once('/').is_some_and(|(_, name)| name == branch)) {
+        return Ok(());
+    }
+
+    let interactive = std::io::stdin().is_terminal() && std::io::stderr().is_terminal();
+    let lookup = pull_request::find_for_branch(branch).map(|pr| {
+        pr.filter(|pr| matches!(pr.state, PullRequestState::Open | PullRequestState::Draft))
+    });
+
+    if let Ok(Some(pr)) = lookup {
+        println!("PR #{} ({}): {}", pr.number, pr.state.label(), pr.url);
+        if interactive {
+            offer(&pr.url, None)?;
+        }
+        return Ok(());
+    }
+
+    if let Some(url) = hint {
+        println!("Create a pull request: {url}");
+    }
+    let create_for = lookup.is_ok().then_some(branch);
+    if interactive && (hint.is_some() || create_for.is_some()) {
+        offer(hint.unwrap_or_default(), create_for)?;
+    }
+    Ok(())
+}
+
+/// Prompt for what to do with `url`. With `create_for`, there is no PR yet
+/// and `gh pr create` is offered for that branch; an empty `url` leaves out
+/// the open/copy choices.
+fn offer(url: &str, create_for: Option<&str>) -> Result<()> {
+    let mut options = Vec::new();
+    if create_for.is_some() {
+        options.push(('n', "ew PR"));
+    }
+    if !url.is_empty() {
+        options.extend([('o', "pen"), ('c', "opy")]);
+    }
+    let message = if create_for.is_some() {
+        "No pull request yet."
+    } else {
+        "Pull request:"
+    };
 
+    match ui::confirm::choose_on_stderr(message, &options)? {
+        Some('o') => {
+            if let Err(e) = browser::open(url) {
+                output::warn(format!("could not open a browser: {e}"));
+            }
+        }
+        Some('c') => match clipboard::copy(url) {
+            Ok(()) => eprintln!("Copied {url}"),
+            Err(e) => output::warn(format!("could not copy to the clipboard: {e}")),
+        },
+        Some('n') => {
+            let branch = create_for.unwrap_or_default();
+            if let Err(e) = gh::run_attached(&["pr", "create", "--head", branch]) {
+                output::warn(match e {
+                    gh::GhError::NotFound => "gh is not installed".to_string(),
+                    gh::GhError::Failed(message) => message,
+                });
+            }
+        }
+        _ => {}
+    }
     Ok(())
 }
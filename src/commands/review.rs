@@ -1,20 +1,93 @@
 //! `gx review` orchestration: resolve the diff range, enumerate the changed
-//! files, and launch the review TUI.
+//! files, and launch the review TUI. A pull-request target (`#123` or a PR
+//! URL) reviews that PR's diff instead of a local range.
+//!
+//! `gx review --output`, `gx review apply-suggestions`, and `gx review
+//! export`/`import` work on a saved review without the TUI: writing its agent
+//! prompt, applying its suggested changes to the working tree, and moving its
+//! comments in and out as JSON (or SARIF, rdjsonl, and Markdown) for scripts.
+//! Handing the review to the agent from the TUI launches it here, once the
+//! terminal is restored.
 
-use crate::config;
-use crate::git::review::{diff, range};
+use crate::ai;
+use crate::args::ReviewFormat;
+use crate::config::{self, Config};
+use crate::git::github::{self, GitHubRef};
+use crate::git::review::range::ReviewRange;
+use crate::git::review::state::{self, Comment, ReviewState, Suggestion};
+use crate::git::review::suggest::{self, Outcome, Text};
+use crate::git::review::{blob, diff, export, pull, range};
+use crate::git::{self, GitError};
+use crate::output;
 use crate::ui;
-use miette::Result;
+use miette::{Diagnostic, IntoDiagnostic, Result};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{IsTerminal, Read};
+use thiserror::Error;
 
-pub fn run(target: Option<String>, base: Option<String>) -> Result<()> {
+#[derive(Error, Debug, Diagnostic)]
+pub enum ReviewCommandError {
+    #[error("Git error: {0}")]
+    #[diagnostic(code(gx::review::git_error), help("Are you in a git repository?"))]
+    GitError(#[from] GitError),
+
+    #[error("No terminal to confirm each suggestion on")]
+    #[diagnostic(
+        code(gx::review::no_terminal),
+        help("Pass --yes to apply every suggestion")
+    )]
+    NoTerminal,
+
+    #[error("HEAD is {head}, but the review covers {tip}")]
+    #[diagnostic(
+        code(gx::review::head_mismatch),
+        help("Check out the reviewed commit (for a pull request, its head) before applying")
+    )]
+    HeadMismatch { head: String, tip: String },
+
+    #[error("AI error: {0}")]
+    #[diagnostic(
+        code(gx::review::ai),
+        help("Ensure the configured AI agent is installed and available in your PATH")
+    )]
+    Ai(String),
+
+    #[error("Could not read {path}: {source}")]
+    #[diagnostic(code(gx::review::read_failed))]
+    ReadFailed {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid review export: {0}")]
+    #[diagnostic(
+        code(gx::review::invalid_import),
+        help("Import reads the JSON written by `gx review export --format json`")
+    )]
+    InvalidImport(String),
+
+    #[error("Could not write {path}: {source}")]
+    #[diagnostic(code(gx::review::write_failed))]
+    WriteFailed {
+        path: String,
+        source: std::io::Error,
+    },
+}
+
+pub fn run(
+    target: Option<String>,
+    base: Option<String>,
+    staged: bool,
+    unstaged: bool,
+    output: Option<String>,
+) -> Result<()> {
     let cfg = config::load()?;
-    // Honor [review] default_mode when no explicit target/base is given.
-    let range = if target.is_none() && base.is_none() && cfg.review.default_mode == "uncommitted" {
-        range::resolve_uncommitted()?
-    } else {
-        range::resolve(target, base)?
-    };
+    let range = resolve_range(&cfg, target, base, staged, unstaged)?;
     let files = diff::changed_files(&range)?;
+    if let Some(output) = output {
+        return write_prompt(&range, &files, &output);
+    }
 
     // Detect the terminal appearance before the TUI takes over the terminal, and
     // pick a matching syntect theme when none is configured.
@@ -29,11 +102,315 @@ pub fn run(target: Option<String>, base: Option<String>) -> Result<()> {
         cfg.review.theme.clone()
     };
 
-    ui::review::run(
+    let handoff = ui::review::run(
         range,
         files,
         &theme,
         cfg.review.side_by_side_min_width,
         appearance,
-    )
+    )?;
+    match handoff {
+        Some(prompt) => hand_to_agent(&cfg, &prompt),
+        None => Ok(()),
+    }
+}
+
+/// Launch the configured agent on the review in the current worktree. The TUI
+/// is already torn down, so the agent can take over the terminal.
+fn hand_to_agent(cfg: &Config, prompt: &str) -> Result<()> {
+    let agent = cfg.ai.get_agent().map_err(ReviewCommandError::Ai)?;
+    let root = git::worktree::current_worktree_root().map_err(ReviewCommandError::from)?;
+    eprintln!("Launching {agent} in {}…", root.display());
+    let status = ai::launch_interactive(&agent, &cfg.ai.model, prompt, &root)
+        .map_err(|e| ReviewCommandError::Ai(e.to_string()))?;
+    if !status.success() {
+        match status.code() {
+            Some(code) => output::warn(format!("{agent} exited with status {code}")),
+            None => output::warn(format!("{agent} was terminated by a signal")),
+        }
+    }
+    Ok(())
+}
+
+/// Write the saved review's agent prompt — what `F` would copy — to `output`
+/// (`-` for stdout), re-anchoring its comments to the current diff first.
+fn write_prompt(range: &ReviewRange, files: &[diff::ChangedFile], output: &str) -> Result<()> {
+    let (review, diffs) = reanchored_review(range, files)?;
+    if review.total() == 0 && review.summary.is_empty() {
+        eprintln!("No saved review of {}", range.label);
+        return Ok(());
+    }
+
+    let blocks = blob::blocks(&review.comments, |file| diffs.get(file));
+    let prompt = blob::build(
+        &range.label,
+        &review.summary,
+        &review.file_comments,
+        &blocks,
+    );
+    if output == "-" {
+        print!("{prompt}");
+        return Ok(());
+    }
+    std::fs::write(output, prompt).map_err(|source| ReviewCommandError::WriteFailed {
+        path: output.to_string(),
+        source,
+    })?;
+    eprintln!(
+        "Wrote the review ({} comment(s)) to {output}",
+        review.total()
+    );
+    Ok(())
+}
+
+/// Print the saved review of the given range in `format`, re-anchoring its
+/// comments to the current diff first.
+pub fn run_export(
+    target: Option<String>,
+    base: Option<String>,
+    staged: bool,
+    unstaged: bool,
+    format: ReviewFormat,
+) -> Result<()> {
+    let cfg = config::load()?;
+    let range = resolve_range(&cfg, target, base, staged, unstaged)?;
+    let files = diff::changed_files(&range)?;
+    if format == ReviewFormat::Markdown {
+        return write_prompt(&range, &files, "-");
+    }
+
+    let (review, diffs) = reanchored_review(&range, &files)?;
+    let info = export::range_info(&range).map_err(ReviewCommandError::from)?;
+    let doc = export::document(info, &review, |file| diffs.get(file));
+    match format {
+        ReviewFormat::Json => println!("{}", serde_json::to_string_pretty(&doc).into_diagnostic()?),
+        ReviewFormat::Sarif => println!(
+            "{}",
+            serde_json::to_string_pretty(&export::sarif(&doc)).into_diagnostic()?
+        ),
+        ReviewFormat::Rdjsonl => print!("{}", export::rdjsonl(&doc)),
+        ReviewFormat::Markdown => unreachable!("handled above"),
+    }
+    Ok(())
+}
+
+/// Add the comments of an exported review (`file`, or stdin for `-`) to the
+/// saved review of the given range. They are re-anchored to the current diff
+/// like the saved ones, so comments from another range that no longer fit
+/// end up orphaned rather than misplaced.
+pub fn run_import(
+    file: String,
+    target: Option<String>,
+    base: Option<String>,
+    staged: bool,
+    unstaged: bool,
+) -> Result<()> {
+    let mut content = String::new();
+    let read = if file == "-" {
+        std::io::stdin().read_to_string(&mut content).map(|_| ())
+    } else {
+        std::fs::read_to_string(&file).map(|text| content = text)
+    };
+    read.map_err(|source| ReviewCommandError::ReadFailed {
+        path: file.clone(),
+        source,
+    })?;
+    let doc: export::Document = serde_json::from_str(&content)
+        .map_err(|e| ReviewCommandError::InvalidImport(e.to_string()))?;
+
+    let cfg = config::load()?;
+    let range = resolve_range(&cfg, target, base, staged, unstaged)?;
+    let files = diff::changed_files(&range)?;
+    let mut review = saved_review(&range)?;
+    let mut diffs = HashMap::new();
+    for f in &files {
+        if doc.comments.iter().any(|c| c.file == f.path) {
+            let diff = f.build(range.to).map_err(ReviewCommandError::from)?;
+            diffs.insert(f.path.clone(), diff);
+        }
+    }
+    let added = export::merge(doc, &mut review, |file| diffs.get(file))
+        .map_err(ReviewCommandError::InvalidImport)?;
+    let orphaned = review.orphaned.len();
+    for (path, diff) in &diffs {
+        review.reanchor_file(path, diff);
+    }
+    // A file outside the range has no diff to anchor to.
+    let (kept, outside): (Vec<Comment>, Vec<Comment>) = std::mem::take(&mut review.comments)
+        .into_iter()
+        .partition(|c| files.iter().any(|f| f.path == c.file));
+    review.comments = kept;
+    review.orphaned.extend(outside);
+
+    let dir = git::worktree::common_git_dir().map_err(ReviewCommandError::from)?;
+    let key = state::storage_key(&dir, &range.scope_id);
+    state::save(&key, &review).map_err(|source| ReviewCommandError::WriteFailed {
+        path: format!("the review of {}", range.label),
+        source,
+    })?;
+    eprintln!(
+        "Imported {added} comment(s) into the review of {}",
+        range.label
+    );
+    if review.orphaned.len() > orphaned {
+        output::warn(format!(
+            "{} comment(s) don't match the current diff and were orphaned",
+            review.orphaned.len() - orphaned
+        ));
+    }
+    Ok(())
+}
+
+/// Apply the suggested changes saved in the review of the given range to the
+/// working tree, asking before each one unless `yes`.
+pub fn run_apply_suggestions(
+    target: Option<String>,
+    base: Option<String>,
+    staged: bool,
+    unstaged: bool,
+    yes: bool,
+) -> Result<()> {
+    let cfg = config::load()?;
+    let range = resolve_range(&cfg, target, base, staged, unstaged)?;
+    let review = saved_review(&range)?;
+
+    // Per file, bottom-up, so applying one never shifts a later anchor.
+    let mut by_file: BTreeMap<&str, Vec<(&Comment, &Suggestion)>> = BTreeMap::new();
+    for c in &review.comments {
+        if let Some(suggestion) = &c.suggestion {
+            by_file.entry(&c.file).or_default().push((c, suggestion));
+        }
+    }
+    if by_file.is_empty() {
+        eprintln!("No suggested changes in the review of {}", range.label);
+        return Ok(());
+    }
+    if !(yes || (std::io::stdin().is_terminal() && std::io::stderr().is_terminal())) {
+        return Err(ReviewCommandError::NoTerminal.into());
+    }
+    // The suggestions are anchored to the reviewed commit; on any other
+    // checkout they'd land on lines the review never saw.
+    if let Some((head, tip)) = range::head_mismatch(&range)? {
+        if yes {
+            return Err(ReviewCommandError::HeadMismatch { head, tip }.into());
+        }
+        let question = format!("HEAD is {head}, but the review covers {tip}. Apply anyway?");
+        if !ui::confirm::run_on_stderr(&question)? {
+            return Ok(());
+        }
+    }
+
+    let workdir = git::worktree::current_worktree_root().map_err(ReviewCommandError::from)?;
+    let (mut applied, mut conflicts) = (0, 0);
+    for (file, mut suggestions) in by_file {
+        suggestions.sort_by_key(|(c, _)| Reverse(c.start_line));
+        let path = workdir.join(file);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            output::warn(format!(
+                "{file} is missing or unreadable; skipped its suggestions"
+            ));
+            conflicts += suggestions.len();
+            continue;
+        };
+        let mut text = Text::new(&content);
+        let mut changed = false;
+        for (c, suggestion) in suggestions {
+            let place = format!("{file}:{}", c.start_line);
+            if !yes {
+                eprintln!(
+                    "\n{}",
+                    suggest::patch(file, c.start_line, suggestion).trim_end()
+                );
+                if !ui::confirm::run_on_stderr(&format!("Apply the suggestion at {place}?"))? {
+                    continue;
+                }
+            }
+            match text.apply(c.start_line, suggestion) {
+                Outcome::Applied(line) => {
+                    eprintln!("Applied {file}:{line}");
+                    applied += 1;
+                    changed = true;
+                }
+                Outcome::AlreadyApplied => eprintln!("Already applied: {place}"),
+                Outcome::Conflict => {
+                    output::warn(format!(
+                        "{place} has changed since the suggestion; left as is"
+                    ));
+                    conflicts += 1;
+                }
+            }
+        }
+        if changed {
+            std::fs::write(&path, text.to_string()).map_err(|source| {
+                ReviewCommandError::WriteFailed {
+                    path: file.to_string(),
+                    source,
+                }
+            })?;
+        }
+    }
+
+    eprintln!("Applied {applied} suggestion(s)");
+    if conflicts > 0 {
+        output::warn(format!("{conflicts} suggestion(s) could not be applied"));
+    }
+    Ok(())
+}
+
+/// The review saved for `range`'s scope (empty when there is none).
+fn saved_review(range: &ReviewRange) -> Result<ReviewState> {
+    let dir = git::worktree::common_git_dir().map_err(ReviewCommandError::from)?;
+    Ok(state::load(&state::storage_key(&dir, &range.scope_id)))
+}
+
+/// The saved review of `range` with its comments re-anchored to the current
+/// diff, plus the diffs of the commented files.
+fn reanchored_review(
+    range: &ReviewRange,
+    files: &[diff::ChangedFile],
+) -> Result<(ReviewState, HashMap<String, diff::FileDiff>)> {
+    let mut review = saved_review(range)?;
+    let mut diffs = HashMap::new();
+    for file in files {
+        if review.comments.iter().any(|c| c.file == file.path) {
+            let diff = file.build(range.to).map_err(ReviewCommandError::from)?;
+            review.reanchor_file(&file.path, &diff);
+            diffs.insert(file.path.clone(), diff);
+        }
+    }
+    Ok((review, diffs))
+}
+
+/// The range `gx review` (and its subcommands) work on: a pull request, the
+/// staged or unstaged changes, or a local range, honoring `[review]
+/// default_mode` when no explicit target/base is given.
+fn resolve_range(
+    cfg: &Config,
+    target: Option<String>,
+    base: Option<String>,
+    staged: bool,
+    unstaged: bool,
+) -> Result<ReviewRange> {
+    // Honor [review] default_mode when no explicit target/base is given.
+    let pull_ref = target
+        .as_deref()
+        .and_then(github::parse_ref)
+        .filter(|r| matches!(r, GitHubRef::Pull { .. } | GitHubRef::PullShort { .. }));
+    let default_mode = if target.is_none() && base.is_none() {
+        cfg.review.default_mode.as_str()
+    } else {
+        ""
+    };
+    let range = if let Some(pull_ref) = pull_ref {
+        pull::resolve(&pull_ref)?
+    } else if staged || (!unstaged && default_mode == "staged") {
+        range::resolve_staged()?
+    } else if unstaged || default_mode == "unstaged" {
+        range::resolve_unstaged()?
+    } else if default_mode == "uncommitted" {
+        range::resolve_uncommitted()?
+    } else {
+        range::resolve(target, base)?
+    };
+    Ok(range)
 }
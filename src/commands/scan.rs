@@ -0,0 +1,44 @@
+//! `gx scan`: run the secret and large-file scan from
+//! [`crate::secret_scan`] on demand, over the staged changes (default), the
+//! whole working tree, the commits a push would send, or a commit range.
+//! Exits non-zero on any finding, so CI and hooks can call it directly.
+
+use crate::git::additions::{self, Addition};
+use crate::git::{self, GitError};
+use crate::secret_scan::{self, ScanError, Settings};
+use miette::Result;
+
+pub fn run(range: Option<String>, uncommitted: bool, outgoing: bool) -> Result<()> {
+    let (changes, what) = if let Some(range) = &range {
+        (additions::in_range(range), format!("commits in {range}"))
+    } else if uncommitted {
+        (additions::uncommitted(), "uncommitted changes".to_string())
+    } else if outgoing {
+        (outgoing_additions(), "outgoing commits".to_string())
+    } else {
+        (additions::staged(), "staged changes".to_string())
+    };
+    let changes = changes.map_err(ScanError::GitError)?;
+
+    let settings = Settings::load()?;
+    let findings = secret_scan::scan(&changes, &settings);
+    if findings.is_empty() {
+        eprintln!("No secrets or large files found in {what}");
+        return Ok(());
+    }
+    eprintln!("Possible secrets or large files in {what}:");
+    secret_scan::report(&findings, settings.max_bytes);
+    Err(ScanError::Findings(findings.len()).into())
+}
+
+/// The commits the current branch would push (see
+/// [`additions::outgoing`]).
+pub(crate) fn outgoing_additions() -> Result<Vec<Addition>, GitError> {
+    let branch = git::branch::get_current_branch()?;
+    let upstream = if branch.is_detached {
+        None
+    } else {
+        git::push::upstream(&branch.name)?
+    };
+    additions::outgoing(upstream.as_ref().map(|u| u.tracking_oid.as_str()))
+}
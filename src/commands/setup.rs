@@ -180,7 +180,7 @@ fn render_integration(
 /// `alias x='cmd ...'`; fish uses `alias x 'cmd ...'` (no `=`).
 fn render_aliases(aliases: &HashMap<String, String>, cmd: &str, shell: ShellKind) -> String {
     let mut sorted: Vec<(&String, &String)> = aliases.iter().collect();
-    sorted.sort_by(|(a, _), (b, _)| a.cmp(b));
+    sorted.sort_by_key(|(a, _)| *a);
 
     let mut out = String::from("# Aliases\n");
     for (alias, command) in sorted {
@@ -0,0 +1,219 @@
+//! `gx sync` (alias `gx pull`): fetch every remote with pruning, fast-forward
+//! the default branch's local ref, update the current branch from its
+//! upstream by the configured strategy, and list branches whose upstream was
+//! deleted. Each step prints one summary line to stderr.
+
+use crate::commands::workspace::main_worktree_root;
+use crate::config;
+use crate::git::sync::{FastForward, Strategy};
+use crate::git::worktree::Worktree;
+use crate::git::{self, GitError};
+use crate::output;
+use crate::repo_config;
+use miette::{Diagnostic, Result};
+use thiserror::Error;
+
+#[derive(Error, Debug, Diagnostic)]
+pub enum SyncError {
+    #[error("Git error: {0}")]
+    #[diagnostic(code(gx::sync::git_error), help("Are you in a git repository?"))]
+    GitError(#[from] GitError),
+
+    #[error("Invalid update strategy: {0}")]
+    #[diagnostic(
+        code(gx::sync::strategy),
+        help(
+            "Set [sync] strategy (or update_strategy in .gx/workspace.toml) to rebase, merge, or ff-only"
+        )
+    )]
+    Strategy(String),
+
+    #[error("'{branch}' has {ahead} local commit(s) and cannot be fast-forwarded to {upstream}")]
+    #[diagnostic(
+        code(gx::sync::not_fast_forward),
+        help("Rebase or merge it yourself, or set [sync] strategy to rebase or merge")
+    )]
+    NotFastForward {
+        branch: String,
+        upstream: String,
+        ahead: usize,
+    },
+
+    #[error("Updating '{branch}' from {upstream} stopped: {message}")]
+    #[diagnostic(code(gx::sync::update_failed), help("{help}"))]
+    UpdateFailed {
+        branch: String,
+        upstream: String,
+        message: String,
+        help: &'static str,
+    },
+}
+
+pub fn run() -> Result<()> {
+    let cfg = config::load()?;
+    let worktrees = git::worktree::list().map_err(SyncError::GitError)?;
+    let policy = repo_config::resolve_for_repo(&main_worktree_root(&worktrees)?)?;
+    let strategy = policy
+        .update_strategy
+        .as_deref()
+        .unwrap_or(&cfg.sync.strategy)
+        .parse::<Strategy>()
+        .map_err(SyncError::Strategy)?;
+
+    fetch();
+
+    let current = git::branch::get_current_branch().map_err(SyncError::GitError)?;
+    let default_branch = policy.default_branch.or_else(|| {
+        git::branch::default_remote_branch()
+            .ok()
+            .flatten()
+            .and_then(|remote| remote.split_once('/').map(|(_, name)| name.to_string()))
+    });
+    if let Some(default_branch) = default_branch
+        && (current.is_detached || current.name != default_branch)
+    {
+        fast_forward_default(&default_branch, &worktrees)?;
+    }
+
+    if current.is_detached {
+        eprintln!("HEAD is detached; no current branch to update");
+    } else {
+        update_current(&current.name, strategy)?;
+    }
+
+    report_gone_branches();
+    Ok(())
+}
+
+/// Fetch every remote. A failed fetch (e.g. offline) only warns: the rest of
+/// the sync still works from the refs fetched before.
+fn fetch() {
+    match git::sync::fetch_prune() {
+        Ok(summary) => {
+            let mut line = match summary.updated {
+                0 => "Fetched: nothing new".to_string(),
+                n => format!("Fetched: {n} {} updated", refs(n)),
+            };
+            if summary.pruned > 0 {
+                line.push_str(&format!(", {} pruned", summary.pruned));
+            }
+            eprintln!("{line}");
+        }
+        Err(e) => output::warn(format!("fetch failed ({e}); using local refs")),
+    }
+}
+
+/// Fast-forward the default branch's local ref when it isn't checked out. A
+/// branch checked out in another workspace is left alone: moving the ref
+/// under it would make its working tree look modified.
+fn fast_forward_default(branch: &str, worktrees: &[Worktree]) -> Result<()> {
+    if !git::worktree::branch_exists(branch).map_err(SyncError::GitError)? {
+        return Ok(());
+    }
+    if let Some(worktree) = worktrees
+        .iter()
+        .find(|w| w.branch.as_deref() == Some(branch))
+    {
+        eprintln!(
+            "'{branch}' is checked out in workspace '{}'; update it there",
+            worktree.name
+        );
+        return Ok(());
+    }
+    let Some(upstream) = git::sync::tracking_branch(branch).map_err(SyncError::GitError)? else {
+        eprintln!("'{branch}' has no upstream; not fast-forwarded");
+        return Ok(());
+    };
+
+    match git::sync::fast_forward_ref(branch, &upstream).map_err(SyncError::GitError)? {
+        FastForward::UpToDate => eprintln!("'{branch}' is up to date with {upstream}"),
+        FastForward::Advanced(n) => {
+            eprintln!(
+                "Fast-forwarded '{branch}' to {upstream} ({n} new {})",
+                commits(n)
+            )
+        }
+        FastForward::Diverged { ahead, behind } => output::warn(format!(
+            "'{branch}' has diverged from {upstream} ({ahead} ahead, {behind} behind); left as is"
+        )),
+    }
+    Ok(())
+}
+
+fn update_current(branch: &str, strategy: Strategy) -> Result<()> {
+    let Some(upstream) = git::sync::tracking_branch(branch).map_err(SyncError::GitError)? else {
+        eprintln!("'{branch}' has no upstream; nothing to update");
+        return Ok(());
+    };
+    let (ahead, behind) = git::branch::get_ahead_behind(branch)
+        .map_err(SyncError::GitError)?
+        .unwrap_or_default();
+
+    if behind == 0 {
+        match ahead {
+            0 => eprintln!("'{branch}' is up to date with {upstream}"),
+            n => eprintln!(
+                "'{branch}' is up to date with {upstream} ({n} {} to push)",
+                commits(n)
+            ),
+        }
+        return Ok(());
+    }
+    if strategy == Strategy::FfOnly && ahead > 0 {
+        return Err(SyncError::NotFastForward {
+            branch: branch.to_string(),
+            upstream,
+            ahead,
+        }
+        .into());
+    }
+
+    git::sync::update_current(&upstream, strategy).map_err(|e| SyncError::UpdateFailed {
+        branch: branch.to_string(),
+        upstream: upstream.clone(),
+        message: e.to_string(),
+        help: match strategy {
+            Strategy::Rebase => {
+                "Resolve the conflicts and run 'git rebase --continue', or 'git rebase --abort' to undo"
+            }
+            _ => "Resolve the conflicts and run 'git merge --continue', or 'git merge --abort' to undo",
+        },
+    })?;
+
+    let new = format!("{behind} new {}", commits(behind));
+    match (strategy, ahead) {
+        (_, 0) | (Strategy::FfOnly, _) => {
+            eprintln!("Fast-forwarded '{branch}' to {upstream} ({new})")
+        }
+        (Strategy::Rebase, _) => eprintln!("Rebased '{branch}' onto {upstream} ({new})"),
+        (Strategy::Merge, _) => eprintln!("Merged {upstream} into '{branch}' ({new})"),
+    }
+    Ok(())
+}
+
+/// List local branches whose upstream branch was deleted on the remote
+/// (typically merged PRs); `gx workspace clean` offers to remove them.
+fn report_gone_branches() {
+    match git::worktree::remote_gone_branches() {
+        Ok(gone) if gone.is_empty() => eprintln!("No local branches lost their upstream"),
+        Ok(gone) => eprintln!(
+            "Upstream gone for {} {}: {} (remove with 'gx workspace clean')",
+            gone.len(),
+            if gone.len() == 1 {
+                "branch"
+            } else {
+                "branches"
+            },
+            gone.join(", ")
+        ),
+        Err(e) => output::warn(format!("could not check for deleted upstreams ({e})")),
+    }
+}
+
+fn commits(n: usize) -> &'static str {
+    if n == 1 { "commit" } else { "commits" }
+}
+
+fn refs(n: usize) -> &'static str {
+    if n == 1 { "ref" } else { "refs" }
+}
@@ -1,5 +1,6 @@
 //! Workspace (git worktree) management, split by responsibility:
 //! - [`create`] — `gx workspace new` and the shared creation pipeline
+//! - [`suggest`] — `gx workspace new --describe/--from-diff` AI-suggested names
 //! - [`navigate`] — go/list/interactive picker + editor launching
 //! - [`lifecycle`] — remove/update/setup/sync/move/lock/repair + their engines
 //!
@@ -18,10 +19,12 @@
 mod create;
 mod lifecycle;
 mod navigate;
+mod suggest;
 
 pub use create::*;
 pub use lifecycle::*;
 pub use navigate::*;
+pub use suggest::*;
 
 use crate::git::{self, GitError, worktree::Worktree};
 use fuzzy_matcher::skim::SkimMatcherV2;
@@ -158,6 +161,13 @@ pub enum WorkspaceError {
     #[error("Conflicting flags: {0}")]
     #[diagnostic(code(gx::workspace::conflicting_flags))]
     ConflictingFlags(String),
+
+    #[error("Could not suggest a workspace name: {0}")]
+    #[diagnostic(
+        code(gx::workspace::name_suggestion_failed),
+        help("Pass a name explicitly, e.g. 'gx workspace new fix-login-redirect'")
+    )]
+    NameSuggestion(String),
 }
 
 /// Options for [`run_new`] / `create_workspace`, gathered into one struct so
@@ -0,0 +1,223 @@
+//! `gx workspace new --describe "<task>"` / `--from-diff`: ask the configured
+//! agent for a branch name, then create the workspace through the normal
+//! [`run_new`] pipeline.
+//!
+//! The reply is untrusted: it is reduced to a plain branch name, checked
+//! against git's ref rules and [`validate_name`], and suffixed (`-2`, `-3`, …)
+//! until it collides with no existing branch or workspace.
+
+use super::{
+    NewWorkspaceOptions, WorkspaceError, home_dir, load_worktrees, main_worktree_root, run_new,
+    validate_name, workspace_path,
+};
+use crate::ai::diff::{self, DiffBudget};
+use crate::ai::prompts::{self, Template};
+use crate::ai::{Feature, Provider};
+use crate::config;
+use crate::git;
+use miette::Result;
+
+/// How many numbered variants of the suggestion are tried before giving up.
+const MAX_SUFFIX: usize = 20;
+
+/// What the agent should name the workspace after.
+#[derive(Debug, Clone)]
+pub enum NameSource {
+    /// A free-form task description (`--describe`).
+    Describe(String),
+    /// The current workspace's uncommitted changes (`--from-diff`).
+    Diff,
+}
+
+/// Suggest a name from `source`, report it on stderr, and create the
+/// workspace as if it had been passed to [`run_new`].
+pub fn run_new_suggested(source: NameSource, opts: NewWorkspaceOptions) -> Result<()> {
+    let config = config::load()?;
+    let provider = Provider::for_feature(&config.ai, Feature::Workspace)
+        .map_err(WorkspaceError::NameSuggestion)?;
+
+    let task = match source {
+        NameSource::Describe(text) => {
+            let text = text.trim();
+            if text.is_empty() {
+                return Err(WorkspaceError::NameSuggestion(
+                    "the --describe text is empty".to_string(),
+                )
+                .into());
+            }
+            format!("Task description:\n{text}")
+        }
+        NameSource::Diff => {
+            let patch = git::staging::get_uncommitted_diff().map_err(WorkspaceError::GitError)?;
+            if patch.is_empty() {
+                return Err(WorkspaceError::NameSuggestion(
+                    "there are no uncommitted changes to describe".to_string(),
+                )
+                .into());
+            }
+            let patch = diff::prepare(&patch, &DiffBudget::from_config(&config.ai));
+            format!("Uncommitted changes:\n{patch}")
+        }
+    };
+
+    let branch = git::branch::get_current_branch()
+        .map(|b| b.name)
+        .unwrap_or_default();
+    let prompt = prompts::render(
+        &prompts::load(Template::Branch)?,
+        &[("task", &task), ("branch", &branch)],
+    );
+    eprintln!("Asking {provider} for a workspace name…");
+    let reply = provider
+        .run_capturing_if(&config.ai.model, &prompt, None, |reply| {
+            suggested_name(reply).is_ok()
+        })
+        .map_err(|e| WorkspaceError::NameSuggestion(e.to_string()))?;
+    let suggested = suggested_name(&reply)?;
+
+    let name = first_free(&suggested, |candidate| {
+        is_taken(candidate, &config.workspace.root)
+    })?
+    .ok_or_else(|| {
+        WorkspaceError::NameSuggestion(format!(
+            "'{suggested}' and its numbered variants are all taken"
+        ))
+    })?;
+    eprintln!("Using workspace name '{name}'");
+    run_new(name, opts)
+}
+
+/// The branch name in the agent's `reply`, cleaned up and checked against
+/// git's and gx's naming rules.
+fn suggested_name(reply: &str) -> Result<String, WorkspaceError> {
+    let suggested = sanitize(reply).ok_or_else(|| {
+        WorkspaceError::NameSuggestion(format!("the reply is not a usable name: {reply:?}"))
+    })?;
+    let valid = git2::Branch::name_is_valid(&suggested).unwrap_or(false);
+    if !valid || validate_name(&suggested).is_err() {
+        return Err(WorkspaceError::NameSuggestion(format!(
+            "'{suggested}' is not a valid branch name"
+        )));
+    }
+    Ok(suggested)
+}
+
+/// Whether `name` is already used as a local or remote branch, blocked by the
+/// ref namespace (`foo` vs `foo/bar`), or taken as a workspace name/path.
+fn is_taken(name: &str, root_template: &str) -> Result<bool, WorkspaceError> {
+    if git::worktree::branch_exists(name)?
+        || git::branch::find_remote_branch(name)?.is_some()
+        || git::worktree::conflicting_branch(name)?.is_some()
+    {
+        return Ok(true);
+    }
+    let dir_name = git::worktree::flatten_slashes(name);
+    let worktrees = load_worktrees()?;
+    if worktrees.iter().any(|w| w.name == dir_name) {
+        return Ok(true);
+    }
+    let main_root = main_worktree_root(&worktrees)?;
+    let path = workspace_path(&main_root, home_dir().as_deref(), root_template, &dir_name);
+    Ok(path.exists())
+}
+
+/// `base`, or the first of `base-2`, `base-3`, … that `taken` rejects.
+fn first_free<E>(
+    base: &str,
+    mut taken: impl FnMut(&str) -> Result<bool, E>,
+) -> Result<Option<String>, E> {
+    for n in 1..=MAX_SUFFIX {
+        let candidate = if n == 1 {
+            base.to_string()
+        } else {
+            format!("{base}-{n}")
+        };
+        if !taken(&candidate)? {
+            return Ok(Some(candidate));
+        }
+    }
+    Ok(None)
+}
+
+/// Reduce an agent reply to a branch-name candidate: the first non-empty
+/// line, unquoted, lowercased, with whitespace and other stray characters
+/// turned into single hyphens. `None` when nothing usable remains.
+fn sanitize(reply: &str) -> Option<String> {
+    let line = reply
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with("```"))?;
+    let line =
+        line.trim_matches(|c: char| matches!(c, '`' | '"' | '\'' | '*') || c.is_whitespace());
+
+    let mut name = String::with_capacity(line.len());
+    for c in line.chars().flat_map(char::to_lowercase) {
+        let c = if c.is_ascii_alphanumeric() || matches!(c, '/' | '.' | '_') {
+            c
+        } else {
+            '-'
+        };
+        if (c == '-' && name.ends_with(['-', '/'])) || (c == '.' && name.ends_with('.')) {
+            continue;
+        }
+        if c == '/' && name.ends_with('-') {
+            name.pop();
+        }
+        name.push(c);
+    }
+    let name = name
+        .split('/')
+        .map(|segment| segment.trim_matches(['-', '.']))
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/");
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_cleans_agent_replies() {
+        assert_eq!(
+            sanitize("fix/login-redirect-loop").as_deref(),
+            Some("fix/login-redirect-loop")
+        );
+        assert_eq!(
+            sanitize("\n```\n`Fix/Login Redirect  Loop`\n```\n").as_deref(),
+            Some("fix/login-redirect-loop")
+        );
+        assert_eq!(
+            sanitize("\"feat: add SSO!\"\nBecause…").as_deref(),
+            Some("feat-add-sso")
+        );
+        assert_eq!(sanitize("-/.hidden//x-/").as_deref(), Some("hidden/x"));
+        assert_eq!(sanitize("  \n\"\"\n"), None);
+    }
+
+    #[test]
+    fn test_sanitized_names_pass_ref_rules() {
+        for reply in ["Fix: login ~ redirect^loop?", "a..b/c.lock", "@{x} y"] {
+            let name = sanitize(reply).unwrap();
+            assert!(validate_name(&name).is_ok(), "{name}");
+            assert!(!name.contains(".."), "{name}");
+            assert!(
+                !name.contains(['~', '^', ':', '?', '*', '[', ' ']),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_first_free_appends_a_counter() {
+        let taken = ["fix-bug", "fix-bug-2"];
+        let free = first_free::<()>("fix-bug", |c| Ok(taken.contains(&c))).unwrap();
+        assert_eq!(free.as_deref(), Some("fix-bug-3"));
+
+        let free = first_free::<()>("new", |_| Ok(false)).unwrap();
+        assert_eq!(free.as_deref(), Some("new"));
+
+        assert_eq!(first_free::<()>("x", |_| Ok(true)).unwrap(), None);
+    }
+}
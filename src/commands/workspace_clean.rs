@@ -52,7 +52,7 @@ impl ProtectionSet {
 
 /// Pure assembly of the protected-branch set from already-resolved inputs.
 /// Extracted from [`resolve_protected`] so it is unit-testable without a repo.
-fn build_protection_set(
+pub(crate) fn build_protection_set(
     configured: &[String],
     default_branch: Option<&str>,
     current_branch: Option<&str>,
@@ -101,7 +101,7 @@ fn resolve_protected(cfg: &Config, worktrees: &[Worktree]) -> ProtectionSet {
 }
 
 /// Strip a leading "<remote>/" component (e.g. "origin/main" -> "main").
-fn strip_remote_prefix(remote_branch: &str) -> &str {
+pub(crate) fn strip_remote_prefix(remote_branch: &str) -> &str {
     remote_branch
         .split_once('/')
         .map(|(_, tail)| tail)
@@ -0,0 +1,471 @@
+//! Commit-message conventions: the resolved [`CommitRules`] (global `[commit]`
+//! config overridden per field by the repo's `.gx/workspace.toml`), the AI
+//! commit prompt generated from them, and the linter `gx commit` and
+//! `gx commit lint` run over every message.
+//!
+//! Everything here is pure over its inputs so the prompt and the linter are
+//! unit-testable without a repository; loading the layers is the only I/O.
+
+use crate::config::{self, CommitConfig};
+use crate::repo_config::{self, RepoConfigFile};
+use miette::{Result, miette};
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Descriptions for the stock conventional-commit types, used in the AI
+/// prompt. Custom types configured by a repo are listed without one.
+const TYPE_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("feat", "NEW functionality or feature added"),
+    ("fix", "BUG fixes or corrections"),
+    ("refactor", "code restructuring WITHOUT behavior changes"),
+    ("docs", "documentation changes ONLY"),
+    (
+        "style",
+        "formatting, whitespace, missing semicolons (no code logic change)",
+    ),
+    ("test", "adding or updating tests"),
+    ("perf", "performance improvements"),
+    ("chore", "dependency updates, build config, tooling"),
+    ("ci", "CI/CD pipeline changes"),
+    ("build", "build system or external dependency changes"),
+];
+
+/// Subjects git writes itself (merges, reverts, autosquash markers). They are
+/// never held to the conventional format.
+const GENERATED_PREFIXES: &[&str] = &["Merge ", "Revert \"", "fixup! ", "squash! ", "amend! "];
+
+/// `<type>(<scope>)!: <description>`, with the scope and `!` optional.
+static SUBJECT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?P<type>[A-Za-z][\w-]*)(?:\((?P<scope>[^()]*)\))?(?P<bang>!)?:(?P<desc>(?: .*)?)$",
+    )
+    .expect("subject regex is valid")
+});
+
+/// The effective commit conventions for one repository.
+#[derive(Debug, Clone)]
+pub struct CommitRules {
+    pub types: Vec<String>,
+    /// Empty means any scope (or none) is accepted.
+    pub scopes: Vec<String>,
+    pub max_subject_length: usize,
+    pub ticket_pattern: Option<Regex>,
+    pub template: Option<String>,
+    /// Whether `gx commit` enforces these rules (`gx commit lint` always does).
+    pub lint: bool,
+}
+
+impl Default for CommitRules {
+    fn default() -> Self {
+        CommitRules {
+            types: config::default_commit_types(),
+            scopes: Vec::new(),
+            max_subject_length: CommitConfig::default().max_subject_length,
+            ticket_pattern: None,
+            template: None,
+            lint: false,
+        }
+    }
+}
+
+/// One rule a message breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    Empty,
+    Malformed,
+    UnknownType(String),
+    UnknownScope(String),
+    EmptyDescription,
+    SubjectTooLong { len: usize, max: usize },
+    MissingBlankLine,
+    MissingTicket(String),
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::Empty => write!(f, "message is empty"),
+            Violation::Malformed => {
+                write!(f, "subject must look like '<type>(<scope>): <description>'")
+            }
+            Violation::UnknownType(t) => write!(f, "type '{t}' is not allowed"),
+            Violation::UnknownScope(s) => write!(f, "scope '{s}' is not allowed"),
+            Violation::EmptyDescription => write!(f, "description after the type is empty"),
+            Violation::SubjectTooLong { len, max } => {
+                write!(f, "subject is {len} characters (max {max})")
+            }
+            Violation::MissingBlankLine => {
+                write!(f, "subject and body must be separated by a blank line")
+            }
+            Violation::MissingTicket(pattern) => {
+                write!(f, "no ticket reference matching '{pattern}'")
+            }
+        }
+    }
+}
+
+/// Merge the global `[commit]` config with the repo's shared then local
+/// `[commit]` sections. Every repo field replaces the layer below when set.
+/// Linting defaults to on as soon as a repo declares a `[commit]` section,
+/// since that is a team opting into conventions; `lint = false` opts back out.
+pub fn resolve(
+    global: &CommitConfig,
+    shared: Option<&RepoConfigFile>,
+    local: Option<&RepoConfigFile>,
+) -> Result<CommitRules> {
+    let mut types = global.types.clone();
+    let mut scopes = global.scopes.clone();
+    let mut max_subject_length = global.max_subject_length;
+    let mut ticket_pattern = global.ticket_pattern.clone();
+    let mut template = global.template.clone();
+    let mut lint = global.lint;
+
+    for section in [shared, local]
+        .into_iter()
+        .flatten()
+        .filter_map(|layer| layer.commit.as_ref())
+    {
+        lint = section.lint.unwrap_or(true);
+        if let Some(value) = &section.types {
+            types = value.clone();
+        }
+        if let Some(value) = &section.scopes {
+            scopes = value.clone();
+        }
+        if let Some(value) = section.max_subject_length {
+            max_subject_length = value;
+        }
+        if let Some(value) = &section.ticket_pattern {
+            ticket_pattern = value.clone();
+        }
+        if let Some(value) = &section.template {
+            template = value.clone();
+        }
+    }
+
+    let ticket_pattern = if ticket_pattern.trim().is_empty() {
+        None
+    } else {
+        Some(
+            Regex::new(&ticket_pattern)
+                .map_err(|e| miette!("invalid [commit] ticket_pattern '{ticket_pattern}': {e}"))?,
+        )
+    };
+
+    Ok(CommitRules {
+        types,
+        scopes,
+        max_subject_length,
+        ticket_pattern,
+        template: Some(template).filter(|t| !t.trim().is_empty()),
+        lint,
+    })
+}
+
+/// Load the global config and the repo layers under `main_root`, then
+/// [`resolve`] them.
+pub fn resolve_for_repo(main_root: &Path) -> Result<CommitRules> {
+    let global = config::load()?;
+    let (shared, local) = repo_config::load_repo_layers(main_root)?;
+    resolve(&global.commit, shared.as_ref(), local.as_ref())
+}
+
+/// The `{rules}` section of the AI commit-message prompt: the allowed types
+/// (with the stock descriptions), then any scope, length, ticket and template
+/// constraints, and the expected output format.
+pub fn prompt_rules(rules: &CommitRules) -> String {
+    let mut prompt = String::new();
+
+    for ty in &rules.types {
+        match TYPE_DESCRIPTIONS.iter().find(|(name, _)| name == ty) {
+            Some((_, description)) => prompt.push_str(&format!("- {ty}: {description}\n")),
+            None => prompt.push_str(&format!("- {ty}\n")),
+        }
+    }
+
+    prompt.push_str("\nUse ONLY the types listed above.\n");
+    if !rules.scopes.is_empty() {
+        prompt.push_str(&format!(
+            "Add a scope when one applies, chosen ONLY from: {}.\n",
+            rules.scopes.join(", ")
+        ));
+    }
+    prompt.push_str(&format!(
+        "Keep the subject line at most {} characters.\n",
+        rules.max_subject_length
+    ));
+    if let Some(pattern) = &rules.ticket_pattern {
+        prompt.push_str(&format!(
+            "The message MUST contain a ticket reference matching the regex `{}`; take it from the diff or branch context, never invent one.\n",
+            pattern.as_str()
+        ));
+    }
+    if let Some(template) = &rules.template {
+        prompt.push_str(&format!(
+            "Follow this message template:\n\n{}\n",
+            template.trim_end()
+        ));
+    }
+
+    let format = if rules.scopes.is_empty() {
+        "<type>: <imperative description>"
+    } else {
+        "<type>(<scope>): <imperative description>"
+    };
+    prompt.push_str(&format!(
+        "\nCarefully analyze what the diff actually does. Most changes are NOT features.\n\n\
+         Message format: {format}"
+    ));
+    prompt
+}
+
+/// The parts of a conventional-commit subject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subject<'a> {
+    pub ty: &'a str,
+    pub scope: Option<&'a str>,
+    /// The `!` marker after the type/scope.
+    pub breaking: bool,
+    pub description: &'a str,
+}
+
+/// Split `subject` into its conventional-commit parts, or `None` when it does
+/// not follow the format. Types are not checked against any rules.
+pub fn parse_subject(subject: &str) -> Option<Subject<'_>> {
+    let caps = SUBJECT_RE.captures(subject.trim_end())?;
+    Some(Subject {
+        ty: caps.name("type")?.as_str(),
+        scope: caps.name("scope").map(|m| m.as_str()),
+        breaking: caps.name("bang").is_some(),
+        description: caps.name("desc")?.as_str().trim(),
+    })
+}
+
+/// Check `message` against `rules`, returning every violation (empty = clean).
+/// Messages git generates itself (merge, revert, fixup!/squash!/amend!) pass.
+pub fn lint(message: &str, rules: &CommitRules) -> Vec<Violation> {
+    let message = message.trim();
+    if message.is_empty() {
+        return vec![Violation::Empty];
+    }
+
+    let mut lines = message.lines();
+    let subject = lines.next().unwrap_or("").trim_end();
+    if GENERATED_PREFIXES.iter().any(|p| subject.starts_with(p)) {
+        return Vec::new();
+    }
+
+    let mut violations = Vec::new();
+
+    match SUBJECT_RE.captures(subject) {
+        None => violations.push(Violation::Malformed),
+        Some(caps) => {
+            let ty = &caps["type"];
+            if !rules.types.iter().any(|t| t == ty) {
+                violations.push(Violation::UnknownType(ty.to_string()));
+            }
+            if let Some(scope) = caps.name("scope").map(|m| m.as_str())
+                && !rules.scopes.is_empty()
+                && !rules.scopes.iter().any(|s| s == scope)
+            {
+                violations.push(Violation::UnknownScope(scope.to_string()));
+            }
+            if caps["desc"].trim().is_empty() {
+                violations.push(Violation::EmptyDescription);
+            }
+        }
+    }
+
+    let len = subject.chars().count();
+    if len > rules.max_subject_length {
+        violations.push(Violation::SubjectTooLong {
+            len,
+            max: rules.max_subject_length,
+        });
+    }
+
+    if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+        violations.push(Violation::MissingBlankLine);
+    }
+
+    if let Some(pattern) = &rules.ticket_pattern
+        && !pattern.is_match(message)
+    {
+        violations.push(Violation::MissingTicket(pattern.as_str().to_string()));
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo_config::RepoCommitSection;
+
+    fn rules() -> CommitRules {
+        CommitRules::default()
+    }
+
+    fn repo_layer(section: RepoCommitSection) -> RepoConfigFile {
+        RepoConfigFile {
+            commit: Some(section),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_subject() {
+        assert_eq!(
+            parse_subject("fix(api)!: drop v1"),
+            Some(Subject {
+                ty: "fix",
+                scope: Some("api"),
+                breaking: true,
+                description: "drop v1",
+            })
+        );
+        assert_eq!(parse_subject("feat: x").map(|s| s.scope), Some(None));
+        assert_eq!(parse_subject("Update README"), None);
+    }
+
+    #[test]
+    fn test_clean_message_passes() {
+        assert!(lint("feat: add login", &rules()).is_empty());
+        assert!(lint("fix(api)!: drop v1\n\nBREAKING CHANGE: gone", &rules()).is_empty());
+    }
+
+    #[test]
+    fn test_generated_messages_pass() {
+        assert!(lint("Merge branch 'main' into feat", &rules()).is_empty());
+        assert!(lint("Revert \"feat: add login\"", &rules()).is_empty());
+        assert!(lint("fixup! feat: add login", &rules()).is_empty());
+    }
+
+    #[test]
+    fn test_malformed_and_empty() {
+        assert_eq!(lint("   \n", &rules()), vec![Violation::Empty]);
+        assert_eq!(lint("add login", &rules()), vec![Violation::Malformed]);
+        assert_eq!(lint("feat: ", &rules()), vec![Violation::EmptyDescription]);
+        assert_eq!(lint("feat:add login", &rules()), vec![Violation::Malformed]);
+    }
+
+    #[test]
+    fn test_unknown_type_and_scope() {
+        assert_eq!(
+            lint("feature: add login", &rules()),
+            vec![Violation::UnknownType("feature".to_string())]
+        );
+
+        let mut scoped = rules();
+        scoped.scopes = vec!["api".to_string(), "cli".to_string()];
+        assert!(lint("feat(api): add login", &scoped).is_empty());
+        assert!(lint("feat: add login", &scoped).is_empty());
+        assert_eq!(
+            lint("feat(ui): add login", &scoped),
+            vec![Violation::UnknownScope("ui".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_subject_length_counts_chars() {
+        let mut short = rules();
+        short.max_subject_length = 12;
+        assert!(lint("fix: äöü abc", &short).is_empty());
+        assert_eq!(
+            lint("fix: äöü abcd", &short),
+            vec![Violation::SubjectTooLong { len: 13, max: 12 }]
+        );
+    }
+
+    #[test]
+    fn test_body_needs_blank_line() {
+        assert_eq!(
+            lint("feat: add login\nmore detail", &rules()),
+            vec![Violation::MissingBlankLine]
+        );
+    }
+
+    #[test]
+    fn test_ticket_pattern_matches_anywhere() {
+        let mut ticketed = rules();
+        ticketed.ticket_pattern = Some(Regex::new(r"[A-Z]+-\d+").unwrap());
+        assert!(lint("feat: add login\n\nRefs: AUTH-12", &ticketed).is_empty());
+        assert_eq!(
+            lint("feat: add login", &ticketed),
+            vec![Violation::MissingTicket(r"[A-Z]+-\d+".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_resolve_global_only_keeps_lint_off() {
+        let resolved = resolve(&CommitConfig::default(), None, None).unwrap();
+        assert!(!resolved.lint);
+        assert_eq!(resolved.types, config::default_commit_types());
+        assert!(resolved.ticket_pattern.is_none());
+        assert!(resolved.template.is_none());
+    }
+
+    #[test]
+    fn test_resolve_repo_section_overrides_and_enables_lint() {
+        let shared = repo_layer(RepoCommitSection {
+            types: Some(vec!["feat".to_string(), "fix".to_string()]),
+            ticket_pattern: Some(r"#\d+".to_string()),
+            ..Default::default()
+        });
+        let local = repo_layer(RepoCommitSection {
+            max_subject_length: Some(50),
+            ..Default::default()
+        });
+
+        let resolved = resolve(&CommitConfig::default(), Some(&shared), Some(&local)).unwrap();
+        assert!(resolved.lint);
+        assert_eq!(resolved.types, vec!["feat".to_string(), "fix".to_string()]);
+        assert_eq!(resolved.max_subject_length, 50);
+        assert_eq!(resolved.ticket_pattern.unwrap().as_str(), r"#\d+");
+    }
+
+    #[test]
+    fn test_resolve_repo_can_opt_out_of_lint() {
+        let shared = repo_layer(RepoCommitSection {
+            lint: Some(false),
+            ..Default::default()
+        });
+        let resolved = resolve(&CommitConfig::default(), Some(&shared), None).unwrap();
+        assert!(!resolved.lint);
+    }
+
+    #[test]
+    fn test_resolve_rejects_invalid_ticket_pattern() {
+        let global = CommitConfig {
+            ticket_pattern: "([".to_string(),
+            ..Default::default()
+        };
+        assert!(resolve(&global, None, None).is_err());
+    }
+
+    #[test]
+    fn test_prompt_lists_types_and_constraints() {
+        let default_prompt = prompt_rules(&rules());
+        assert!(default_prompt.contains("- feat: NEW functionality or feature added\n"));
+        assert!(default_prompt.contains("- build: build system"));
+        assert!(default_prompt.contains("at most 72 characters"));
+        assert!(default_prompt.contains("Message format: <type>: <imperative description>"));
+        assert!(!default_prompt.contains("ticket"));
+
+        let custom = CommitRules {
+            types: vec!["feat".to_string(), "deps".to_string()],
+            scopes: vec!["api".to_string()],
+            ticket_pattern: Some(Regex::new(r"JIRA-\d+").unwrap()),
+            template: Some("<type>: <summary>\n\nRefs: <ticket>".to_string()),
+            ..rules()
+        };
+        let prompt = prompt_rules(&custom);
+        assert!(prompt.contains("- deps\n"));
+        assert!(!prompt.contains("- fix:"));
+        assert!(prompt.contains("chosen ONLY from: api."));
+        assert!(prompt.contains(r"`JIRA-\d+`"));
+        assert!(prompt.contains("Refs: <ticket>"));
+        assert!(prompt.contains("Message format: <type>(<scope>): <imperative description>"));
+    }
+}
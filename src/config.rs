@@ -1,10 +1,17 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The agent CLI gx drives. `OpenCode` and `Claude` are built-in presets;
+/// `Custom` comes from an `[ai.agents.<name>]` entry (which may also redefine
+/// a preset's name).
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Agent {
     OpenCode,
     Claude,
+    Custom {
+        name: String,
+        definition: AgentDefinition,
+    },
 }
 
 impl Agent {
@@ -12,6 +19,23 @@ impl Agent {
         match self {
             Agent::OpenCode => "opencode",
             Agent::Claude => "claude",
+            Agent::Custom { name, .. } => name,
+        }
+    }
+
+    /// The command line template behind this agent. The presets spell out the
+    /// argv gx has always used for opencode and claude.
+    pub fn definition(&self) -> AgentDefinition {
+        let preset = |command: &str, args: &[&str]| AgentDefinition {
+            command: command.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            prompt_input: PromptInput::Argv,
+            interactive_args: None,
+        };
+        match self {
+            Agent::OpenCode => preset("opencode", &["run", "{prompt}", "--model", "{model}"]),
+            Agent::Claude => preset("claude", &["-p", "{prompt}", "--model", "{model}"]),
+            Agent::Custom { definition, .. } => definition.clone(),
         }
     }
 }
@@ -26,32 +50,60 @@ impl TryFrom<String> for Agent {
     type Error = String;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        match value.as_str() {
-            "opencode" => Ok(Agent::OpenCode),
-            "claude" => Ok(Agent::Claude),
-            _ => Err(format!(
-                "Unknown agent: {}. Expected 'opencode' or 'claude'",
-                value
-            )),
-        }
+        Agent::try_from(value.as_str())
     }
 }
 
 impl<'a> TryFrom<&'a str> for Agent {
     type Error = String;
 
+    /// Resolves the built-in presets only; `[ai.agents]` entries are resolved
+    /// by [`AiConfig::get_agent`].
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
         match value {
             "opencode" => Ok(Agent::OpenCode),
             "claude" => Ok(Agent::Claude),
             _ => Err(format!(
-                "Unknown agent: {}. Expected 'opencode' or 'claude'",
-                value
+                "Unknown agent: {}. Expected 'opencode', 'claude', or an [ai.agents.{}] entry",
+                value, value
             )),
         }
     }
 }
 
+/// How an agent receives the prompt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptInput {
+    /// Substituted into the `{prompt}` argument.
+    #[default]
+    Argv,
+    /// Written to the agent's stdin, ahead of any piped input (e.g. the diff).
+    Stdin,
+}
+
+/// An `[ai.agents.<name>]` entry: how to invoke an agent CLI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentDefinition {
+    /// Executable to run (looked up on PATH).
+    pub command: String,
+
+    /// Argument template. `{prompt}` and `{model}` are replaced in every
+    /// argument; in argv mode the prompt is appended when no argument
+    /// mentions `{prompt}`.
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// `"argv"` (default) or `"stdin"`.
+    #[serde(default)]
+    pub prompt_input: PromptInput,
+
+    /// Argument template for interactive sessions (PR troubleshoot), which
+    /// always receive the prompt via `{prompt}`. Defaults to `args`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interactive_args: Option<Vec<String>>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default)]
@@ -68,6 +120,150 @@ pub struct Config {
 
     #[serde(default)]
     pub review: ReviewConfig,
+
+    #[serde(default)]
+    pub commit: CommitConfig,
+
+    #[serde(default)]
+    pub push: PushConfig,
+
+    #[serde(default)]
+    pub sync: SyncConfig,
+
+    #[serde(default)]
+    pub land: LandConfig,
+
+    #[serde(default)]
+    pub scan: ScanConfig,
+}
+
+/// The secret and large-file scan `gx commit` and `gx push` run, mapped to
+/// the `[scan]` table. `gx scan` runs regardless of `enabled`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanConfig {
+    #[serde(default = "default_scan_enabled")]
+    pub enabled: bool,
+
+    /// Files larger than this many KiB are reported.
+    #[serde(default = "default_scan_max_file_size_kb")]
+    pub max_file_size_kb: u64,
+}
+
+fn default_scan_enabled() -> bool {
+    true
+}
+
+fn default_scan_max_file_size_kb() -> u64 {
+    1024
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        ScanConfig {
+            enabled: default_scan_enabled(),
+            max_file_size_kb: default_scan_max_file_size_kb(),
+        }
+    }
+}
+
+/// Safety checks for `gx push`, mapped to the `[push]` table.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PushConfig {
+    /// Refuse pushes and force pushes to protected branches instead of asking
+    /// for confirmation.
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// How `gx sync` updates the current branch, mapped to the `[sync]` table. A
+/// repo's `.gx/workspace.toml` `update_strategy` takes precedence.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// "rebase", "merge", or "ff-only".
+    #[serde(default = "default_sync_strategy")]
+    pub strategy: String,
+}
+
+fn default_sync_strategy() -> String {
+    "rebase".to_string()
+}
+
+/// `gx land`, mapped to the `[land]` table. A repo's `.gx/workspace.toml`
+/// `[land]` section takes precedence.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LandConfig {
+    /// Shell command run in the workspace after rebasing and before pushing.
+    /// Empty skips the test step.
+    #[serde(default)]
+    pub test_command: String,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        SyncConfig {
+            strategy: default_sync_strategy(),
+        }
+    }
+}
+
+/// Commit-message conventions, mapped to the `[commit]` table. A repo's
+/// `.gx/workspace.toml` `[commit]` section overrides these per field (see
+/// `commit_lint::resolve`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitConfig {
+    /// Allowed conventional-commit types. Also the type list the AI commit
+    /// prompt offers the agent.
+    #[serde(default = "default_commit_types")]
+    pub types: Vec<String>,
+
+    /// Allowed scopes. Empty means any scope (or none) is accepted.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+
+    /// Maximum subject-line length in characters.
+    #[serde(default = "default_max_subject_length")]
+    pub max_subject_length: usize,
+
+    /// Regex every message must match somewhere (e.g. `[A-Z]+-\d+` for a
+    /// ticket reference). Empty disables the check.
+    #[serde(default)]
+    pub ticket_pattern: String,
+
+    /// Initial editor content for a fresh commit, also shown to the AI agent
+    /// as the shape to follow. Empty means no template.
+    #[serde(default)]
+    pub template: String,
+
+    /// Whether `gx commit` lints every message before committing. Off by
+    /// default globally; a repo `[commit]` section turns it on.
+    #[serde(default)]
+    pub lint: bool,
+}
+
+pub fn default_commit_types() -> Vec<String> {
+    [
+        "feat", "fix", "refactor", "docs", "style", "test", "perf", "chore", "ci", "build",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_max_subject_length() -> usize {
+    72
+}
+
+impl Default for CommitConfig {
+    fn default() -> Self {
+        CommitConfig {
+            types: default_commit_types(),
+            scopes: Vec::new(),
+            max_subject_length: default_max_subject_length(),
+            ticket_pattern: String::new(),
+            template: String::new(),
+            lint: false,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -123,7 +319,7 @@ pub struct ReviewConfig {
     pub side_by_side_min_width: u16,
 
     /// Default range mode when none is given on the CLI: "branch", "commit",
-    /// or "uncommitted".
+    /// "uncommitted", "staged", or "unstaged".
     #[serde(default = "default_review_mode")]
     pub default_mode: String,
 }
@@ -230,6 +426,143 @@ pub struct AiConfig {
 
     #[serde(default = "default_model")]
     pub model: String,
+
+    /// Token budget for diffs sent to the agent (about 4 bytes per token).
+    /// Lockfiles, vendored and binary files are dropped first, then whole files
+    /// once the budget is spent; the prompt says what was left out.
+    #[serde(default = "default_diff_budget_tokens")]
+    pub diff_budget_tokens: usize,
+
+    /// Hunks longer than this many lines are collapsed to their +/- stats.
+    #[serde(default = "default_diff_max_hunk_lines")]
+    pub diff_max_hunk_lines: usize,
+
+    /// User-defined agents (`[ai.agents.<name>]`), selectable via `agent`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub agents: HashMap<String, AgentDefinition>,
+
+    /// Agent for `gx commit --ai` / `--ai-split` and `gx changelog --ai`;
+    /// empty uses `agent`. `"http"` selects the built-in `[ai.http]` provider.
+    #[serde(default)]
+    pub commit_agent: String,
+
+    /// Agent for the PR dashboard's reviewer-suggestion fallback; empty uses
+    /// `agent`. `"http"` selects the built-in `[ai.http]` provider.
+    #[serde(default)]
+    pub reviewer_agent: String,
+
+    /// Agent for the "explain" action in `gx log` and `gx review`; empty uses
+    /// `agent`. `"http"` selects the built-in `[ai.http]` provider.
+    #[serde(default)]
+    pub explain_agent: String,
+
+    /// Agent for `gx workspace new --describe` / `--from-diff`; empty uses
+    /// `agent`. `"http"` selects the built-in `[ai.http]` provider.
+    #[serde(default)]
+    pub workspace_agent: String,
+
+    /// The built-in OpenAI-compatible chat-completions provider.
+    #[serde(default)]
+    pub http: HttpProviderConfig,
+
+    /// The on-disk cache of one-shot replies (`[ai.cache]`).
+    #[serde(default)]
+    pub cache: AiCacheConfig,
+}
+
+/// Agent name that selects the built-in [`HttpProviderConfig`] provider.
+pub const HTTP_AGENT: &str = "http";
+
+/// `[ai.http]`: an OpenAI-compatible chat-completions endpoint (OpenAI, a local
+/// llama.cpp / ollama server, ...) gx talks to directly, without an agent CLI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpProviderConfig {
+    /// API root; `/chat/completions` is appended.
+    #[serde(default = "default_http_base_url")]
+    pub base_url: String,
+
+    /// Model to request. Empty uses `[ai] model`.
+    #[serde(default)]
+    pub model: String,
+
+    /// Environment variable holding the API key, sent as a bearer token.
+    /// Empty sends no `Authorization` header (typical for local servers).
+    #[serde(default)]
+    pub api_key_env: String,
+
+    /// Whole-request timeout in seconds.
+    #[serde(default = "default_http_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Request a streamed (server-sent events) response.
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// `[ai.cache]`: replies are reused when the provider, model, prompt and input
+/// all match a stored answer younger than `ttl_hours`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AiCacheConfig {
+    #[serde(default = "default_cache_enabled")]
+    pub enabled: bool,
+
+    /// Age after which a cached reply is ignored and deleted.
+    #[serde(default = "default_cache_ttl_hours")]
+    pub ttl_hours: u64,
+
+    /// Total size the cache directory is trimmed to, oldest entries first.
+    #[serde(default = "default_cache_max_size_mb")]
+    pub max_size_mb: u64,
+}
+
+fn default_cache_enabled() -> bool {
+    true
+}
+
+fn default_cache_ttl_hours() -> u64 {
+    24
+}
+
+fn default_cache_max_size_mb() -> u64 {
+    32
+}
+
+impl Default for AiCacheConfig {
+    fn default() -> Self {
+        AiCacheConfig {
+            enabled: default_cache_enabled(),
+            ttl_hours: default_cache_ttl_hours(),
+            max_size_mb: default_cache_max_size_mb(),
+        }
+    }
+}
+
+fn default_http_base_url() -> String {
+    "http://localhost:11434/v1".to_string()
+}
+
+fn default_http_timeout_secs() -> u64 {
+    120
+}
+
+impl Default for HttpProviderConfig {
+    fn default() -> Self {
+        HttpProviderConfig {
+            base_url: default_http_base_url(),
+            model: String::new(),
+            api_key_env: String::new(),
+            timeout_secs: default_http_timeout_secs(),
+            stream: false,
+        }
+    }
+}
+
+fn default_diff_budget_tokens() -> usize {
+    24_000
+}
+
+fn default_diff_max_hunk_lines() -> usize {
+    400
 }
 
 fn default_agent() -> String {
@@ -241,8 +574,27 @@ fn default_model() -> String {
 }
 
 impl AiConfig {
+    /// Resolve `agent`: an `[ai.agents]` entry of that name wins, otherwise
+    /// the built-in preset.
     pub fn get_agent(&self) -> Result<Agent, String> {
-        self.agent.as_str().try_into()
+        self.resolve_agent(&self.agent)
+    }
+
+    /// Resolve an agent CLI by name (see [`Self::get_agent`]). The `"http"`
+    /// provider is not a CLI, so it only resolves through
+    /// `ai::Provider::for_feature`.
+    pub fn resolve_agent(&self, name: &str) -> Result<Agent, String> {
+        match self.agents.get(name) {
+            Some(definition) => Ok(Agent::Custom {
+                name: name.to_string(),
+                definition: definition.clone(),
+            }),
+            None if name == HTTP_AGENT => Err(format!(
+                "The built-in '{HTTP_AGENT}' provider cannot drive an agent session; \
+                 configure an agent CLI for this feature"
+            )),
+            None => name.try_into(),
+        }
     }
 }
 
@@ -251,6 +603,15 @@ impl Default for AiConfig {
         AiConfig {
             agent: default_agent(),
             model: default_model(),
+            diff_budget_tokens: default_diff_budget_tokens(),
+            diff_max_hunk_lines: default_diff_max_hunk_lines(),
+            agents: HashMap::new(),
+            commit_agent: String::new(),
+            reviewer_agent: String::new(),
+            explain_agent: String::new(),
+            workspace_agent: String::new(),
+            http: HttpProviderConfig::default(),
+            cache: AiCacheConfig::default(),
         }
     }
 }
@@ -277,6 +638,11 @@ impl Default for Config {
             workspace: WorkspaceConfig::default(),
             pr: PrConfig::default(),
             review: ReviewConfig::default(),
+            commit: CommitConfig::default(),
+            push: PushConfig::default(),
+            sync: SyncConfig::default(),
+            land: LandConfig::default(),
+            scan: ScanConfig::default(),
         }
     }
 }
@@ -378,6 +744,18 @@ mod tests {
         assert!(restored.workspace.protected_branches.is_empty());
     }
 
+    #[test]
+    fn test_default_commit_config() {
+        let commit = CommitConfig::default();
+        assert_eq!(commit.types.len(), 10);
+        assert_eq!(commit.types[0], "feat");
+        assert!(commit.scopes.is_empty());
+        assert_eq!(commit.max_subject_length, 72);
+        assert!(commit.ticket_pattern.is_empty());
+        assert!(commit.template.is_empty());
+        assert!(!commit.lint);
+    }
+
     #[test]
     fn test_default_pr_config() {
         let pr = PrConfig::default();
@@ -386,11 +764,33 @@ mod tests {
         assert!(pr.orgs.is_empty());
     }
 
+    #[test]
+    fn test_push_config_defaults_to_confirming() {
+        assert!(!Config::default().push.strict);
+        let config: Config = toml::from_str("[push]\nstrict = true\n").unwrap();
+        assert!(config.push.strict);
+    }
+
+    #[test]
+    fn test_sync_config_defaults_to_rebase() {
+        assert_eq!(Config::default().sync.strategy, "rebase");
+        let config: Config = toml::from_str("[sync]\nstrategy = \"merge\"\n").unwrap();
+        assert_eq!(config.sync.strategy, "merge");
+    }
+
     #[test]
     fn test_default_ai_config() {
         let ai_config = AiConfig::default();
         assert_eq!(ai_config.agent, "opencode");
         assert_eq!(ai_config.model, "opencode/big-pickle");
+        assert_eq!(ai_config.diff_budget_tokens, 24_000);
+        assert_eq!(ai_config.diff_max_hunk_lines, 400);
+        assert!(ai_config.commit_agent.is_empty());
+        assert!(ai_config.reviewer_agent.is_empty());
+        assert!(ai_config.explain_agent.is_empty());
+        assert_eq!(ai_config.http.base_url, "http://localhost:11434/v1");
+        assert_eq!(ai_config.http.timeout_secs, 120);
+        assert!(!ai_config.http.stream);
     }
 
     #[test]
@@ -429,11 +829,62 @@ mod tests {
         assert!(matches!(config.get_agent(), Ok(Agent::OpenCode)));
     }
 
+    #[test]
+    fn test_ai_config_resolves_custom_agent() {
+        let config: AiConfig = toml::from_str(
+            r#"
+agent = "ollama"
+model = "llama3"
+
+[agents.ollama]
+command = "ollama"
+args = ["run", "{model}"]
+prompt_input = "stdin"
+"#,
+        )
+        .expect("parses");
+        let agent = config.get_agent().expect("custom agent resolves");
+        assert_eq!(agent.as_str(), "ollama");
+        let definition = agent.definition();
+        assert_eq!(definition.command, "ollama");
+        assert_eq!(definition.prompt_input, PromptInput::Stdin);
+        assert!(definition.interactive_args.is_none());
+    }
+
+    #[test]
+    fn test_ai_config_custom_entry_overrides_preset() {
+        let mut config = AiConfig {
+            agent: "claude".to_string(),
+            ..Default::default()
+        };
+        config.agents.insert(
+            "claude".to_string(),
+            AgentDefinition {
+                command: "/opt/claude".to_string(),
+                args: vec!["{prompt}".to_string()],
+                prompt_input: PromptInput::Argv,
+                interactive_args: None,
+            },
+        );
+        let agent = config.get_agent().unwrap();
+        assert!(matches!(agent, Agent::Custom { .. }));
+        assert_eq!(agent.definition().command, "/opt/claude");
+    }
+
+    #[test]
+    fn test_preset_definitions() {
+        let opencode = Agent::OpenCode.definition();
+        assert_eq!(opencode.command, "opencode");
+        assert_eq!(opencode.args, vec!["run", "{prompt}", "--model", "{model}"]);
+        assert_eq!(Agent::Claude.definition().args[0], "-p");
+    }
+
     #[test]
     fn test_ai_config_get_agent_invalid() {
         let config = AiConfig {
             agent: "invalid".to_string(),
             model: "test".to_string(),
+            ..Default::default()
         };
         assert!(config.get_agent().is_err());
     }
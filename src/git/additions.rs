@@ -0,0 +1,153 @@
+//! The content a change adds, file by file: what the secret scanner
+//! (`crate::secret_scan`) inspects for staged changes, uncommitted work, and
+//! the commits a push would send.
+
+use super::{GitError, get_repo};
+use std::path::Path;
+
+/// One added or modified file in a change.
+#[derive(Debug, Clone, Default)]
+pub struct Addition {
+    pub path: String,
+    /// Short id of the commit that adds it, when scanning commits.
+    pub commit: Option<String>,
+    /// Size of the new blob in bytes.
+    pub size: u64,
+    /// Added lines with their new line number (empty for binary files).
+    pub lines: Vec<(u32, String)>,
+}
+
+/// The staged changes: the index against HEAD.
+pub fn staged() -> Result<Vec<Addition>, GitError> {
+    let repo = get_repo()?;
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let diff = repo.diff_tree_to_index(head_tree.as_ref(), None, None)?;
+    collect(&repo, &diff, None)
+}
+
+/// Everything not yet committed, untracked files included: HEAD against the
+/// working tree.
+pub fn uncommitted() -> Result<Vec<Addition>, GitError> {
+    let repo = get_repo()?;
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let mut options = git2::DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    let diff = repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut options))?;
+    collect(&repo, &diff, None)
+}
+
+/// The commits in `range` (`A..B`, or a single revision), each against its
+/// first parent. Merge commits are skipped; their content arrives through
+/// their other parents.
+pub fn in_range(range: &str) -> Result<Vec<Addition>, GitError> {
+    let repo = get_repo()?;
+    if !range.contains("..") {
+        let commit = repo.revparse_single(range)?.peel_to_commit()?;
+        return commit_additions(&repo, &commit);
+    }
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_range(range)?;
+    walk(&repo, revwalk)
+}
+
+/// The commits HEAD would push: those after `upstream` (the remote-tracking
+/// commit), or, for a branch without one, those on no remote-tracking branch.
+pub fn outgoing(upstream: Option<&str>) -> Result<Vec<Addition>, GitError> {
+    let repo = get_repo()?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    match upstream {
+        Some(oid) => revwalk.hide(git2::Oid::from_str(oid)?)?,
+        None => revwalk.hide_glob("refs/remotes/*")?,
+    }
+    walk(&repo, revwalk)
+}
+
+fn walk(repo: &git2::Repository, revwalk: git2::Revwalk) -> Result<Vec<Addition>, GitError> {
+    let mut additions = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        additions.extend(commit_additions(repo, &commit)?);
+    }
+    Ok(additions)
+}
+
+fn commit_additions(
+    repo: &git2::Repository,
+    commit: &git2::Commit,
+) -> Result<Vec<Addition>, GitError> {
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let short_id = commit.as_object().short_id()?;
+    collect(repo, &diff, short_id.as_str().map(str::to_string))
+}
+
+fn collect(
+    repo: &git2::Repository,
+    diff: &git2::Diff,
+    commit: Option<String>,
+) -> Result<Vec<Addition>, GitError> {
+    let mut additions = Vec::new();
+    for idx in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(idx) else {
+            continue;
+        };
+        if delta.status() == git2::Delta::Deleted {
+            continue;
+        }
+        let Some(path) = delta.new_file().path() else {
+            continue;
+        };
+        let path = path.to_string_lossy().replace('\\', "/");
+        let size = blob_size(repo, &delta.new_file(), &path);
+
+        let mut lines = Vec::new();
+        let mut binary = delta.flags().is_binary();
+        if !binary && let Some(patch) = git2::Patch::from_diff(diff, idx)? {
+            binary = patch.delta().flags().is_binary();
+            for hunk in 0..patch.num_hunks() {
+                for line in 0..patch.num_lines_in_hunk(hunk)? {
+                    let line = patch.line_in_hunk(hunk, line)?;
+                    if line.origin() == '+' {
+                        let content = String::from_utf8_lossy(line.content());
+                        lines.push((
+                            line.new_lineno().unwrap_or_default(),
+                            content.trim_end_matches(['\n', '\r']).to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        additions.push(Addition {
+            path,
+            commit: commit.clone(),
+            size,
+            lines: if binary { Vec::new() } else { lines },
+        });
+    }
+    Ok(additions)
+}
+
+/// The new blob's size: from the object database header when it is stored,
+/// otherwise (an untracked file) from the working tree.
+fn blob_size(repo: &git2::Repository, file: &git2::DiffFile, path: &str) -> u64 {
+    if !file.id().is_zero()
+        && let Ok((size, _)) = repo.odb().and_then(|odb| odb.read_header(file.id()))
+    {
+        return size as u64;
+    }
+    repo.workdir()
+        .map(|root| root.join(Path::new(path)))
+        .and_then(|p| std::fs::metadata(p).ok())
+        .map_or(file.size(), |m| m.len())
+}
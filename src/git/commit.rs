@@ -68,6 +68,48 @@ pub fn create_commit_with_editor(initial_message: &str, amend: bool) -> Result<S
     Ok("Commit created".to_string())
 }
 
+/// Open the user's git editor (`git var GIT_EDITOR`, so `core.editor` is
+/// honored) on `initial` and return the saved message with `#` comment lines
+/// and surrounding blank lines stripped, like git's default cleanup. Used when
+/// gx has to see the message before committing (linting, templates).
+pub fn edit_message(initial: &str) -> Result<String, GitError> {
+    let repo = get_repo()?;
+    let path = repo.path().join("COMMIT_EDITMSG");
+    std::fs::write(&path, initial)?;
+
+    let editor = exec(["var", "GIT_EDITOR"], ExecOptions::capture())?;
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg("gx-editor")
+        .arg(&path)
+        .status()?;
+    if !status.success() {
+        return Err(GitError::CommandFailed {
+            stderr: format!("editor '{editor}' exited with status {status}"),
+            code: status.code(),
+        });
+    }
+
+    Ok(cleanup_message(&std::fs::read_to_string(&path)?))
+}
+
+/// The full message of the commit HEAD points at.
+pub fn head_message() -> Result<String, GitError> {
+    let repo = get_repo()?;
+    let commit = repo.head()?.peel_to_commit()?;
+    Ok(commit.message().unwrap_or("").to_string())
+}
+
+fn cleanup_message(raw: &str) -> String {
+    let lines: Vec<&str> = raw
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(str::trim_end)
+        .collect();
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
 pub fn is_valid_commit_ref(commit_ish: &str) -> bool {
     if let Ok(repo) = get_repo() {
         repo.revparse_single(commit_ish)
@@ -94,3 +136,19 @@ pub fn checkout_commit(commit_ish: &str) -> Result<String, GitError> {
 
     Ok(short_id.as_str().unwrap_or(commit_ish).to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cleanup_message_strips_comments_and_blank_edges() {
+        let raw = "\nfeat: add login  \n\nbody line\n# gx commit lint:\n#   - bad\n\n";
+        assert_eq!(cleanup_message(raw), "feat: add login\n\nbody line");
+    }
+
+    #[test]
+    fn test_cleanup_message_all_comments_is_empty() {
+        assert_eq!(cleanup_message("# nothing\n#\n"), "");
+    }
+}
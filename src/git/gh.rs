@@ -5,7 +5,8 @@
 //! one place instead of being re-implemented per call site.
 
 use serde::Deserialize;
-use std::process::Command;
+use std::io::Write;
+use std::process::{Command, Stdio};
 
 /// Failure spawning or running `gh`.
 #[derive(Debug)]
@@ -44,6 +45,61 @@ pub fn capture(args: &[&str]) -> Result<String, GhError> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Like [`capture`], but feeds `input` to `gh` on stdin (e.g. a JSON request
+/// body for `gh api --input -`).
+pub fn capture_with_input(args: &[&str], input: &str) -> Result<String, GhError> {
+    let mut child = Command::new("gh")
+        .args(args)
+        .env("GH_PROMPT_DISABLED", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => GhError::NotFound,
+            _ => GhError::Failed(e.to_string()),
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| GhError::Failed(e.to_string()))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| GhError::Failed(e.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(GhError::Failed(if stderr.is_empty() {
+            "gh exited with an error".to_string()
+        } else {
+            stderr
+        }));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Run `gh` attached to the terminal, for commands that prompt the user
+/// themselves (such as `gh pr create`). Prompts stay enabled, unlike
+/// [`capture`].
+pub fn run_attached(args: &[&str]) -> Result<(), GhError> {
+    let status = Command::new("gh")
+        .args(args)
+        .status()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => GhError::NotFound,
+            _ => GhError::Failed(e.to_string()),
+        })?;
+    if !status.success() {
+        return Err(GhError::Failed(match status.code() {
+            Some(code) => format!("gh exited with status {code}"),
+            None => "gh terminated by a signal".to_string(),
+        }));
+    }
+    Ok(())
+}
+
 /// A GitHub user handle as serialized by `gh ... --json` (`{ "login": ... }`).
 /// Shared by the PR dashboard and the reviewer-suggestion feature.
 #[derive(Deserialize, Default)]
@@ -128,7 +128,7 @@ fn exec_inner(
     Ok(output.stdout)
 }
 
-fn map_git_error(stderr: String, code: Option<i32>) -> GitError {
+pub(super) fn map_git_error(stderr: String, code: Option<i32>) -> GitError {
     if stderr.contains("fatal: not a git repository") {
         GitError::NotInRepo
     } else {
@@ -79,6 +79,15 @@ pub enum GitHubError {
         help("Check the pull-request number and that 'gh' is authenticated ('gh auth login').")
     )]
     GhFailed(String),
+
+    #[error("Could not submit the review: {0}")]
+    #[diagnostic(
+        code(gx::github::review_failed),
+        help(
+            "GitHub only accepts comments on lines inside the pull request's diff, and one pending review per reviewer."
+        )
+    )]
+    ReviewFailed(String),
 }
 
 /// Parse `query` as a GitHub reference. Returns `None` when it is a plain
@@ -170,7 +179,12 @@ pub fn resolve_branch(gh_ref: &GitHubRef) -> Result<String, GitHubError> {
     }
 }
 
-fn ensure_same_repo(origin: &(String, String), owner: &str, repo: &str) -> Result<(), GitHubError> {
+/// Error unless `owner/repo` is the `origin` remote's repository.
+pub(crate) fn ensure_same_repo(
+    origin: &(String, String),
+    owner: &str,
+    repo: &str,
+) -> Result<(), GitHubError> {
     let (origin_owner, origin_repo) = origin;
     if origin_owner.eq_ignore_ascii_case(owner) && origin_repo.eq_ignore_ascii_case(repo) {
         Ok(())
@@ -202,6 +202,28 @@ fn build_graph(
     graph_lines
 }
 
+/// The non-merge commits selected by `spec`, oldest first, as linted by `gx
+/// commit lint`. An `A..B` spec walks the range with [`get_range`] (an empty
+/// side means `HEAD`); anything else names a single commit.
+pub fn commit_messages(spec: &str) -> Result<Vec<RangeCommit>, GitError> {
+    let mut commits = match spec.split_once("..") {
+        Some((from, to)) => get_range(Some(or_head(from)), or_head(to))?,
+        None => {
+            let repo = get_repo()?;
+            let commit = repo.revparse_single(spec)?.peel_to_commit()?;
+            vec![range_commit(&commit)?]
+        }
+    };
+    commits.retain(|c| !c.is_merge);
+    commits.reverse();
+    Ok(commits)
+}
+
+/// A range side as `git log` reads it: empty means `HEAD`.
+fn or_head(rev: &str) -> &str {
+    if rev.is_empty() { "HEAD" } else { rev }
+}
+
 pub fn get_commit_details(oid: git2::Oid) -> Result<CommitDetails, GitError> {
     let repo = get_repo()?;
     let commit = repo.find_commit(oid)?;
@@ -273,3 +295,88 @@ pub struct CommitDetails {
     pub insertions: usize,
     pub deletions: usize,
 }
+
+/// A commit in a `from..to` range, with its full message for changelogs.
+#[derive(Debug, Clone)]
+pub struct RangeCommit {
+    pub oid: git2::Oid,
+    pub short_id: String,
+    pub summary: String,
+    pub message: String,
+    pub author_name: String,
+    pub is_merge: bool,
+}
+
+/// Commits reachable from `to` but not from `from` (everything reachable from
+/// `to` when `from` is `None`), newest first, like `git log from..to`.
+pub fn get_range(from: Option<&str>, to: &str) -> Result<Vec<RangeCommit>, GitError> {
+    let repo = get_repo()?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TIME | git2::Sort::TOPOLOGICAL)?;
+    revwalk.push(repo.revparse_single(to)?.peel_to_commit()?.id())?;
+    if let Some(from) = from {
+        revwalk.hide(repo.revparse_single(from)?.peel_to_commit()?.id())?;
+    }
+
+    revwalk
+        .map(|oid| range_commit(&repo.find_commit(oid?)?))
+        .collect()
+}
+
+/// The [`RangeCommit`] for `commit`.
+fn range_commit(commit: &git2::Commit) -> Result<RangeCommit, GitError> {
+    let short_id = commit
+        .as_object()
+        .short_id()?
+        .as_str()
+        .unwrap_or("")
+        .to_string();
+    Ok(RangeCommit {
+        oid: commit.id(),
+        short_id,
+        summary: commit.summary().unwrap_or("").to_string(),
+        message: commit.message().unwrap_or("").to_string(),
+        author_name: commit.author().name().unwrap_or("Unknown").to_string(),
+        is_merge: commit.parent_count() > 1,
+    })
+}
+
+/// The most recent tag reachable from `rev`, skipping a tag that points at
+/// `rev` itself so a just-tagged release still gets its own changes.
+pub fn last_tag_before(rev: &str) -> Result<Option<String>, GitError> {
+    let repo = get_repo()?;
+    let target = repo.revparse_single(rev)?.peel_to_commit()?;
+
+    let mut opts = git2::DescribeOptions::new();
+    opts.describe_tags();
+    let mut format = git2::DescribeFormatOptions::new();
+    format.abbreviated_size(0);
+
+    let describe = |commit: &git2::Commit| -> Option<String> {
+        commit
+            .as_object()
+            .describe(&opts)
+            .and_then(|d| d.format(Some(&format)))
+            .ok()
+    };
+    let Some(tag) = describe(&target) else {
+        return Ok(None);
+    };
+    if repo.revparse_single(&tag)?.peel_to_commit()?.id() != target.id() {
+        return Ok(Some(tag));
+    }
+    Ok(target.parent(0).ok().and_then(|parent| describe(&parent)))
+}
+
+/// The patch a commit introduces against its first parent (the empty tree for
+/// a root commit), as unified diff text.
+pub fn get_commit_patch(oid: git2::Oid) -> Result<String, GitError> {
+    let repo = get_repo()?;
+    let commit = repo.find_commit(oid)?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    super::staging::patch_text(&diff)
+}
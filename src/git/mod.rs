@@ -1,3 +1,4 @@
+pub mod additions;
 pub mod branch;
 pub mod commit;
 pub mod fetch;
@@ -11,9 +12,11 @@ pub mod pull_request;
 pub mod push;
 pub mod review;
 pub mod reviewers;
+pub mod split;
 pub mod staging;
 pub mod stash;
 pub mod status;
+pub mod sync;
 pub mod time;
 pub mod worktree;
 
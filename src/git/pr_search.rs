@@ -241,7 +241,7 @@ pub fn dedup_prs(
 ) -> Vec<DashboardPr> {
     let mut seen: HashSet<PrId> = HashSet::new();
     let mut out = Vec::new();
-    for pr in review_requested.into_iter().chain(authored.into_iter()) {
+    for pr in review_requested.into_iter().chain(authored) {
         if seen.insert(pr.id()) {
             out.push(pr);
         }
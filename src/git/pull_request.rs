@@ -77,11 +77,25 @@ struct PullRequestCandidate {
 pub fn list_for_worktrees(
     worktrees: &[Worktree],
 ) -> Result<HashMap<String, PullRequestSummary>, PullRequestLookupError> {
-    let branch_names: HashSet<&str> = worktrees
-        .iter()
-        .filter_map(|worktree| worktree.branch.as_deref())
-        .collect();
+    list_for_branches(
+        worktrees
+            .iter()
+            .filter_map(|worktree| worktree.branch.as_deref())
+            .collect(),
+    )
+}
+
+/// The pull request for a single branch, preferring an open one over merged
+/// or closed ones, as [`list_for_worktrees`] does.
+pub fn find_for_branch(
+    branch_name: &str,
+) -> Result<Option<PullRequestSummary>, PullRequestLookupError> {
+    Ok(list_for_branches(HashSet::from([branch_name]))?.remove(branch_name))
+}
 
+fn list_for_branches(
+    branch_names: HashSet<&str>,
+) -> Result<HashMap<String, PullRequestSummary>, PullRequestLookupError> {
     if branch_names.is_empty() {
         return Ok(HashMap::new());
     }
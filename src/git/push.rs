@@ -1,22 +1,250 @@
-use super::GitError;
 use super::branch::get_current_branch;
 use super::git_exec::{ExecOptions, exec};
+use super::{GitError, get_repo};
+use std::io::{IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
 
 #[derive(Default)]
 pub struct PushOptions {
     pub force: bool,
     pub force_dangerously: bool,
+    /// Push with `-u <remote> <branch>`, for a branch without an upstream.
+    pub set_upstream: Option<String>,
 }
 
-pub fn push(options: PushOptions) -> Result<String, GitError> {
+/// What a successful push left behind worth acting on; git's own report has
+/// already been shown as it ran.
+pub struct PushOutcome {
+    /// The "create a pull request" link the remote printed, if any.
+    pub pr_hint: Option<String>,
+}
+
+pub fn push(options: PushOptions) -> Result<PushOutcome, GitError> {
+    let branch = get_current_branch()?;
     let mut args = vec!["push".to_string()];
+    // git only reports progress to a terminal, and its stderr is a pipe here.
+    if std::io::stderr().is_terminal() {
+        args.push("--progress".to_string());
+    }
 
     if options.force_dangerously {
         args.push("--force".to_string());
     } else if options.force {
-        let branch = get_current_branch()?;
         args.push(format!("--force-with-lease={}", branch.name));
     }
+    if let Some(remote) = options.set_upstream {
+        args.extend(["-u".to_string(), remote, branch.name]);
+    }
+
+    // Run directly rather than through `exec`: git's stderr (progress, hook
+    // output, the remote's messages) is streamed as it comes, and a copy kept
+    // for the remote's pull request hint. Stdin stays the terminal's, for
+    // hooks and credential prompts.
+    let mut child = Command::new("git")
+        .args(&args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => GitError::NotFound(e),
+            _ => GitError::IoError(e),
+        })?;
+    let mut stderr = Vec::new();
+    if let Some(pipe) = child.stderr.take() {
+        tee(pipe, &mut std::io::stderr(), &mut stderr)?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        // git's own message has been shown already.
+        return Err(GitError::CommandFailed {
+            stderr: match status.code() {
+                Some(code) => format!("git push exited with status {code}"),
+                None => "git push was terminated by a signal".to_string(),
+            },
+            code: status.code(),
+        });
+    }
+
+    Ok(PushOutcome {
+        pr_hint: pr_hint_url(&String::from_utf8_lossy(&stderr)),
+    })
+}
+
+/// Copy `from` to `to` as it arrives, keeping everything read in `copy`.
+fn tee(mut from: impl Read, to: &mut impl Write, copy: &mut Vec<u8>) -> Result<(), GitError> {
+    let mut buf = [0; 4096];
+    loop {
+        let n = match from.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(GitError::IoError(e)),
+        };
+        // The terminal going away mustn't lose the copy.
+        let _ = to.write_all(&buf[..n]).and_then(|()| to.flush());
+        copy.extend_from_slice(&buf[..n]);
+    }
+}
+
+/// The remote a branch without an upstream should be pushed to:
+/// `branch.<name>.pushRemote`, then `remote.pushDefault`, then `origin`, then
+/// the only configured remote. `None` when there is nothing to choose from.
+pub fn default_push_remote(branch_name: &str) -> Result<Option<String>, GitError> {
+    let repo = get_repo()?;
+    let config = repo.config()?;
+    for key in [
+        format!("branch.{branch_name}.pushRemote"),
+        "remote.pushDefault".to_string(),
+    ] {
+        if let Ok(remote) = config.get_string(&key) {
+            return Ok(Some(remote));
+        }
+    }
+
+    let remotes = repo.remotes()?;
+    let remotes: Vec<&str> = remotes.iter().flatten().collect();
+    if remotes.contains(&"origin") {
+        return Ok(Some("origin".to_string()));
+    }
+    Ok(match remotes.as_slice() {
+        [only] => Some(only.to_string()),
+        _ => None,
+    })
+}
+
+/// Where a local branch pushes to, and what gx last fetched from there.
+pub struct Upstream {
+    pub remote: String,
+    /// The branch name on the remote (`branch.<name>.merge` without
+    /// `refs/heads/`).
+    pub branch: String,
+    /// The remote-tracking ref's commit: the force-with-lease expectation.
+    pub tracking_oid: String,
+}
 
-    exec(args, ExecOptions::default())
+pub fn upstream(branch_name: &str) -> Result<Option<Upstream>, GitError> {
+    let repo = get_repo()?;
+    let local = match repo.find_branch(branch_name, git2::BranchType::Local) {
+        Ok(b) => b,
+        Err(_) => return Ok(None),
+    };
+    let Ok(tracking) = local.upstream() else {
+        return Ok(None);
+    };
+    let refname = format!("refs/heads/{branch_name}");
+    let remote = repo.branch_upstream_remote(&refname)?;
+    let merge = repo.branch_upstream_merge(&refname)?;
+    let (Some(remote), Some(merge)) = (remote.as_str(), merge.as_str()) else {
+        return Ok(None);
+    };
+    Ok(Some(Upstream {
+        remote: remote.to_string(),
+        branch: merge
+            .strip_prefix("refs/heads/")
+            .unwrap_or(merge)
+            .to_string(),
+        tracking_oid: tracking.get().peel_to_commit()?.id().to_string(),
+    }))
+}
+
+/// The commit `branch` points at on `remote` right now (`git ls-remote`), or
+/// `None` when the remote has no such branch.
+pub fn remote_tip(remote: &str, branch: &str) -> Result<Option<String>, GitError> {
+    let refname = format!("refs/heads/{branch}");
+    let out = exec(
+        ["ls-remote", "--", remote, refname.as_str()],
+        ExecOptions::capture(),
+    )?;
+    Ok(out
+        .lines()
+        .find_map(|l| l.split_once('\t').filter(|(_, r)| *r == refname))
+        .map(|(oid, _)| oid.to_string()))
+}
+
+/// One-line summaries (`<short id> <subject>`) of the commits reachable from
+/// `oid` but not from HEAD: what a force push over `oid` would discard.
+/// `None` when `oid` is not in the local object store (never fetched).
+pub fn commits_lost_by_force(oid: &str) -> Result<Option<Vec<String>>, GitError> {
+    let repo = get_repo()?;
+    let Ok(oid) = git2::Oid::from_str(oid) else {
+        return Ok(None);
+    };
+    if repo.find_commit(oid).is_err() {
+        return Ok(None);
+    }
+    let head = repo.head()?.peel_to_commit()?.id();
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL)?;
+    revwalk.push(oid)?;
+    revwalk.hide(head)?;
+    let mut lost = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let short_id = commit.as_object().short_id()?;
+        lost.push(format!(
+            "{} {}",
+            short_id.as_str().unwrap_or_default(),
+            commit.summary().unwrap_or_default()
+        ));
+    }
+    Ok(Some(lost))
+}
+
+/// Pick the "create a pull/merge request" link out of the `remote:` lines a
+/// push prints (GitHub's `/pull/new/`, GitLab's `/merge_requests/new`,
+/// Bitbucket's `/pull-requests/new`).
+pub fn pr_hint_url(stderr: &str) -> Option<String> {
+    stderr
+        .lines()
+        .filter_map(|l| l.strip_prefix("remote:"))
+        .flat_map(str::split_whitespace)
+        .find(|word| {
+            word.starts_with("http")
+                && ["/pull/new/", "/merge_requests/new", "/pull-requests/new"]
+                    .iter()
+                    .any(|marker| word.contains(marker))
+        })
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tee_streams_and_keeps_a_copy() {
+        let progress = b"Writing objects:  50%\rWriting objects: 100%\nremote: hi\n";
+        let (mut shown, mut copy) = (Vec::new(), Vec::new());
+        tee(&progress[..], &mut shown, &mut copy).unwrap();
+        assert_eq!(shown, progress);
+        assert_eq!(copy, progress);
+    }
+
+    #[test]
+    fn test_pr_hint_url_finds_github_and_gitlab_links() {
+        let github = "\
+remote:
+remote: Create a pull request for 'feat/x' on GitHub by visiting:
+remote:      https://github.com/acme/repo/pull/new/feat/x
+remote:
+To github.com:acme/repo.git
+ * [new branch]      feat/x -> feat/x";
+        assert_eq!(
+            pr_hint_url(github).as_deref(),
+            Some("https://github.com/acme/repo/pull/new/feat/x")
+        );
+
+        let gitlab = "remote: To create a merge request for fix, visit:\n\
+remote:   https://gitlab.com/acme/repo/-/merge_requests/new?merge_request%5Bsource_branch%5D=fix";
+        assert!(pr_hint_url(gitlab).unwrap().contains("merge_requests/new"));
+    }
+
+    #[test]
+    fn test_pr_hint_url_ignores_other_output() {
+        let stderr = "To https://github.com/acme/repo/pull/new/x.git\n   abc..def  main -> main";
+        assert_eq!(pr_hint_url(stderr), None);
+        assert_eq!(pr_hint_url("remote: see https://example.com/docs"), None);
+    }
 }
@@ -1,35 +1,87 @@
 //! Serialize a finished review into an agent-ready, prompt-wrapped Markdown
-//! blob (copied to the clipboard by the "finish" action).
+//! blob (copied to the clipboard, or handed to the agent, by the "finish"
+//! action; `gx review --output` writes it for scripts).
 //!
-//! The blob leads with a wrapping instruction, then groups comments by file,
-//! each with a small diff snippet for context and the reviewer's note. Only the
-//! commented regions are included — the agent reads the rest from disk.
+//! The blob leads with a wrapping instruction and the reviewer's overall
+//! summary and whole-file comments, then groups line comments by file, each
+//! with a small diff snippet for context and the reviewer's note. Only the
+//! commented regions are included — the agent reads the rest from disk. A
+//! suggested change follows its note as a `suggestion` block plus the same
+//! change as a unified patch the agent can apply verbatim.
 
 use crate::git::review::diff::{FileDiff, RowKind};
-use crate::git::review::state::Side;
+use crate::git::review::state::{Comment, FileComment, Side, Suggestion, Summary};
+use crate::git::review::suggest;
 
 /// Lines of surrounding diff context included on each side of a comment.
 const CONTEXT: usize = 2;
 
-const PROMPT_HEADER: &str = "You are addressing code-review feedback. Each item below is a comment on a specific line of a diff. Work through every comment: make the change it asks for, or briefly explain if you disagree. Line numbers refer to the post-change (new) side unless marked (old side).";
+const PROMPT_HEADER: &str = "You are addressing code-review feedback: comments on the change as a whole, on whole files, and on specific lines of a diff. Work through every comment: make the change it asks for, or briefly explain if you disagree. Line numbers refer to the post-change (new) side unless marked (old side).";
 
-/// One comment ready to render: its file, a human location, a diff snippet, and
-/// the reviewer's note.
+/// One comment ready to render: its file, a human location, a diff snippet,
+/// the reviewer's note, and any suggested change (anchored at `start_line`).
 pub struct CommentBlock {
     pub file: String,
     pub location: String,
+    pub start_line: usize,
     pub snippet: Vec<String>,
     pub body: String,
+    pub suggestion: Option<Suggestion>,
+}
+
+/// `comments` as blocks in (file, line) order, each with a snippet from its
+/// file's diff when `diff_for` has one.
+pub fn blocks<'a>(
+    comments: &[Comment],
+    diff_for: impl Fn(&str) -> Option<&'a FileDiff>,
+) -> Vec<CommentBlock> {
+    let mut comments: Vec<&Comment> = comments.iter().collect();
+    comments.sort_by(|a, b| a.file.cmp(&b.file).then(a.start_line.cmp(&b.start_line)));
+    comments
+        .into_iter()
+        .map(|c| CommentBlock {
+            file: c.file.clone(),
+            location: location(c.side, c.start_line, c.end_line),
+            start_line: c.start_line,
+            snippet: diff_for(&c.file)
+                .map(|diff| context_lines(diff, c.side, c.start_line, c.end_line))
+                .unwrap_or_default(),
+            body: c.body.clone(),
+            suggestion: c.suggestion.clone(),
+        })
+        .collect()
 }
 
 /// Render the wrapped Markdown blob. `blocks` should already be ordered by file
 /// then line so the per-file grouping is contiguous.
-pub fn build(label: &str, blocks: &[CommentBlock]) -> String {
+pub fn build(
+    label: &str,
+    summary: &Summary,
+    file_comments: &[FileComment],
+    blocks: &[CommentBlock],
+) -> String {
     let mut out = String::new();
     out.push_str("# Code review feedback\n\n");
     out.push_str(PROMPT_HEADER);
     out.push_str(&format!("\n\n**Review range:** `{label}`\n"));
 
+    if !summary.is_empty() {
+        out.push_str("\n## Overall\n\n");
+        out.push_str(&format!("**Verdict:** {}\n", summary.verdict.label()));
+        if !summary.body.trim().is_empty() {
+            out.push_str(&format!("\n{}\n", summary.body.trim_end()));
+        }
+    }
+
+    if !file_comments.is_empty() {
+        out.push_str("\n## File comments\n");
+        let mut sorted: Vec<&FileComment> = file_comments.iter().collect();
+        sorted.sort_by(|a, b| a.file.cmp(&b.file));
+        for c in sorted {
+            out.push_str(&format!("\n### `{}`\n\n{}\n", c.file, c.body.trim_end()));
+        }
+    }
+
     let mut current_file: Option<&str> = None;
     for block in blocks {
         if current_file != Some(block.file.as_str()) {
@@ -45,7 +97,19 @@ pub fn build(label: &str, blocks: &[CommentBlock]) -> String {
             }
             out.push_str("```\n");
         }
-        out.push_str(&format!("\n{}\n", block.body.trim_end()));
+        if !block.body.trim().is_empty() {
+            out.push_str(&format!("\n{}\n", block.body.trim_end()));
+        }
+        if let Some(suggestion) = &block.suggestion {
+            out.push_str("\n**Suggested change:**\n\n```suggestion\n");
+            if !suggestion.replacement.is_empty() {
+                out.push_str(&suggestion.replacement);
+                out.push('\n');
+            }
+            out.push_str("```\n\n```diff\n");
+            out.push_str(&suggest::patch(&block.file, block.start_line, suggestion));
+            out.push_str("```\n");
+        }
     }
 
     out
@@ -110,6 +174,7 @@ pub fn context_lines(diff: &FileDiff, side: Side, start: usize, end: usize) -> V
 mod tests {
     use super::*;
     use crate::git::review::diff::{Hunk, Row};
+    use crate::git::review::state::Verdict;
     use crate::git::status::FileStatus;
 
     fn row(kind: RowKind, old: Option<usize>, new: Option<usize>, text: &str) -> Row {
@@ -135,17 +200,21 @@ mod tests {
             CommentBlock {
                 file: "a.rs".into(),
                 location: "L1".into(),
+                start_line: 1,
                 snippet: vec!["+let x = 1;".into()],
                 body: "rename x".into(),
+                suggestion: None,
             },
             CommentBlock {
                 file: "b.rs".into(),
                 location: "L9 (old side)".into(),
+                start_line: 9,
                 snippet: vec![],
                 body: "why remove this?".into(),
+                suggestion: None,
             },
         ];
-        let out = build("main...HEAD", &blocks);
+        let out = build("main...HEAD", &Summary::default(), &[], &blocks);
 
         assert!(out.contains("# Code review feedback"));
         assert!(out.contains("**Review range:** `main...HEAD`"));
@@ -154,6 +223,51 @@ mod tests {
         assert!(out.contains("rename x"));
         assert!(out.contains("why remove this?"));
         assert!(out.contains("```diff"));
+        assert!(!out.contains("## Overall"));
+        assert!(!out.contains("## File comments"));
+    }
+
+    #[test]
+    fn build_renders_summary_and_file_comments_in_their_own_sections() {
+        let summary = Summary {
+            verdict: Verdict::RequestChanges,
+            body: "split this PR".into(),
+        };
+        let files = vec![FileComment {
+            file: "b.rs".into(),
+            body: "move this to util/".into(),
+        }];
+        let out = build("main...HEAD", &summary, &files, &[]);
+
+        let overall = out.find("## Overall").unwrap();
+        let file_section = out.find("## File comments").unwrap();
+        assert!(overall < file_section);
+        assert!(out.contains("**Verdict:** Request changes"));
+        assert!(out.contains("split this PR"));
+        assert!(out.contains("### `b.rs`\n\nmove this to util/"));
+    }
+
+    #[test]
+    fn build_renders_suggestion_and_patch_after_the_note() {
+        let blocks = vec![CommentBlock {
+            file: "a.rs".into(),
+            location: "L3".into(),
+            start_line: 3,
+            snippet: vec![],
+            body: "shorter".into(),
+            suggestion: Some(Suggestion {
+                original: "let value = 1;".into(),
+                replacement: "let v = 1;".into(),
+            }),
+        }];
+        let out = build("main...HEAD", &Summary::default(), &[], &blocks);
+
+        let note = out.find("shorter").unwrap();
+        let suggestion = out.find("```suggestion\nlet v = 1;\n```").unwrap();
+        let patch = out
+            .find("--- a/a.rs\n+++ b/a.rs\n@@ -3,1 +3,1 @@\n-let value = 1;\n+let v = 1;\n```")
+            .unwrap();
+        assert!(note < suggestion && suggestion < patch);
     }
 
     #[test]
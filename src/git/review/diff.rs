@@ -11,7 +11,7 @@
 use super::range::{Endpoint, ReviewRange};
 use crate::git::status::FileStatus;
 use crate::git::{GitError, get_repo};
-use git2::{DiffFindOptions, DiffOptions, Oid};
+use git2::{DiffFindOptions, DiffOptions, ObjectType, Oid};
 use similar::{ChangeTag, TextDiff};
 
 /// Lines of unchanged context kept around each change when grouping into hunks.
@@ -62,6 +62,35 @@ pub struct FileDiff {
     pub hunks: Vec<Hunk>,
 }
 
+impl FileDiff {
+    /// The file's diff as unified patch text (`---`/`+++` header, then each
+    /// hunk), for handing to an agent. Binary and oversized files get a
+    /// one-line note instead of a body.
+    pub fn patch_text(&self) -> String {
+        let old = self.old_path.as_deref().unwrap_or(&self.path);
+        let mut out = format!("--- a/{old}\n+++ b/{}\n", self.path);
+        if self.is_binary {
+            out.push_str("(binary file changed)\n");
+        } else if self.too_large {
+            out.push_str("(file too large to diff)\n");
+        }
+        for hunk in &self.hunks {
+            out.push_str(&hunk.header);
+            out.push('\n');
+            for row in &hunk.rows {
+                out.push(match row.kind {
+                    RowKind::Context => ' ',
+                    RowKind::Added => '+',
+                    RowKind::Removed => '-',
+                });
+                out.push_str(&row.text);
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
 /// A changed file as enumerated from the range, before its hunks are built.
 /// Carries the blob oids so the per-file diff can be built later without
 /// re-running the whole tree diff.
@@ -106,6 +135,24 @@ impl ChangedFile {
         })
     }
 
+    /// The id of the content under review — the new blob, or the old one for
+    /// a deletion — so a per-file mark can tell when the file has changed. A
+    /// working-tree file libgit2 left unhashed is hashed from disk.
+    pub fn content_id(&self, to: Endpoint) -> Oid {
+        if self.status == FileStatus::Deleted {
+            return self.old_id;
+        }
+        if self.new_id.is_zero()
+            && to == Endpoint::WorkingTree
+            && let Ok(repo) = get_repo()
+            && let Some(root) = repo.workdir()
+            && let Ok(id) = Oid::hash_file(ObjectType::Blob, root.join(&self.path))
+        {
+            return id;
+        }
+        self.new_id
+    }
+
     /// Load the raw old/new bytes for this file. New content comes from the
     /// object database for committed ranges, and from the working tree when the
     /// blob isn't yet hashed (worktree / untracked).
@@ -116,9 +163,13 @@ impl ChangedFile {
     ) -> Result<(Vec<u8>, Vec<u8>), GitError> {
         let old = blob_bytes(repo, self.old_id)?;
         let new = match to {
-            Endpoint::Commit(_) => blob_bytes(repo, self.new_id)?,
+            Endpoint::Commit(_) | Endpoint::Tree(_) | Endpoint::Index => {
+                blob_bytes(repo, self.new_id)?
+            }
             Endpoint::WorkingTree => {
-                if self.new_id.is_zero() {
+                // Index → working tree diffs hash files on disk without
+                // writing their blobs, so the id may not be in the odb.
+                if self.new_id.is_zero() || !repo.odb()?.exists(self.new_id) {
                     read_workdir(repo, &self.path)?
                 } else {
                     blob_bytes(repo, self.new_id)?
@@ -128,6 +179,13 @@ impl ChangedFile {
         Ok((old, new))
     }
 
+    /// Raw old and new file contents, for rebuilding a file with only some of
+    /// its hunks applied (`gx commit --ai-split`).
+    pub fn load_bytes(&self, to: Endpoint) -> Result<(Vec<u8>, Vec<u8>), GitError> {
+        let repo = get_repo()?;
+        self.load_raw(&repo, to)
+    }
+
     /// Old and new file contents as lossy UTF-8, for whole-file syntax
     /// highlighting (the highlighter indexes lines back into the diff's rows).
     pub fn load_contents(&self, to: Endpoint) -> Result<(String, String), GitError> {
@@ -156,21 +214,38 @@ impl ChangedFile {
 pub fn changed_files(range: &ReviewRange) -> Result<Vec<ChangedFile>, GitError> {
     let repo = get_repo()?;
 
+    let mut opts = DiffOptions::new();
+    if range.to == Endpoint::WorkingTree {
+        opts.include_untracked(true)
+            .show_untracked_content(true)
+            .recurse_untracked_dirs(true);
+    }
+
     let old_tree = match range.from {
-        Some(oid) => Some(repo.find_commit(oid)?.tree()?),
+        Some(Endpoint::Commit(oid)) => Some(repo.find_commit(oid)?.tree()?),
+        Some(Endpoint::Tree(oid)) => Some(repo.find_tree(oid)?),
+        Some(Endpoint::Index) => {
+            if range.to != Endpoint::WorkingTree {
+                return Err(unsupported_range());
+            }
+            // Index → working tree: what `git diff` shows, plus untracked files.
+            None
+        }
+        Some(Endpoint::WorkingTree) => return Err(unsupported_range()),
         None => None,
     };
 
-    let mut opts = DiffOptions::new();
-    let mut diff = match range.to {
-        Endpoint::Commit(to_oid) => {
+    let mut diff = match (range.from, range.to) {
+        (Some(Endpoint::Index), _) => repo.diff_index_to_workdir(None, Some(&mut opts))?,
+        (_, Endpoint::Commit(to_oid)) => {
             let new_tree = repo.find_commit(to_oid)?.tree()?;
             repo.diff_tree_to_tree(old_tree.as_ref(), Some(&new_tree), Some(&mut opts))?
         }
-        Endpoint::WorkingTree => {
-            opts.include_untracked(true)
-                .show_untracked_content(true)
-                .recurse_untracked_dirs(true);
+        (_, Endpoint::Tree(_)) => return Err(unsupported_range()),
+        (_, Endpoint::Index) => {
+            repo.diff_tree_to_index(old_tree.as_ref(), None, Some(&mut opts))?
+        }
+        (_, Endpoint::WorkingTree) => {
             repo.diff_tree_to_workdir_with_index(old_tree.as_ref(), Some(&mut opts))?
         }
     };
@@ -209,9 +284,18 @@ pub fn changed_files(range: &ReviewRange) -> Result<Vec<ChangedFile>, GitError>
     Ok(files)
 }
 
+fn unsupported_range() -> GitError {
+    GitError::CommandFailed {
+        stderr: "a review range can only start from a commit, a tree, or the index (the index \
+                 only against the working tree), and end at a commit, the index, or the working tree"
+            .to_string(),
+        code: None,
+    }
+}
+
 /// Group the line diff into hunks with [`CONTEXT_LINES`] of surrounding context,
 /// carrying word-level emphasis ranges on changed lines.
-fn build_hunks(old: &str, new: &str) -> Vec<Hunk> {
+pub(crate) fn build_hunks(old: &str, new: &str) -> Vec<Hunk> {
     let diff = TextDiff::from_lines(old, new);
     let mut hunks = Vec::new();
 
@@ -335,7 +419,10 @@ mod tests {
     use super::*;
 
     fn rows(old: &str, new: &str) -> Vec<Row> {
-        build_hunks(old, new).into_iter().flat_map(|h| h.rows).collect()
+        build_hunks(old, new)
+            .into_iter()
+            .flat_map(|h| h.rows)
+            .collect()
     }
 
     #[test]
@@ -357,6 +444,22 @@ mod tests {
         assert_eq!(added.old_no, None);
     }
 
+    #[test]
+    fn patch_text_renders_unified_hunks() {
+        let diff = FileDiff {
+            path: "src/new.rs".to_string(),
+            old_path: Some("src/old.rs".to_string()),
+            status: FileStatus::Renamed,
+            is_binary: false,
+            too_large: false,
+            hunks: build_hunks("a\nb\n", "a\nB\n"),
+        };
+        assert_eq!(
+            diff.patch_text(),
+            "--- a/src/old.rs\n+++ b/src/new.rs\n@@ -1,2 +1,2 @@\n a\n-b\n+B\n"
+        );
+    }
+
     #[test]
     fn pure_addition_has_only_added_rows() {
         let rs = rows("", "x\ny\n");
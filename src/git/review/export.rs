@@ -0,0 +1,484 @@
+//! Machine-readable exports of a saved review (`gx review export`), and
+//! loading comments back from the JSON one (`gx review import`).
+//!
+//! The JSON document is the source format: every comment with its place in the
+//! diff, its anchor text and diff snippet, plus the range it was left on. SARIF
+//! and reviewdog's rdjsonl are derived from it for code-scanning and CI
+//! tooling. Those two describe places in the current files, so old-side
+//! comments (on removed or pre-change lines) are left out of them.
+
+use super::blob;
+use super::diff::FileDiff;
+use super::range::{Endpoint, ReviewRange};
+use super::state::{Comment, FileComment, ReviewState, Side, Suggestion, Summary, Verdict};
+use crate::git::{GitError, get_repo};
+use git2::Sort;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// The current version of the JSON document.
+const VERSION: u32 = 1;
+
+/// Tool name reported in SARIF and rdjsonl.
+const TOOL: &str = "gx review";
+
+/// A review as exported: what `gx review import` reads back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Document {
+    #[serde(default = "version")]
+    pub version: u32,
+    #[serde(default)]
+    pub range: RangeInfo,
+    #[serde(default)]
+    pub summary: Summary,
+    #[serde(default)]
+    pub comments: Vec<ExportedComment>,
+    #[serde(default)]
+    pub file_comments: Vec<FileComment>,
+}
+
+fn version() -> u32 {
+    VERSION
+}
+
+/// The range a review was left on.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RangeInfo {
+    pub label: String,
+    /// The old side: a commit or tree id, `index`, or absent for the empty tree.
+    pub from: Option<String>,
+    /// The new side: a commit id, `index`, or `working-tree`.
+    pub to: String,
+    /// The commits in the range, oldest first (none when it ends at the index
+    /// or the working tree).
+    #[serde(default)]
+    pub commits: Vec<String>,
+}
+
+/// A line comment with its context.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedComment {
+    pub file: String,
+    pub side: Side,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// The first anchored line; filled in from the diff on import when absent.
+    #[serde(default)]
+    pub anchor_text: String,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<Suggestion>,
+    /// The diff around the comment, each line prefixed with `+`, `-`, or a
+    /// space. Ignored on import.
+    #[serde(default)]
+    pub snippet: Vec<String>,
+}
+
+/// `range` as recorded in an export.
+pub fn range_info(range: &ReviewRange) -> Result<RangeInfo, GitError> {
+    let commits = match (range.from, range.to) {
+        (from @ (None | Some(Endpoint::Commit(_))), Endpoint::Commit(to)) => {
+            let repo = get_repo()?;
+            let mut walk = repo.revwalk()?;
+            walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+            walk.push(to)?;
+            if let Some(Endpoint::Commit(from)) = from {
+                walk.hide(from)?;
+            }
+            walk.map(|oid| oid.map(|oid| oid.to_string()))
+                .collect::<Result<_, _>>()?
+        }
+        _ => Vec::new(),
+    };
+    Ok(RangeInfo {
+        label: range.label.clone(),
+        from: range.from.map(endpoint),
+        to: endpoint(range.to),
+        commits,
+    })
+}
+
+fn endpoint(endpoint: Endpoint) -> String {
+    match endpoint {
+        Endpoint::Commit(oid) | Endpoint::Tree(oid) => oid.to_string(),
+        Endpoint::Index => "index".to_string(),
+        Endpoint::WorkingTree => "working-tree".to_string(),
+    }
+}
+
+/// `review` as an export of `range`, its comments in (file, line) order, each
+/// with a snippet from its file's diff when `diff_for` has one. Orphaned
+/// comments have no place in the diff and are left out.
+pub fn document<'a>(
+    range: RangeInfo,
+    review: &ReviewState,
+    diff_for: impl Fn(&str) -> Option<&'a FileDiff>,
+) -> Document {
+    let mut comments: Vec<&Comment> = review.comments.iter().collect();
+    comments.sort_by(|a, b| a.file.cmp(&b.file).then(a.start_line.cmp(&b.start_line)));
+    Document {
+        version: VERSION,
+        range,
+        summary: review.summary.clone(),
+        comments: comments
+            .into_iter()
+            .map(|c| ExportedComment {
+                file: c.file.clone(),
+                side: c.side,
+                start_line: c.start_line,
+                end_line: c.end_line,
+                anchor_text: c.anchor_text.clone(),
+                body: c.body.clone(),
+                suggestion: c.suggestion.clone(),
+                snippet: diff_for(&c.file)
+                    .map(|diff| blob::context_lines(diff, c.side, c.start_line, c.end_line))
+                    .unwrap_or_default(),
+            })
+            .collect(),
+        file_comments: review.file_comments.clone(),
+    }
+}
+
+/// Add `doc`'s comments to `review`, skipping any it already has, and return
+/// how many were added. A comment without anchor text takes it from its line
+/// in `diff_for`'s diff; the summary is taken only when `review` has none.
+/// Nothing is added when a comment's line range is invalid.
+pub fn merge<'a>(
+    doc: Document,
+    review: &mut ReviewState,
+    diff_for: impl Fn(&str) -> Option<&'a FileDiff>,
+) -> Result<usize, String> {
+    if let Some(c) = doc
+        .comments
+        .iter()
+        .find(|c| c.start_line == 0 || c.end_line < c.start_line)
+    {
+        return Err(format!(
+            "{}: invalid line range {}-{}",
+            c.file, c.start_line, c.end_line
+        ));
+    }
+
+    let mut added = 0;
+    for c in doc.comments {
+        let exists = review.comments.iter().chain(&review.orphaned).any(|e| {
+            e.file == c.file
+                && e.side == c.side
+                && e.start_line == c.start_line
+                && e.end_line == c.end_line
+                && e.body == c.body
+        });
+        if exists {
+            continue;
+        }
+        let anchor_text = if c.anchor_text.is_empty() {
+            diff_for(&c.file)
+                .and_then(|diff| line_text(diff, c.side, c.start_line))
+                .unwrap_or_default()
+        } else {
+            c.anchor_text
+        };
+        review.add(Comment {
+            file: c.file,
+            side: c.side,
+            start_line: c.start_line,
+            end_line: c.end_line,
+            anchor_text,
+            body: c.body,
+            suggestion: c.suggestion,
+            handoff: None,
+        });
+        added += 1;
+    }
+    for c in doc.file_comments {
+        if review.file_comment(&c.file).map(|e| &e.body) != Some(&c.body) {
+            review.set_file_comment(&c.file, c.body);
+            added += 1;
+        }
+    }
+    if review.summary.is_empty() {
+        review.summary = doc.summary;
+    }
+    Ok(added)
+}
+
+/// The text of `line` on `side` of `diff`, if the diff shows it.
+fn line_text(diff: &FileDiff, side: Side, line: usize) -> Option<String> {
+    diff.hunks
+        .iter()
+        .flat_map(|h| &h.rows)
+        .find(|r| match side {
+            Side::New => r.new_no,
+            Side::Old => r.old_no,
+        } == Some(line))
+        .map(|r| r.text.clone())
+}
+
+/// The document as a SARIF 2.1.0 log: one result per new-side and whole-file
+/// comment, with a suggested change as a fix.
+pub fn sarif(doc: &Document) -> Value {
+    let level = match doc.summary.verdict {
+        Verdict::RequestChanges => "warning",
+        _ => "note",
+    };
+    let result = |file: &str, region: Option<Value>, body: &str| {
+        let mut location = json!({ "artifactLocation": { "uri": file } });
+        if let Some(region) = region {
+            location["region"] = region;
+        }
+        json!({
+            "ruleId": "review-comment",
+            "level": level,
+            "message": { "text": body },
+            "locations": [{ "physicalLocation": location }],
+        })
+    };
+
+    let mut results: Vec<Value> = doc
+        .file_comments
+        .iter()
+        .map(|c| result(&c.file, None, &c.body))
+        .collect();
+    for c in doc.comments.iter().filter(|c| c.side == Side::New) {
+        let mut region = json!({ "startLine": c.start_line, "endLine": c.end_line });
+        if !c.anchor_text.is_empty() {
+            region["snippet"] = json!({ "text": c.anchor_text });
+        }
+        let mut result = result(&c.file, Some(region), &c.body);
+        if let Some(suggestion) = &c.suggestion {
+            // Whole lines, as in `rdjsonl`: a region without columns stops
+            // short of its last line's newline.
+            let deleted = json!({
+                "startLine": c.start_line,
+                "startColumn": 1,
+                "endLine": c.end_line + 1,
+                "endColumn": 1,
+            });
+            result["fixes"] = json!([{
+                "description": { "text": "Suggested change" },
+                "artifactChanges": [{
+                    "artifactLocation": { "uri": c.file },
+                    "replacements": [{
+                        "deletedRegion": deleted,
+                        "insertedContent": { "text": replacement_text(suggestion) },
+                    }],
+                }],
+            }]);
+        }
+        results.push(result);
+    }
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": {
+                "name": TOOL,
+                "rules": [{
+                    "id": "review-comment",
+                    "shortDescription": { "text": "Code review comment" },
+                }],
+            }},
+            "properties": { "range": doc.range.label, "commits": doc.range.commits },
+            "results": results,
+        }],
+    })
+}
+
+/// The document as reviewdog diagnostics, one JSON object per line, with a
+/// suggested change as a suggestion replacing the commented lines.
+pub fn rdjsonl(doc: &Document) -> String {
+    let severity = match doc.summary.verdict {
+        Verdict::RequestChanges => "WARNING",
+        _ => "INFO",
+    };
+    let diagnostic = |location: Value, body: &str| {
+        json!({
+            "message": body,
+            "location": location,
+            "severity": severity,
+            "source": { "name": TOOL },
+        })
+    };
+
+    let mut lines: Vec<Value> = doc
+        .file_comments
+        .iter()
+        .map(|c| diagnostic(json!({ "path": c.file }), &c.body))
+        .collect();
+    for c in doc.comments.iter().filter(|c| c.side == Side::New) {
+        let location = json!({
+            "path": c.file,
+            "range": {
+                "start": { "line": c.start_line },
+                "end": { "line": c.end_line },
+            },
+        });
+        let mut diagnostic = diagnostic(location, &c.body);
+        if let Some(suggestion) = &c.suggestion {
+            // Whole lines: from the first line's start to the next line's.
+            diagnostic["suggestions"] = json!([{
+                "range": {
+                    "start": { "line": c.start_line, "column": 1 },
+                    "end": { "line": c.end_line + 1, "column": 1 },
+                },
+                "text": replacement_text(suggestion),
+            }]);
+        }
+        lines.push(diagnostic);
+    }
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// A suggestion's replacement as whole lines, each ending in a newline.
+fn replacement_text(suggestion: &Suggestion) -> String {
+    if suggestion.replacement.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", suggestion.replacement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Document {
+        let comment = |side: Side, start: usize, suggestion: Option<Suggestion>| ExportedComment {
+            file: "src/lib.rs".into(),
+            side,
+            start_line: start,
+            end_line: start + 1,
+            anchor_text: "let x = 1;".into(),
+            body: "nit".into(),
+            suggestion,
+            snippet: vec!["+let x = 1;".into()],
+        };
+        Document {
+            version: VERSION,
+            range: RangeInfo {
+                label: "main...HEAD".into(),
+                from: Some("a".repeat(40)),
+                to: "b".repeat(40),
+                commits: vec!["b".repeat(40)],
+            },
+            summary: Summary::default(),
+            comments: vec![
+                comment(
+                    Side::New,
+                    4,
+                    Some(Suggestion {
+                        original: "let x = 1;\nlet y = 2;".into(),
+                        replacement: "let x = 2;".into(),
+                    }),
+                ),
+                comment(Side::Old, 9, None),
+            ],
+            file_comments: vec![FileComment {
+                file: "README.md".into(),
+                body: "update the docs".into(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_document_roundtrips_and_fills_defaults() {
+        let json = serde_json::to_string(&sample()).unwrap();
+        let back: Document = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.comments.len(), 2);
+        assert_eq!(back.comments[0].suggestion, sample().comments[0].suggestion);
+
+        let minimal: Document = serde_json::from_str(
+            r#"{"comments":[
+                {"file":"a.rs","side":"New","start_line":3,"end_line":3,"body":"hi"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(minimal.version, VERSION);
+        assert_eq!(minimal.comments[0].anchor_text, "");
+    }
+
+    #[test]
+    fn test_merge_skips_duplicates_and_rejects_bad_ranges() {
+        let mut review = ReviewState::default();
+        let doc = sample();
+        assert_eq!(merge(doc.clone(), &mut review, |_| None), Ok(3));
+        assert_eq!(review.comments.len(), 2);
+        assert_eq!(review.comments[0].anchor_text, "let x = 1;");
+        assert_eq!(merge(doc.clone(), &mut review, |_| None), Ok(0));
+
+        let mut bad = doc;
+        bad.comments[1].end_line = 3;
+        assert!(merge(bad, &mut ReviewState::default(), |_| None).is_err());
+    }
+
+    #[test]
+    fn test_sarif_skips_old_side_and_carries_fixes() {
+        let log = sarif(&sample());
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0]["locations"][0]["physicalLocation"]["region"].is_null());
+
+        let region = &results[1]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 4);
+        assert_eq!(region["endLine"], 5);
+        let replacement = &results[1]["fixes"][0]["artifactChanges"][0]["replacements"][0];
+        assert_eq!(replacement["insertedContent"]["text"], "let x = 2;\n");
+        assert_eq!(log["runs"][0]["properties"]["range"], "main...HEAD");
+    }
+
+    /// Apply a SARIF replacement the way a consumer does: 1-based lines and
+    /// columns, the end exclusive.
+    fn apply_replacement(content: &str, replacement: &Value) -> String {
+        let offset = |line: &Value, column: &Value| {
+            let (line, column) = (line.as_u64().unwrap(), column.as_u64().unwrap());
+            let start: usize = content
+                .split_inclusive('\n')
+                .take(line as usize - 1)
+                .map(str::len)
+                .sum();
+            start + column as usize - 1
+        };
+        let region = &replacement["deletedRegion"];
+        let start = offset(&region["startLine"], &region["startColumn"]);
+        let end = offset(&region["endLine"], &region["endColumn"]);
+        let inserted = replacement["insertedContent"]["text"].as_str().unwrap();
+        format!("{}{inserted}{}", &content[..start], &content[end..])
+    }
+
+    #[test]
+    fn test_sarif_fix_replaces_whole_lines() {
+        let content = "fn f() {\n    let x = 1;\n    let y = 2;\n}\n";
+        let fix = |replacement: &str| {
+            let mut doc = sample();
+            doc.comments[0].start_line = 2;
+            doc.comments[0].end_line = 3;
+            doc.comments[0].suggestion = Some(Suggestion {
+                original: "    let x = 1;\n    let y = 2;".into(),
+                replacement: replacement.into(),
+            });
+            let log = sarif(&doc);
+            let result = &log["runs"][0]["results"][1];
+            apply_replacement(
+                content,
+                &result["fixes"][0]["artifactChanges"][0]["replacements"][0],
+            )
+        };
+        assert_eq!(fix("    let x = 3;"), "fn f() {\n    let x = 3;\n}\n");
+        assert_eq!(fix(""), "fn f() {\n}\n");
+    }
+
+    #[test]
+    fn test_rdjsonl_is_one_diagnostic_per_line() {
+        let out = rdjsonl(&sample());
+        let lines: Vec<Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["location"]["path"], "README.md");
+        assert_eq!(lines[1]["location"]["range"]["start"]["line"], 4);
+        assert_eq!(lines[1]["suggestions"][0]["range"]["end"]["line"], 6);
+        assert_eq!(lines[1]["severity"], "INFO");
+    }
+}
@@ -0,0 +1,116 @@
+//! Handing a finished review straight to the coding agent, and telling on the
+//! next open which commented lines it has changed since.
+//!
+//! At hand-off each new-side comment records its anchored lines, as long as
+//! the working tree (where the agent works) has them. On the next open, a
+//! comment whose lines are no longer there — neither at the anchor nor moved
+//! elsewhere — counts as touched. Old-side lines are history; the agent
+//! can't change them, so those comments are never tracked.
+
+use super::diff::ChangedFile;
+use super::range::Endpoint;
+use super::state::{Comment, Handoff, ReviewState, Side};
+use super::suggest::Text;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Record the anchored lines of every comment in `review`, replacing any
+/// earlier hand-off. `to` is the range's new side, `root` the working tree.
+pub fn mark(review: &mut ReviewState, files: &[ChangedFile], to: Endpoint, root: &Path) {
+    let mut texts: HashMap<String, Option<(Text, Text)>> = HashMap::new();
+    for c in &mut review.comments {
+        let pair = texts.entry(c.file.clone()).or_insert_with(|| {
+            let file = files.iter().find(|f| f.path == c.file)?;
+            let (_, reviewed) = file.load_contents(to).ok()?;
+            let worktree = std::fs::read_to_string(root.join(&c.file)).ok()?;
+            Some((Text::new(&reviewed), Text::new(&worktree)))
+        });
+        c.handoff = pair
+            .as_ref()
+            .and_then(|(reviewed, worktree)| snapshot(c, reviewed, worktree));
+    }
+}
+
+/// Work out which handed-off comments (placed or orphaned) the agent has
+/// touched, returning how many were handed off and how many of those changed.
+pub fn check(review: &mut ReviewState, root: &Path) -> (usize, usize) {
+    let mut worktrees: HashMap<String, Option<Text>> = HashMap::new();
+    let (mut handed, mut touched) = (0, 0);
+    for c in review.comments.iter_mut().chain(&mut review.orphaned) {
+        let Some(handoff) = c.handoff.as_mut() else {
+            continue;
+        };
+        let worktree = worktrees.entry(c.file.clone()).or_insert_with(|| {
+            let content = std::fs::read_to_string(root.join(&c.file)).ok()?;
+            Some(Text::new(&content))
+        });
+        handoff.touched = is_touched(worktree.as_ref(), handoff, c.start_line);
+        handed += 1;
+        touched += usize::from(handoff.touched);
+    }
+    (handed, touched)
+}
+
+/// The comment's lines on the reviewed side, when the working tree has them
+/// too (otherwise a later difference wouldn't be the agent's doing).
+fn snapshot(c: &Comment, reviewed: &Text, worktree: &Text) -> Option<Handoff> {
+    if c.side != Side::New {
+        return None;
+    }
+    let lines = reviewed.lines(c.start_line, c.end_line)?;
+    worktree.find(&lines, c.start_line)?;
+    Some(Handoff {
+        lines,
+        touched: false,
+    })
+}
+
+/// A deleted file counts as touched.
+fn is_touched(worktree: Option<&Text>, handoff: &Handoff, start: usize) -> bool {
+    worktree.is_none_or(|text| text.find(&handoff.lines, start).is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(side: Side, start: usize, end: usize) -> Comment {
+        Comment {
+            file: "a.rs".into(),
+            side,
+            start_line: start,
+            end_line: end,
+            anchor_text: String::new(),
+            body: "fix".into(),
+            suggestion: None,
+            handoff: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_needs_new_side_lines_present_in_the_worktree() {
+        let reviewed = Text::new("a\nb\nc\n");
+        let handoff = snapshot(&comment(Side::New, 2, 3), &reviewed, &reviewed).unwrap();
+        assert_eq!(handoff.lines, "b\nc");
+
+        assert!(snapshot(&comment(Side::Old, 2, 2), &reviewed, &reviewed).is_none());
+        let edited = Text::new("a\nB\nc\n");
+        assert!(snapshot(&comment(Side::New, 2, 2), &reviewed, &edited).is_none());
+    }
+
+    #[test]
+    fn test_touched_when_lines_are_edited_but_not_when_moved() {
+        let handoff = snapshot(
+            &comment(Side::New, 2, 2),
+            &Text::new("a\nb\nc\n"),
+            &Text::new("a\nb\nc\n"),
+        )
+        .unwrap();
+        let touched = |content: &str| is_touched(Some(&Text::new(content)), &handoff, 2);
+
+        assert!(!touched("a\nb\nc\n"));
+        assert!(!touched("x\ny\na\nb\nc\n"));
+        assert!(touched("a\nbetter b\nc\n"));
+        assert!(is_touched(None, &handoff, 2));
+    }
+}
@@ -4,5 +4,11 @@
 
 pub mod blob;
 pub mod diff;
+pub mod export;
+pub mod handoff;
+pub mod pull;
 pub mod range;
+pub mod since;
+pub mod stage;
 pub mod state;
+pub mod suggest;
@@ -0,0 +1,575 @@
+//! Reviewing a GitHub pull request: fetching its head and base so the review
+//! covers exactly the PR's diff, and sending the finished review back to GitHub
+//! as line-anchored review comments.
+//!
+//! The range is `merge-base(base, head) → head`, the same diff GitHub shows on
+//! the PR's "Files changed" tab. Submission goes through
+//! `gh api repos/<owner>/<repo>/pulls/<number>/reviews`; the summary and
+//! whole-file comments become the review's body, the verdict its event, and a
+//! suggested change GitHub's own `suggestion` block. The PR's existing review
+//! threads are read (and replied to) through the GraphQL API, the only one that
+//! reports whether a thread is resolved.
+
+use super::range::{Endpoint, ReviewRange};
+use super::state::{Comment, Reply, ReviewState, Side, Verdict};
+use crate::git::git_exec::{ExecOptions, exec};
+use crate::git::github::{self, GitHubError, GitHubRef};
+use crate::git::{GitError, get_repo, gh};
+use git2::Oid;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+/// The pull request a review range was resolved from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequest {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    /// The head commit the review covers; comments are anchored to it.
+    pub head: Oid,
+}
+
+/// How a review is handed to GitHub.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Submission {
+    /// A pending review, visible only to the reviewer until submitted on GitHub.
+    Pending,
+    /// A submitted review, with the reviewer's verdict.
+    Submit,
+}
+
+/// An existing review thread on the pull request, shown read-only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thread {
+    /// GraphQL node id, the target of a reply.
+    pub id: String,
+    pub path: String,
+    pub side: Side,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub resolved: bool,
+    /// The thread's lines changed after it was left; it is placed at its
+    /// original position, which may no longer match the code.
+    pub outdated: bool,
+    pub comments: Vec<ThreadComment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadComment {
+    pub author: String,
+    pub body: String,
+}
+
+const THREADS_QUERY: &str = "query($owner: String!, $name: String!, $number: Int!) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) {
+        nodes {
+          id isResolved isOutdated path diffSide
+          line startLine originalLine originalStartLine
+          comments(first: 100) { nodes { author { login } body } }
+        }
+      }
+    }
+  }
+}";
+
+const REPLY_MUTATION: &str = "mutation($review: ID!, $thread: ID!, $body: String!) {
+  addPullRequestReviewThreadReply(input: {
+    pullRequestReviewId: $review, pullRequestReviewThreadId: $thread, body: $body
+  }) { comment { id } }
+}";
+
+#[derive(Deserialize)]
+struct RawPull {
+    #[serde(rename = "baseRefName")]
+    base_ref_name: String,
+    #[serde(rename = "headRefName")]
+    head_ref_name: String,
+}
+
+/// Resolve a pull-request reference (`#123` or a PR URL) into a review range.
+/// The PR must belong to `origin`; its head (`refs/pull/<n>/head`, so PRs from
+/// forks work too) is fetched from there into `refs/gx/pull/<n>`, and its base
+/// branch into `origin/<base>`.
+pub fn resolve(gh_ref: &GitHubRef) -> Result<ReviewRange, GitHubError> {
+    let origin = github::origin_owner_repo()?.ok_or(GitHubError::NoOrigin)?;
+    let (owner, repo, number) = match gh_ref {
+        GitHubRef::Pull {
+            owner,
+            repo,
+            number,
+        } => {
+            github::ensure_same_repo(&origin, owner, repo)?;
+            (owner.clone(), repo.clone(), *number)
+        }
+        GitHubRef::PullShort { number } => (origin.0, origin.1, *number),
+        GitHubRef::Tree { owner, repo, .. } => {
+            return Err(GitHubError::GhFailed(format!(
+                "'{owner}/{repo}' branch URLs are not pull requests; pass the PR URL or '#<number>'"
+            )));
+        }
+    };
+    let slug = format!("{owner}/{repo}");
+
+    let stdout = gh::capture(&[
+        "pr",
+        "view",
+        &number.to_string(),
+        "--repo",
+        &slug,
+        "--json",
+        "baseRefName,headRefName",
+    ])
+    .map_err(|e| match e {
+        gh::GhError::NotFound => GitHubError::GhNotFound,
+        gh::GhError::Failed(detail) => {
+            GitHubError::GhFailed(format!("#{number} in '{slug}': {detail}"))
+        }
+    })?;
+    let pull: RawPull = serde_json::from_str(stdout.trim()).map_err(|e| {
+        GitHubError::GhFailed(format!("#{number} in '{slug}': unexpected gh output ({e})"))
+    })?;
+
+    // Named destinations rather than FETCH_HEAD, which any concurrent fetch
+    // (an editor's background one, say) can overwrite under us.
+    let head_ref = format!("refs/gx/pull/{number}");
+    let base_ref = format!("refs/remotes/origin/{}", pull.base_ref_name);
+    exec(
+        [
+            "fetch",
+            "--no-tags",
+            "origin",
+            &format!("+refs/pull/{number}/head:{head_ref}"),
+            &format!("+refs/heads/{}:{base_ref}", pull.base_ref_name),
+        ],
+        ExecOptions::silent(),
+    )?;
+
+    let git_repo = get_repo()?;
+    let head = git_repo
+        .revparse_single(&head_ref)
+        .and_then(|o| o.peel_to_commit())
+        .map_err(GitError::from)?
+        .id();
+    let base = git_repo
+        .revparse_single(&base_ref)
+        .and_then(|o| o.peel_to_commit())
+        .map_err(GitError::from)?
+        .id();
+    let merge_base = git_repo.merge_base(base, head).map_err(GitError::from)?;
+
+    Ok(ReviewRange {
+        from: Some(Endpoint::Commit(merge_base)),
+        to: Endpoint::Commit(head),
+        label: format!("#{number} {}...{}", pull.base_ref_name, pull.head_ref_name),
+        scope_id: format!("pr:{owner}/{repo}#{number}"),
+        pull: Some(PullRequest {
+            owner,
+            repo,
+            number,
+            head,
+        }),
+    })
+}
+
+/// Fetch the pull request's review threads (the first 100).
+pub fn threads(pr: &PullRequest) -> Result<Vec<Thread>, GitHubError> {
+    let stdout = gh::capture(&[
+        "api",
+        "graphql",
+        "-f",
+        &format!("query={THREADS_QUERY}"),
+        "-f",
+        &format!("owner={}", pr.owner),
+        "-f",
+        &format!("name={}", pr.repo),
+        "-F",
+        &format!("number={}", pr.number),
+    ])
+    .map_err(|e| match e {
+        gh::GhError::NotFound => GitHubError::GhNotFound,
+        gh::GhError::Failed(detail) => GitHubError::GhFailed(format!("#{}: {detail}", pr.number)),
+    })?;
+    parse_threads(&stdout)
+        .map_err(|e| GitHubError::GhFailed(format!("#{}: unexpected gh output ({e})", pr.number)))
+}
+
+/// Decode the review-threads GraphQL response. An outdated thread has no
+/// current `line`, so it falls back to where it was left; a thread on a whole
+/// file (no line at all) is skipped.
+fn parse_threads(json: &str) -> Result<Vec<Thread>, serde_json::Error> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RawThread {
+        id: String,
+        is_resolved: bool,
+        is_outdated: bool,
+        path: String,
+        diff_side: Option<String>,
+        line: Option<usize>,
+        start_line: Option<usize>,
+        original_line: Option<usize>,
+        original_start_line: Option<usize>,
+        comments: Nodes<RawThreadComment>,
+    }
+    #[derive(Deserialize)]
+    struct RawThreadComment {
+        author: Option<RawAuthor>,
+        body: String,
+    }
+    #[derive(Deserialize)]
+    struct RawAuthor {
+        login: String,
+    }
+    #[derive(Deserialize)]
+    struct Nodes<T> {
+        nodes: Vec<T>,
+    }
+
+    let value: Value = serde_json::from_str(json.trim())?;
+    let raw: Nodes<RawThread> = serde_json::from_value(
+        value["data"]["repository"]["pullRequest"]["reviewThreads"].clone(),
+    )?;
+
+    Ok(raw
+        .nodes
+        .into_iter()
+        .filter_map(|t| {
+            let (end_line, start_line) = match t.line {
+                Some(line) => (line, t.start_line),
+                None => (t.original_line?, t.original_start_line),
+            };
+            Some(Thread {
+                id: t.id,
+                path: t.path,
+                side: match t.diff_side.as_deref() {
+                    Some("LEFT") => Side::Old,
+                    _ => Side::New,
+                },
+                start_line: start_line.unwrap_or(end_line).min(end_line),
+                end_line,
+                resolved: t.is_resolved,
+                outdated: t.is_outdated,
+                comments: t
+                    .comments
+                    .nodes
+                    .into_iter()
+                    .map(|c| ThreadComment {
+                        author: c.author.map_or_else(|| "ghost".to_string(), |a| a.login),
+                        body: c.body,
+                    })
+                    .collect(),
+            })
+        })
+        .collect())
+}
+
+/// The request body for creating `review`. Old-side anchors map to GitHub's
+/// `LEFT`, new-side ones to `RIGHT`; a multi-line comment spans
+/// `start_line`..`line`. Leaving out `event` makes the review pending.
+pub fn review_payload(pr: &PullRequest, review: &ReviewState, submission: Submission) -> Value {
+    let comments: Vec<Value> = review
+        .comments
+        .iter()
+        .map(|c| {
+            let side = match c.side {
+                Side::Old => "LEFT",
+                Side::New => "RIGHT",
+            };
+            let mut comment = json!({
+                "path": c.file,
+                "body": comment_body(c),
+                "side": side,
+                "line": c.end_line,
+            });
+            if c.end_line > c.start_line {
+                comment["start_line"] = json!(c.start_line);
+                comment["start_side"] = json!(side);
+            }
+            comment
+        })
+        .collect();
+
+    let mut payload = json!({
+        "commit_id": pr.head.to_string(),
+        "comments": comments,
+    });
+    let body = review_body(review);
+    if !body.is_empty() {
+        payload["body"] = json!(body);
+    }
+    if submission == Submission::Submit {
+        payload["event"] = json!(event(review.summary.verdict));
+    }
+    payload
+}
+
+/// A line comment's body, with any suggested change as a `suggestion` block
+/// GitHub offers to commit.
+fn comment_body(c: &Comment) -> String {
+    let Some(suggestion) = &c.suggestion else {
+        return c.body.clone();
+    };
+    let mut body = c.body.trim_end().to_string();
+    if !body.is_empty() {
+        body.push_str("\n\n");
+    }
+    body.push_str("```suggestion\n");
+    if !suggestion.replacement.is_empty() {
+        body.push_str(&suggestion.replacement);
+        body.push('\n');
+    }
+    body.push_str("```");
+    body
+}
+
+/// The review's top-level body: the summary, then each whole-file comment
+/// (GitHub reviews have no file-level comments of their own).
+fn review_body(review: &ReviewState) -> String {
+    let mut parts = Vec::new();
+    if !review.summary.body.trim().is_empty() {
+        parts.push(review.summary.body.trim_end().to_string());
+    }
+    let mut files: Vec<_> = review.file_comments.iter().collect();
+    files.sort_by(|a, b| a.file.cmp(&b.file));
+    for c in files {
+        parts.push(format!("**`{}`**: {}", c.file, c.body.trim_end()));
+    }
+    parts.join("\n\n")
+}
+
+fn event(verdict: Verdict) -> &'static str {
+    match verdict {
+        Verdict::Comment => "COMMENT",
+        Verdict::Approve => "APPROVE",
+        Verdict::RequestChanges => "REQUEST_CHANGES",
+    }
+}
+
+/// Create the review on GitHub and return its URL. Replies to existing
+/// threads join the same review: it is created pending, the replies are added
+/// to it, and only then is it submitted when `submission` asks for that. If a
+/// reply or the submission fails, the pending review is deleted again.
+pub fn submit(
+    pr: &PullRequest,
+    review: &ReviewState,
+    submission: Submission,
+) -> Result<String, GitHubError> {
+    let endpoint = format!("repos/{}/{}/pulls/{}/reviews", pr.owner, pr.repo, pr.number);
+    let replies = &review.replies;
+    let create_as = if replies.is_empty() {
+        submission
+    } else {
+        Submission::Pending
+    };
+    let payload = review_payload(pr, review, create_as).to_string();
+    let response = api_with_input(&["--method", "POST", &endpoint], &payload)?;
+
+    if !replies.is_empty() {
+        let review_id = response["id"].as_u64().ok_or_else(|| missing_field("id"))?;
+        let review_endpoint = format!("{endpoint}/{review_id}");
+        let completed = add_replies(&response, replies).and_then(|()| {
+            if submission == Submission::Submit {
+                api_with_input(
+                    &["--method", "POST", &format!("{review_endpoint}/events")],
+                    &json!({ "event": event(review.summary.verdict) }).to_string(),
+                )?;
+            }
+            Ok(())
+        });
+        if let Err(e) = completed {
+            // Don't leave a half-built pending review behind: GitHub allows
+            // one per reviewer, so it would block the retry. The comments are
+            // still saved locally.
+            let _ = gh::capture(&["api", "--method", "DELETE", &review_endpoint]);
+            return Err(e);
+        }
+    }
+
+    Ok(response["html_url"]
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            format!(
+                "https://github.com/{}/{}/pull/{}",
+                pr.owner, pr.repo, pr.number
+            )
+        }))
+}
+
+/// Post each reply to its thread, as part of the pending review `response`
+/// describes.
+fn add_replies(response: &Value, replies: &[Reply]) -> Result<(), GitHubError> {
+    let review_node = response["node_id"]
+        .as_str()
+        .ok_or_else(|| missing_field("node_id"))?;
+    for reply in replies {
+        gh::capture(&[
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={REPLY_MUTATION}"),
+            "-f",
+            &format!("review={review_node}"),
+            "-f",
+            &format!("thread={}", reply.thread_id),
+            "-f",
+            &format!("body={}", reply.body),
+        ])
+        .map_err(review_error)?;
+    }
+    Ok(())
+}
+
+fn missing_field(field: &str) -> GitHubError {
+    GitHubError::ReviewFailed(format!(
+        "GitHub's response to creating the review has no '{field}'"
+    ))
+}
+
+/// `gh api <args> --input -` with `body`, decoding the JSON response.
+fn api_with_input(args: &[&str], body: &str) -> Result<Value, GitHubError> {
+    let args: Vec<&str> = ["api"]
+        .into_iter()
+        .chain(args.iter().copied())
+        .chain(["--input", "-"])
+        .collect();
+    let stdout = gh::capture_with_input(&args, body).map_err(review_error)?;
+    Ok(serde_json::from_str(stdout.trim()).unwrap_or_default())
+}
+
+fn review_error(e: gh::GhError) -> GitHubError {
+    match e {
+        gh::GhError::NotFound => GitHubError::GhNotFound,
+        gh::GhError::Failed(detail) => GitHubError::ReviewFailed(detail),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::review::state::Suggestion;
+
+    fn pr() -> PullRequest {
+        PullRequest {
+            owner: "acme".to_string(),
+            repo: "widgets".to_string(),
+            number: 7,
+            head: Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap(),
+        }
+    }
+
+    fn review(comments: Vec<Comment>) -> ReviewState {
+        ReviewState {
+            comments,
+            ..ReviewState::default()
+        }
+    }
+
+    fn comment(side: Side, start_line: usize, end_line: usize) -> Comment {
+        Comment {
+            file: "src/lib.rs".to_string(),
+            side,
+            start_line,
+            end_line,
+            anchor_text: String::new(),
+            body: "nit".to_string(),
+            suggestion: None,
+            handoff: None,
+        }
+    }
+
+    #[test]
+    fn test_review_payload_maps_sides_and_ranges() {
+        let payload = review_payload(
+            &pr(),
+            &review(vec![comment(Side::New, 4, 4), comment(Side::Old, 10, 12)]),
+            Submission::Pending,
+        );
+        assert_eq!(
+            payload,
+            json!({
+                "commit_id": "0123456789abcdef0123456789abcdef01234567",
+                "comments": [
+                    {"path": "src/lib.rs", "body": "nit", "side": "RIGHT", "line": 4},
+                    {
+                        "path": "src/lib.rs",
+                        "body": "nit",
+                        "side": "LEFT",
+                        "line": 12,
+                        "start_line": 10,
+                        "start_side": "LEFT"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_review_payload_submitted_sets_event() {
+        let mut review = review(vec![comment(Side::New, 1, 1)]);
+        let payload = review_payload(&pr(), &review, Submission::Submit);
+        assert_eq!(payload["event"], "COMMENT");
+
+        review.summary.verdict = Verdict::RequestChanges;
+        let payload = review_payload(&pr(), &review, Submission::Submit);
+        assert_eq!(payload["event"], "REQUEST_CHANGES");
+        assert!(review_payload(&pr(), &review, Submission::Pending)["event"].is_null());
+    }
+
+    #[test]
+    fn test_review_payload_appends_suggestion_block() {
+        let mut c = comment(Side::New, 4, 4);
+        c.suggestion = Some(Suggestion {
+            original: "let x = 1;".into(),
+            replacement: "let x = 2;".into(),
+        });
+        let payload = review_payload(&pr(), &review(vec![c]), Submission::Pending);
+        assert_eq!(
+            payload["comments"][0]["body"],
+            "nit\n\n```suggestion\nlet x = 2;\n```"
+        );
+    }
+
+    #[test]
+    fn test_review_payload_body_has_summary_then_file_comments() {
+        let mut review = review(Vec::new());
+        assert!(review_payload(&pr(), &review, Submission::Pending)["body"].is_null());
+
+        review.summary.body = "Overall: split this PR.".into();
+        review.set_file_comment("src/old.rs", "Move to src/new/.".into());
+        let payload = review_payload(&pr(), &review, Submission::Pending);
+        assert_eq!(
+            payload["body"],
+            "Overall: split this PR.\n\n**`src/old.rs`**: Move to src/new/."
+        );
+    }
+
+    #[test]
+    fn test_parse_threads_places_current_and_outdated_threads() {
+        let json = r#"{"data":{"repository":{"pullRequest":{"reviewThreads":{"nodes":[
+            {"id":"T1","isResolved":false,"isOutdated":false,"path":"a.rs","diffSide":"RIGHT",
+             "line":12,"startLine":10,"originalLine":12,"originalStartLine":10,
+             "comments":{"nodes":[{"author":{"login":"octo"},"body":"why?"},
+                                  {"author":null,"body":"gone"}]}},
+            {"id":"T2","isResolved":true,"isOutdated":true,"path":"b.rs","diffSide":"LEFT",
+             "line":null,"startLine":null,"originalLine":7,"originalStartLine":null,
+             "comments":{"nodes":[]}},
+            {"id":"T3","isResolved":false,"isOutdated":false,"path":"c.rs","diffSide":"RIGHT",
+             "line":null,"startLine":null,"originalLine":null,"originalStartLine":null,
+             "comments":{"nodes":[]}}
+        ]}}}}}"#;
+        let threads = parse_threads(json).unwrap();
+        assert_eq!(threads.len(), 2);
+
+        assert_eq!(threads[0].side, Side::New);
+        assert_eq!((threads[0].start_line, threads[0].end_line), (10, 12));
+        assert_eq!(threads[0].comments[0].author, "octo");
+        assert_eq!(threads[0].comments[1].author, "ghost");
+
+        assert_eq!(threads[1].side, Side::Old);
+        assert_eq!((threads[1].start_line, threads[1].end_line), (7, 7));
+        assert!(threads[1].resolved && threads[1].outdated);
+    }
+}
@@ -1,43 +1,59 @@
 //! Resolving the diff range a `gx review` session targets.
 //!
-//! A review compares a "from" side (the tree of some commit, or the empty tree
-//! for a root commit) against a "to" side (another commit, or the working
-//! tree). Three modes are supported:
+//! A review compares a "from" side (the tree of some commit, the index, or the
+//! empty tree for a root commit) against a "to" side (another commit, the
+//! index, or the working tree). These modes are supported:
 //!
 //! - **Branch** (default): `merge-base(base, HEAD) → HEAD`, i.e. what this
 //!   branch adds over its base (origin's default branch unless overridden).
 //! - **Commit**: a single commit (`<commit>^..<commit>`) or an explicit
 //!   `A..B` / `A...B` range.
 //! - **Uncommitted**: the working tree against `HEAD`.
+//! - **Staged**: the index against `HEAD` — exactly what would be committed.
+//! - **Unstaged**: the working tree against the index.
+//!
+//! A GitHub pull request (`#123` or a PR URL) resolves through the `pull`
+//! unit to the PR's own diff, and "changes since the last review" through the
+//! `since` unit.
 //!
 //! Each resolved range also carries a `scope_id` used as the per-branch
-//! persistence key (see the `state` unit): `branch:<name>`, `commit:<oid…>`, or
-//! `worktree`.
+//! persistence key (see the `state` unit): `branch:<name>`, `commit:<oid…>`,
+//! `pr:<owner>/<repo>#<n>`, `worktree`, `staged`, or `unstaged`.
 
+use super::pull::PullRequest;
 use crate::git::{GitError, branch, get_repo};
 use git2::Oid;
 
-/// The "to" side of a review range.
+/// One side of a review range.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Endpoint {
-    /// Compare against this commit's tree.
+    /// This commit's tree.
     Commit(Oid),
-    /// Compare against the current working tree (index + unstaged).
+    /// A tree with no commit of its own (e.g. the last-reviewed tip replayed
+    /// onto a new base; see the `since` unit). Only valid as the "from" side.
+    Tree(Oid),
+    /// The index (staged content).
+    Index,
+    /// The current working tree (index + unstaged). Only valid as the "to"
+    /// side.
     WorkingTree,
 }
 
 /// A fully resolved review range, ready for the diff builder to consume.
 #[derive(Debug, Clone)]
 pub struct ReviewRange {
-    /// Commit whose tree is the "old" side. `None` means the empty tree (a root
-    /// commit has no parent to diff against).
-    pub from: Option<Oid>,
+    /// The "old" side: a commit's tree or the index. `None` means the empty
+    /// tree (a root commit has no parent to diff against).
+    pub from: Option<Endpoint>,
     pub to: Endpoint,
     /// Human-readable description for the header (e.g. `origin/main...HEAD`).
     pub label: String,
     /// Stable per-review key fragment, combined with the clone identity for
-    /// persistence: `branch:<name>`, `commit:<oid>`, or `worktree`.
+    /// persistence: `branch:<name>`, `commit:<oid>`, `pr:<owner>/<repo>#<n>`,
+    /// `worktree`, `staged`, or `unstaged`.
     pub scope_id: String,
+    /// The GitHub pull request under review, when the range came from one.
+    pub pull: Option<PullRequest>,
 }
 
 /// How a positional `target` argument is interpreted.
@@ -98,10 +114,11 @@ pub fn resolve_branch(base: Option<String>) -> Result<ReviewRange, GitError> {
     let merge_base = repo.merge_base(base_oid, head_oid)?;
 
     Ok(ReviewRange {
-        from: Some(merge_base),
+        from: Some(Endpoint::Commit(merge_base)),
         to: Endpoint::Commit(head_oid),
         label: format!("{base_ref}...HEAD"),
         scope_id: branch_scope_id(&repo, head_oid)?,
+        pull: None,
     })
 }
 
@@ -110,7 +127,7 @@ pub fn resolve_commit(reference: &str) -> Result<ReviewRange, GitError> {
     let repo = get_repo()?;
     let commit = repo.revparse_single(reference)?.peel_to_commit()?;
     let to_oid = commit.id();
-    let from = commit.parent(0).ok().map(|p| p.id());
+    let from = commit.parent(0).ok().map(|p| Endpoint::Commit(p.id()));
     let sh = short_oid(&repo, to_oid);
 
     Ok(ReviewRange {
@@ -118,6 +135,7 @@ pub fn resolve_commit(reference: &str) -> Result<ReviewRange, GitError> {
         to: Endpoint::Commit(to_oid),
         label: format!("{sh}^..{sh}"),
         scope_id: format!("commit:{to_oid}"),
+        pull: None,
     })
 }
 
@@ -128,10 +146,11 @@ pub fn resolve_explicit_range(from_ref: &str, to_ref: &str) -> Result<ReviewRang
     let to_oid = repo.revparse_single(to_ref)?.peel_to_commit()?.id();
 
     Ok(ReviewRange {
-        from: Some(from_oid),
+        from: Some(Endpoint::Commit(from_oid)),
         to: Endpoint::Commit(to_oid),
         label: format!("{from_ref}..{to_ref}"),
         scope_id: format!("commit:{from_oid}-{to_oid}"),
+        pull: None,
     })
 }
 
@@ -141,10 +160,36 @@ pub fn resolve_uncommitted() -> Result<ReviewRange, GitError> {
     let head_oid = repo.head()?.peel_to_commit()?.id();
 
     Ok(ReviewRange {
-        from: Some(head_oid),
+        from: Some(Endpoint::Commit(head_oid)),
         to: Endpoint::WorkingTree,
         label: "HEAD..<working tree>".to_string(),
         scope_id: "worktree".to_string(),
+        pull: None,
+    })
+}
+
+/// Index against `HEAD`: only the staged changes.
+pub fn resolve_staged() -> Result<ReviewRange, GitError> {
+    let repo = get_repo()?;
+    let head_oid = repo.head()?.peel_to_commit()?.id();
+
+    Ok(ReviewRange {
+        from: Some(Endpoint::Commit(head_oid)),
+        to: Endpoint::Index,
+        label: "HEAD..<index>".to_string(),
+        scope_id: "staged".to_string(),
+        pull: None,
+    })
+}
+
+/// Working tree against the index: only the changes not yet staged.
+pub fn resolve_unstaged() -> Result<ReviewRange, GitError> {
+    Ok(ReviewRange {
+        from: Some(Endpoint::Index),
+        to: Endpoint::WorkingTree,
+        label: "<index>..<working tree>".to_string(),
+        scope_id: "unstaged".to_string(),
+        pull: None,
     })
 }
 
@@ -160,8 +205,19 @@ fn branch_scope_id(repo: &git2::Repository, head_oid: Oid) -> Result<String, Git
     }
 }
 
+/// When `range` ends at a commit that isn't `HEAD`: `HEAD` and that commit,
+/// abbreviated for display.
+pub fn head_mismatch(range: &ReviewRange) -> Result<Option<(String, String)>, GitError> {
+    let Endpoint::Commit(tip) = range.to else {
+        return Ok(None);
+    };
+    let repo = get_repo()?;
+    let head = repo.head()?.peel_to_commit()?.id();
+    Ok((head != tip).then(|| (short_oid(&repo, head), short_oid(&repo, tip))))
+}
+
 /// Abbreviated object id for display, falling back to a 7-char prefix.
-fn short_oid(repo: &git2::Repository, oid: Oid) -> String {
+pub(super) fn short_oid(repo: &git2::Repository, oid: Oid) -> String {
     repo.find_object(oid, None)
         .ok()
         .and_then(|o| o.short_id().ok())
@@ -211,4 +267,18 @@ mod tests {
         assert_eq!(range.scope_id, "worktree");
         assert!(range.from.is_some());
     }
+
+    #[test]
+    fn staged_and_unstaged_use_the_index_and_distinct_scopes() {
+        let staged = resolve_staged().expect("resolve staged");
+        assert_eq!(staged.to, Endpoint::Index);
+        let unstaged = resolve_unstaged().expect("resolve unstaged");
+        assert_eq!(unstaged.from, Some(Endpoint::Index));
+        assert_eq!(unstaged.to, Endpoint::WorkingTree);
+
+        let uncommitted = resolve_uncommitted().expect("resolve uncommitted");
+        assert_ne!(staged.scope_id, unstaged.scope_id);
+        assert_ne!(staged.scope_id, uncommitted.scope_id);
+        assert_ne!(unstaged.scope_id, uncommitted.scope_id);
+    }
 }
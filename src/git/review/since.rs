@@ -0,0 +1,219 @@
+//! "Changes since my last review": an interdiff between the tip a reviewer
+//! last had open and the range's current tip.
+//!
+//! When the author only pushed more commits, that is a plain diff between the
+//! two tips. After a rebase (or a merge of the base branch) the old tip sits on
+//! an older base, so diffing it against the new tip would mix in everything
+//! upstream changed. Instead the reviewed changes are replayed onto the new
+//! base — a three-way tree merge of `old base → old tip` onto it — and the
+//! interdiff starts from that tree. When the replay conflicts, only the new
+//! commits whose patch-id matches a reviewed commit (the ones the rebase left
+//! unchanged) are replayed, so every new or rewritten commit still shows.
+
+use super::diff::{self, ChangedFile};
+use super::range::{Endpoint, ReviewRange, short_oid};
+use crate::git::{GitError, get_repo};
+use git2::{Commit, Oid, Repository, Sort};
+use std::collections::HashSet;
+
+/// The "since last review" range plus how the branch's commits compare with
+/// the ones reviewed.
+#[derive(Debug)]
+pub struct Interdiff {
+    pub range: ReviewRange,
+    /// Commits on the branch now that were already reviewed (same patch-id).
+    pub unchanged: usize,
+    /// Commits that are new or were rewritten since.
+    pub changed: usize,
+}
+
+/// The interdiff between `reviewed` (the tip last reviewed) and `range`'s tip.
+/// Only a commit-to-commit range with a base can be compared.
+pub fn resolve(range: &ReviewRange, reviewed: Oid) -> Result<Interdiff, GitError> {
+    let (Some(Endpoint::Commit(base)), Endpoint::Commit(tip)) = (range.from, range.to) else {
+        return Err(failed(
+            "only a range between two commits can show changes since a review",
+        ));
+    };
+    let repo = get_repo()?;
+    if repo.find_commit(reviewed).is_err() {
+        return Err(failed(format!(
+            "the last-reviewed commit {reviewed} is no longer in this clone"
+        )));
+    }
+
+    let (from, unchanged, changed) = interdiff_from(&repo, base, reviewed, tip)?;
+    Ok(Interdiff {
+        range: ReviewRange {
+            from: Some(from),
+            to: range.to,
+            label: format!(
+                "{} since last review ({})",
+                range.label,
+                short_oid(&repo, reviewed)
+            ),
+            // Same scope: the new side is the same tip, so comments carry over.
+            scope_id: range.scope_id.clone(),
+            pull: range.pull.clone(),
+        },
+        unchanged,
+        changed,
+    })
+}
+
+/// Paths of `files` (a full review of some range) that the interdiff doesn't
+/// touch — their changes are exactly what was reviewed last time.
+pub fn unchanged_files(
+    files: &[ChangedFile],
+    interdiff: &ReviewRange,
+) -> Result<HashSet<String>, GitError> {
+    let touched: HashSet<String> = diff::changed_files(interdiff)?
+        .into_iter()
+        .flat_map(|f| [Some(f.path), f.old_path])
+        .flatten()
+        .collect();
+    Ok(files
+        .iter()
+        .filter(|f| !touched.contains(&f.path))
+        .map(|f| f.path.clone())
+        .collect())
+}
+
+/// The "from" side of the interdiff, plus the unchanged and changed commit
+/// counts of `base..tip` against `old base..reviewed`.
+fn interdiff_from(
+    repo: &Repository,
+    base: Oid,
+    reviewed: Oid,
+    tip: Oid,
+) -> Result<(Endpoint, usize, usize), GitError> {
+    let old_base = repo.merge_base(reviewed, base)?;
+    let reviewed_ids = commits(repo, old_base, reviewed)?
+        .iter()
+        .map(|c| patch_id(repo, c))
+        .collect::<Result<HashSet<_>, _>>()?;
+    let (unchanged, changed): (Vec<Commit>, Vec<Commit>) =
+        commits(repo, base, tip)?.into_iter().partition(|c| {
+            patch_id(repo, c).is_ok_and(|id| id.is_some() && reviewed_ids.contains(&id))
+        });
+
+    // Only more commits on the same base: the old tip is the baseline as is.
+    if old_base == base && (reviewed == tip || repo.graph_descendant_of(tip, reviewed)?) {
+        return Ok((Endpoint::Commit(reviewed), unchanged.len(), changed.len()));
+    }
+
+    let tree = |oid: Oid| repo.find_commit(oid).and_then(|c| c.tree());
+    let mut index = repo.merge_trees(&tree(old_base)?, &tree(base)?, &tree(reviewed)?, None)?;
+    if !index.has_conflicts() {
+        let replayed = index.write_tree_to(repo)?;
+        return Ok((Endpoint::Tree(replayed), unchanged.len(), changed.len()));
+    }
+
+    // Fall back to the commits the rebase left unchanged, skipping any that
+    // no longer apply on their own.
+    let mut current = tree(base)?;
+    for commit in &unchanged {
+        let parent = commit.parent(0)?.tree()?;
+        let mut index = repo.merge_trees(&parent, &current, &commit.tree()?, None)?;
+        if !index.has_conflicts() {
+            current = repo.find_tree(index.write_tree_to(repo)?)?;
+        }
+    }
+    Ok((Endpoint::Tree(current.id()), unchanged.len(), changed.len()))
+}
+
+/// Commits in `from..to`, oldest first.
+fn commits(repo: &Repository, from: Oid, to: Oid) -> Result<Vec<Commit<'_>>, GitError> {
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    walk.push(to)?;
+    walk.hide(from)?;
+    walk.map(|oid| Ok(repo.find_commit(oid?)?)).collect()
+}
+
+/// The commit's patch-id (stable across rebases that don't change its diff).
+/// Merge and root commits have none.
+fn patch_id(repo: &Repository, commit: &Commit) -> Result<Option<Oid>, GitError> {
+    if commit.parent_count() != 1 {
+        return Ok(None);
+    }
+    let parent = commit.parent(0)?.tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&parent), Some(&commit.tree()?), None)?;
+    Ok(Some(diff.patchid(None)?))
+}
+
+fn failed(message: impl Into<String>) -> GitError {
+    GitError::CommandFailed {
+        stderr: message.into(),
+        code: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+
+    /// Commit `files` (path, content) on top of `parent` without moving any ref.
+    fn commit(repo: &Repository, parent: Option<Oid>, files: &[(&str, &str)]) -> Oid {
+        let parent = parent.map(|p| repo.find_commit(p).unwrap());
+        let mut builder = repo
+            .treebuilder(parent.as_ref().map(|p| p.tree().unwrap()).as_ref())
+            .unwrap();
+        for (path, content) in files {
+            let blob = repo.blob(content.as_bytes()).unwrap();
+            builder.insert(path, blob, 0o100644).unwrap();
+        }
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let sig = Signature::now("gx", "gx@example.com").unwrap();
+        let parents: Vec<&Commit> = parent.iter().collect();
+        repo.commit(None, &sig, &sig, "c", &tree, &parents).unwrap()
+    }
+
+    fn changed_paths(repo: &Repository, from: Endpoint, to: Oid) -> Vec<String> {
+        let old = match from {
+            Endpoint::Commit(oid) => repo.find_commit(oid).unwrap().tree().unwrap(),
+            Endpoint::Tree(oid) => repo.find_tree(oid).unwrap(),
+            _ => unreachable!(),
+        };
+        let new = repo.find_commit(to).unwrap().tree().unwrap();
+        let diff = repo
+            .diff_tree_to_tree(Some(&old), Some(&new), None)
+            .unwrap();
+        diff.deltas()
+            .filter_map(|d| d.new_file().path().map(|p| p.display().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_interdiff_after_more_commits_starts_at_reviewed_tip() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(tmp.path()).unwrap();
+        let base = commit(&repo, None, &[("base", "0\n")]);
+        let reviewed = commit(&repo, Some(base), &[("a", "a\n")]);
+        let tip = commit(&repo, Some(reviewed), &[("b", "b\n")]);
+
+        let (from, unchanged, changed) = interdiff_from(&repo, base, reviewed, tip).unwrap();
+        assert_eq!(from, Endpoint::Commit(reviewed));
+        assert_eq!((unchanged, changed), (1, 1));
+    }
+
+    #[test]
+    fn test_interdiff_across_rebase_skips_upstream_changes() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(tmp.path()).unwrap();
+        let old_base = commit(&repo, None, &[("base", "0\n")]);
+        let a = commit(&repo, Some(old_base), &[("a", "a\n")]);
+        let reviewed = commit(&repo, Some(a), &[("b", "b\n")]);
+
+        // Upstream moves on; the branch is rebased onto it and `b` amended.
+        let new_base = commit(&repo, Some(old_base), &[("upstream", "u\n")]);
+        let a2 = commit(&repo, Some(new_base), &[("a", "a\n")]);
+        let tip = commit(&repo, Some(a2), &[("b", "b, fixed\n")]);
+
+        let (from, unchanged, changed) = interdiff_from(&repo, new_base, reviewed, tip).unwrap();
+        assert!(matches!(from, Endpoint::Tree(_)));
+        assert_eq!((unchanged, changed), (1, 1));
+        assert_eq!(changed_paths(&repo, from, tip), vec!["b"]);
+    }
+}
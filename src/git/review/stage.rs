@@ -0,0 +1,391 @@
+//! Staging and unstaging part of a file from the review TUI: the hunk or the
+//! selection under the cursor.
+//!
+//! As with `gx commit --ai-split` (see `git::split`), nothing goes through
+//! `git apply`: the file's index content is rebuilt from the diff's old side
+//! with exactly the chosen changes spliced in.
+//!
+//! Three ranges support it, each against the index state it shows:
+//!
+//! - **Unstaged** (index → working tree): stage the chosen changes.
+//! - **Staged** (`HEAD` → index): unstage the chosen changes.
+//! - **Uncommitted** (`HEAD` → working tree): stage or unstage, as long as
+//!   the file is wholly unstaged or wholly staged — the diff can't show which
+//!   of its lines a partly staged file has in the index.
+
+use super::diff::{ChangedFile, FileDiff, Row, RowKind};
+use super::range::{Endpoint, ReviewRange};
+use crate::git::split::add_blob;
+use crate::git::status::FileStatus;
+use crate::git::{GitError, get_repo};
+use std::collections::HashSet;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Stage,
+    Unstage,
+}
+
+#[derive(Error, Debug)]
+pub enum StageError {
+    #[error("{0}")]
+    Git(#[from] GitError),
+
+    #[error("{0}")]
+    Unsupported(&'static str),
+}
+
+/// The change rows an action covers, by line number: removed rows by their
+/// old-side number, added rows by their new-side number.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub old: HashSet<usize>,
+    pub new: HashSet<usize>,
+}
+
+impl Selection {
+    /// Add `row` when it is a change (context rows are never staged).
+    pub fn insert(&mut self, row: &Row) {
+        match row.kind {
+            RowKind::Removed => self.old.extend(row.old_no),
+            RowKind::Added => self.new.extend(row.new_no),
+            RowKind::Context => {}
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.old.len() + self.new.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn covers(&self, row: &Row) -> bool {
+        match row.kind {
+            RowKind::Removed => row.old_no.is_some_and(|n| self.old.contains(&n)),
+            RowKind::Added => row.new_no.is_some_and(|n| self.new.contains(&n)),
+            RowKind::Context => false,
+        }
+    }
+}
+
+/// Stage or unstage the `selection` of `file` (whose built diff is `diff`)
+/// within `range`, rewriting only that file's index entry.
+pub fn apply(
+    range: &ReviewRange,
+    file: &ChangedFile,
+    diff: &FileDiff,
+    selection: &Selection,
+    action: Action,
+) -> Result<(), StageError> {
+    if diff.old_path.is_some() {
+        return Err(StageError::Unsupported(
+            "a renamed file can only be staged whole ('git add')",
+        ));
+    }
+    if diff.hunks.is_empty() {
+        return Err(StageError::Unsupported(
+            "a binary or oversized file can only be staged whole ('git add')",
+        ));
+    }
+
+    let repo = get_repo().map_err(StageError::from)?;
+    let head = repo
+        .head()
+        .and_then(|h| h.peel_to_commit())
+        .map_err(GitError::from)?
+        .id();
+    let mut index = repo.index().map_err(GitError::from)?;
+    let path = Path::new(&diff.path);
+    let (old, new) = file.load_bytes(range.to)?;
+    let (old_exists, new_exists) = (
+        file.status != FileStatus::New,
+        file.status != FileStatus::Deleted,
+    );
+
+    // Which changes end up applied on top of the old side.
+    let take_selected = take_selected(range, head, action, || {
+        let staged = index
+            .get_path(path, 0)
+            .map(|entry| repo.find_blob(entry.id).map(|b| b.content().to_vec()))
+            .transpose()
+            .map_err(GitError::from)?;
+        let matches = |exists: bool, bytes: &[u8]| match &staged {
+            Some(content) => exists && content.as_slice() == bytes,
+            None => !exists,
+        };
+        let state = match (matches(old_exists, &old), matches(new_exists, &new)) {
+            (true, _) => IndexState::Unstaged,
+            (_, true) => IndexState::Staged,
+            _ => IndexState::Partly,
+        };
+        Ok(state)
+    })?;
+
+    let content = splice(&old, &new, diff, |row| {
+        selection.covers(row) == take_selected
+    });
+    let absent = (content == new && !new_exists) || (content == old && !old_exists);
+    if absent {
+        index.remove_path(path).map_err(GitError::from)?;
+    } else {
+        let mode = index
+            .get_path(path, 0)
+            .map(|entry| entry.mode)
+            .or_else(|| {
+                repo.find_commit(head)
+                    .and_then(|c| c.tree())
+                    .ok()
+                    .and_then(|tree| tree.get_path(path).ok())
+                    .map(|entry| entry.filemode() as u32)
+            })
+            .unwrap_or(0o100644);
+        add_blob(&repo, &mut index, &diff.path, &content, mode)?;
+    }
+    index.write().map_err(GitError::from)?;
+    Ok(())
+}
+
+/// Where a file's index entry stands in the uncommitted range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IndexState {
+    /// The index still has the `HEAD` content.
+    Unstaged,
+    /// The index has the working-tree content.
+    Staged,
+    Partly,
+}
+
+/// Whether `action` applies the selected changes on top of the diff's old side
+/// (true) or everything but them (false), or why `range` can't take it.
+/// `index_state` is only asked for in the uncommitted range.
+fn take_selected(
+    range: &ReviewRange,
+    head: git2::Oid,
+    action: Action,
+    index_state: impl FnOnce() -> Result<IndexState, StageError>,
+) -> Result<bool, StageError> {
+    match (range.from, range.to, action) {
+        (Some(Endpoint::Index), Endpoint::WorkingTree, Action::Stage) => Ok(true),
+        (Some(Endpoint::Index), Endpoint::WorkingTree, Action::Unstage) => Err(
+            StageError::Unsupported("nothing here is staged; unstage from the staged range (s, i)"),
+        ),
+        (Some(Endpoint::Commit(from)), Endpoint::Index, Action::Unstage) if from == head => {
+            Ok(false)
+        }
+        (Some(Endpoint::Commit(from)), Endpoint::Index, Action::Stage) if from == head => {
+            Err(StageError::Unsupported("these changes are already staged"))
+        }
+        (Some(Endpoint::Commit(from)), Endpoint::WorkingTree, action) if from == head => {
+            match (action, index_state()?) {
+                (Action::Stage, IndexState::Unstaged) => Ok(true),
+                (Action::Unstage, IndexState::Staged) => Ok(false),
+                (Action::Stage, IndexState::Staged) => {
+                    Err(StageError::Unsupported("this file is already staged"))
+                }
+                (Action::Unstage, IndexState::Unstaged) => {
+                    Err(StageError::Unsupported("nothing in this file is staged"))
+                }
+                (_, IndexState::Partly) => Err(StageError::Unsupported(
+                    "this file is partly staged; use the staged (s, i) or unstaged (s, w) range",
+                )),
+            }
+        }
+        _ => Err(StageError::Unsupported(
+            "staging works in the uncommitted, staged, and unstaged ranges",
+        )),
+    }
+}
+
+/// Rebuild `old` with the changes `take` accepts applied: a taken removal
+/// drops its line, a taken addition inserts it. Lines are copied byte-for-byte
+/// so line endings and a missing final newline survive.
+fn splice(old: &[u8], new: &[u8], diff: &FileDiff, take: impl Fn(&Row) -> bool) -> Vec<u8> {
+    let old_lines: Vec<&[u8]> = old.split_inclusive(|&b| b == b'\n').collect();
+    let new_lines: Vec<&[u8]> = new.split_inclusive(|&b| b == b'\n').collect();
+    let old_line = |n: usize| old_lines.get(n - 1).copied().unwrap_or_default();
+
+    let mut out = Vec::with_capacity(new.len().max(old.len()));
+    // The next old line (0-based) not yet copied.
+    let mut cursor = 0;
+    for row in diff.hunks.iter().flat_map(|h| &h.rows) {
+        if let Some(n) = row.old_no {
+            for line in old_lines.get(cursor..n - 1).unwrap_or_default() {
+                out.extend_from_slice(line);
+            }
+            cursor = cursor.max(n);
+        }
+        match row.kind {
+            RowKind::Context => out.extend_from_slice(row.old_no.map_or(&[], old_line)),
+            RowKind::Removed => {
+                if !take(row) {
+                    out.extend_from_slice(row.old_no.map_or(&[], old_line));
+                }
+            }
+            RowKind::Added => {
+                if take(row) {
+                    let line = row.new_no.and_then(|n| new_lines.get(n - 1));
+                    out.extend_from_slice(line.copied().unwrap_or_default());
+                }
+            }
+        }
+    }
+    for line in old_lines.get(cursor..).unwrap_or_default() {
+        out.extend_from_slice(line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::review::diff::build_hunks;
+
+    fn file_diff(old: &str, new: &str) -> FileDiff {
+        FileDiff {
+            path: "a.txt".into(),
+            old_path: None,
+            status: FileStatus::Modified,
+            is_binary: false,
+            too_large: false,
+            hunks: build_hunks(old, new),
+        }
+    }
+
+    fn splice_str(old: &str, new: &str, take: impl Fn(&Row) -> bool) -> String {
+        let diff = file_diff(old, new);
+        String::from_utf8(splice(old.as_bytes(), new.as_bytes(), &diff, take)).unwrap()
+    }
+
+    #[test]
+    fn test_splice_all_or_nothing() {
+        let old = "a\nb\nc\n";
+        let new = "a\nB\nc\nd\n";
+        assert_eq!(splice_str(old, new, |_| true), new);
+        assert_eq!(splice_str(old, new, |_| false), old);
+    }
+
+    #[test]
+    fn test_splice_selected_lines_only() {
+        let old: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+        let new = old
+            .replace("line 2\n", "two\n")
+            .replace("line 19\n", "line 19\nnineteen\n");
+        let diff = file_diff(&old, &new);
+        assert_eq!(diff.hunks.len(), 2);
+
+        // Only the second hunk's addition.
+        let mut selection = Selection::default();
+        for row in &diff.hunks[1].rows {
+            selection.insert(row);
+        }
+        assert_eq!(selection.len(), 1);
+        assert_eq!(
+            splice_str(&old, &new, |row| selection.covers(row)),
+            old.replace("line 19\n", "line 19\nnineteen\n")
+        );
+
+        // The first hunk's removal without its replacement line.
+        assert_eq!(
+            splice_str(&old, &new, |row| row.kind == RowKind::Removed),
+            old.replacen("line 2\n", "", 1)
+        );
+    }
+
+    #[test]
+    fn test_take_selected_per_range_and_action() {
+        use Action::{Stage, Unstage};
+        use IndexState::{Partly, Staged, Unstaged};
+
+        let head = git2::Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+        let other = git2::Oid::from_str("89abcdef0123456789abcdef0123456789abcdef").unwrap();
+        let range = |from, to| ReviewRange {
+            from: Some(from),
+            to,
+            label: String::new(),
+            scope_id: String::new(),
+            pull: None,
+        };
+        let unstaged = range(Endpoint::Index, Endpoint::WorkingTree);
+        let staged = range(Endpoint::Commit(head), Endpoint::Index);
+        let uncommitted = range(Endpoint::Commit(head), Endpoint::WorkingTree);
+        let branch = range(Endpoint::Commit(other), Endpoint::Commit(head));
+        let old_staged = range(Endpoint::Commit(other), Endpoint::Index);
+
+        // (range, action, index state, taken selection or error fragment)
+        let cases = [
+            (&unstaged, Stage, None, Ok(true)),
+            (&unstaged, Unstage, None, Err("nothing here is staged")),
+            (&staged, Unstage, None, Ok(false)),
+            (&staged, Stage, None, Err("already staged")),
+            (&uncommitted, Stage, Some(Unstaged), Ok(true)),
+            (&uncommitted, Unstage, Some(Staged), Ok(false)),
+            (&uncommitted, Stage, Some(Staged), Err("already staged")),
+            (&uncommitted, Unstage, Some(Unstaged), Err("nothing in")),
+            (&uncommitted, Stage, Some(Partly), Err("partly staged")),
+            (&uncommitted, Unstage, Some(Partly), Err("partly staged")),
+            (&branch, Stage, None, Err("staging works in")),
+            (&branch, Unstage, None, Err("staging works in")),
+            (&old_staged, Unstage, None, Err("staging works in")),
+        ];
+        for (range, action, state, expected) in cases {
+            let got = take_selected(range, head, action, || {
+                Ok(state.expect("only the uncommitted range reads the index"))
+            });
+            let case = format!(
+                "{} → {:?}, {action:?}, {state:?}",
+                range.from.map_or(String::new(), |f| format!("{f:?}")),
+                range.to
+            );
+            match (got, expected) {
+                (Ok(got), Ok(expected)) => assert_eq!(got, expected, "{case}"),
+                (Err(e), Err(fragment)) => {
+                    assert!(e.to_string().contains(fragment), "{case}: {e}")
+                }
+                (got, expected) => panic!("{case}: got {got:?}, expected {expected:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_unstage_selection_per_side() {
+        // Unstaging rebuilds the index from the old side with everything but
+        // the selection: a selected removal comes back, a selected addition
+        // goes away, and the rest stays staged.
+        let old = "a\nb\nc\n";
+        let new = "a\nB\nc\n";
+        let diff = file_diff(old, new);
+        let rows: Vec<&Row> = diff.hunks.iter().flat_map(|h| &h.rows).collect();
+        let pick = |kind: RowKind| {
+            let mut selection = Selection::default();
+            for row in rows.iter().filter(|r| r.kind == kind) {
+                selection.insert(row);
+            }
+            selection
+        };
+
+        let removed = pick(RowKind::Removed);
+        assert_eq!(
+            splice_str(old, new, |row| !removed.covers(row)),
+            "a\nb\nB\nc\n"
+        );
+        let added = pick(RowKind::Added);
+        assert_eq!(splice_str(old, new, |row| !added.covers(row)), "a\nc\n");
+        // And staging is the mirror image.
+        assert_eq!(splice_str(old, new, |row| removed.covers(row)), "a\nc\n");
+        assert_eq!(
+            splice_str(old, new, |row| added.covers(row)),
+            "a\nb\nB\nc\n"
+        );
+    }
+
+    #[test]
+    fn test_splice_keeps_crlf() {
+        let old = "a\r\nb\r\nc\r\n";
+        let new = "a\r\nB\r\nc\r\n";
+        assert_eq!(splice_str(old, new, |_| true), new);
+    }
+}
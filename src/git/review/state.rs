@@ -1,4 +1,5 @@
-//! In-memory review state: the line comments a reviewer has left.
+//! In-memory review state: the line comments a reviewer has left, plus
+//! whole-file comments and a review-level summary with a verdict.
 //!
 //! Comments anchor to `(file, side, line-range)` and carry a snapshot of the
 //! anchored line (`anchor_text`) so a later session can re-attach them even if
@@ -6,8 +7,9 @@
 //! derives are present now so U7 can persist this type unchanged.
 
 use crate::git::review::diff::{FileDiff, Row};
+use git2::Oid;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Which side of the diff a comment is anchored to. New-side anchors carry the
@@ -29,6 +31,91 @@ pub struct Comment {
     /// Snapshot of the first anchored line, for re-anchoring on resume (U7).
     pub anchor_text: String,
     pub body: String,
+    /// Replacement text proposed for the anchored lines (new side only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<Suggestion>,
+    /// Set when the review was handed to the coding agent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handoff: Option<Handoff>,
+}
+
+/// A comment's anchored lines as they were when the review was handed to the
+/// coding agent, to tell on the next open whether the agent changed them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handoff {
+    pub lines: String,
+    /// The working tree no longer has `lines` (worked out on open, not saved).
+    #[serde(skip)]
+    pub touched: bool,
+}
+
+/// A suggested change: what the anchored lines should read instead. The
+/// lines as they were when it was written are kept so applying it later can
+/// tell whether they have changed since.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suggestion {
+    /// The anchored lines, newline-separated.
+    pub original: String,
+    /// Their replacement, newline-separated; empty deletes the lines.
+    pub replacement: String,
+}
+
+/// A comment on a whole file rather than on some of its lines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileComment {
+    pub file: String,
+    pub body: String,
+}
+
+/// The reviewer's overall call on the change.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    #[default]
+    Comment,
+    Approve,
+    RequestChanges,
+}
+
+impl Verdict {
+    pub fn label(self) -> &'static str {
+        match self {
+            Verdict::Comment => "Comment",
+            Verdict::Approve => "Approve",
+            Verdict::RequestChanges => "Request changes",
+        }
+    }
+
+    /// The next verdict in the popup's cycle.
+    pub fn next(self) -> Self {
+        match self {
+            Verdict::Comment => Verdict::Approve,
+            Verdict::Approve => Verdict::RequestChanges,
+            Verdict::RequestChanges => Verdict::Comment,
+        }
+    }
+}
+
+/// The review-level summary: a verdict plus an optional note on the change as
+/// a whole.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Summary {
+    pub verdict: Verdict,
+    pub body: String,
+}
+
+impl Summary {
+    /// Nothing beyond the default "Comment" verdict.
+    pub fn is_empty(&self) -> bool {
+        self.verdict == Verdict::Comment && self.body.trim().is_empty()
+    }
+}
+
+/// A reply drafted to an existing GitHub review thread, posted on finish.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reply {
+    /// The thread's GraphQL node id.
+    pub thread_id: String,
+    pub body: String,
 }
 
 /// All comments left in the current review session, plus any that could not be
@@ -37,6 +124,17 @@ pub struct Comment {
 pub struct ReviewState {
     pub comments: Vec<Comment>,
     pub orphaned: Vec<Comment>,
+    /// Replies to imported pull-request threads (at most one per thread).
+    pub replies: Vec<Reply>,
+    /// Whole-file comments (at most one per file).
+    pub file_comments: Vec<FileComment>,
+    pub summary: Summary,
+    /// The range's tip when this scope was last reviewed, for "changes since
+    /// last review" (only set for ranges ending at a commit).
+    pub reviewed: Option<Oid>,
+    /// Files marked as viewed, by path, with the content id they were viewed
+    /// at — a mark on since-changed content no longer counts.
+    pub viewed: HashMap<String, Oid>,
 }
 
 /// Line numbers, per side, that carry a comment in one file — used to draw
@@ -45,6 +143,26 @@ pub struct ReviewState {
 pub struct Marks {
     pub old: HashSet<usize>,
     pub new: HashSet<usize>,
+    /// Lines carrying an imported review thread, per side, mapped to whether
+    /// the thread is still active (neither resolved nor outdated).
+    pub threads_old: HashMap<usize, bool>,
+    pub threads_new: HashMap<usize, bool>,
+    /// New-side comment lines the agent has changed since the hand-off.
+    pub touched: HashSet<usize>,
+}
+
+impl Marks {
+    /// Mark `start..=end` on `side` as carrying a thread. An active thread
+    /// wins over an inactive one on a shared line.
+    pub fn add_thread(&mut self, side: Side, start: usize, end: usize, active: bool) {
+        let map = match side {
+            Side::Old => &mut self.threads_old,
+            Side::New => &mut self.threads_new,
+        };
+        for line in start..=end {
+            *map.entry(line).or_default() |= active;
+        }
+    }
 }
 
 impl ReviewState {
@@ -52,12 +170,39 @@ impl ReviewState {
         self.comments.push(comment);
     }
 
+    /// Line and whole-file comments.
     pub fn total(&self) -> usize {
-        self.comments.len()
+        self.comments.len() + self.file_comments.len()
+    }
+
+    /// Whether there is nothing to finish: no comments of any kind, replies,
+    /// or verdict.
+    pub fn is_empty(&self) -> bool {
+        self.comments.is_empty()
+            && self.file_comments.is_empty()
+            && self.replies.is_empty()
+            && self.summary.is_empty()
     }
 
+    /// Line comments plus the whole-file comment on `file`.
     pub fn count_for_file(&self, file: &str) -> usize {
         self.comments.iter().filter(|c| c.file == file).count()
+            + usize::from(self.file_comment(file).is_some())
+    }
+
+    pub fn file_comment(&self, file: &str) -> Option<&FileComment> {
+        self.file_comments.iter().find(|c| c.file == file)
+    }
+
+    /// Set the whole-file comment on `file`; an empty body removes it.
+    pub fn set_file_comment(&mut self, file: &str, body: String) {
+        self.file_comments.retain(|c| c.file != file);
+        if !body.trim().is_empty() {
+            self.file_comments.push(FileComment {
+                file: file.to_string(),
+                body,
+            });
+        }
     }
 
     /// Index of a comment whose range covers `(file, side, line)`, if any —
@@ -74,12 +219,50 @@ impl ReviewState {
         }
     }
 
+    pub fn set_suggestion(&mut self, idx: usize, suggestion: Option<Suggestion>) {
+        if let Some(c) = self.comments.get_mut(idx) {
+            c.suggestion = suggestion;
+        }
+    }
+
     pub fn remove(&mut self, idx: usize) {
         if idx < self.comments.len() {
             self.comments.remove(idx);
         }
     }
 
+    /// The drafted reply to thread `thread_id`, if any.
+    pub fn reply_for(&self, thread_id: &str) -> Option<&Reply> {
+        self.replies.iter().find(|r| r.thread_id == thread_id)
+    }
+
+    /// Set the reply to thread `thread_id`; an empty body removes it.
+    pub fn set_reply(&mut self, thread_id: &str, body: String) {
+        self.replies.retain(|r| r.thread_id != thread_id);
+        if !body.trim().is_empty() {
+            self.replies.push(Reply {
+                thread_id: thread_id.to_string(),
+                body,
+            });
+        }
+    }
+
+    pub fn is_viewed(&self, file: &str, id: Oid) -> bool {
+        self.viewed.get(file) == Some(&id)
+    }
+
+    /// Flip `file`'s viewed mark at content `id`, returning whether it is now
+    /// viewed. A stale mark (other content) counts as unviewed.
+    pub fn toggle_viewed(&mut self, file: &str, id: Oid) -> bool {
+        if self.is_viewed(file, id) {
+            self.viewed.remove(file);
+            false
+        } else {
+            self.viewed.insert(file.to_string(), id);
+            true
+        }
+    }
+
     /// Lines (per side) carrying a comment in `file`, for gutter markers.
     pub fn marks_for(&self, file: &str) -> Marks {
         let mut marks = Marks::default();
@@ -91,6 +274,9 @@ impl ReviewState {
             for line in c.start_line..=c.end_line {
                 set.insert(line);
             }
+            if c.side == Side::New && c.handoff.as_ref().is_some_and(|h| h.touched) {
+                marks.touched.extend(c.start_line..=c.end_line);
+            }
         }
         marks
     }
@@ -131,10 +317,9 @@ impl ReviewState {
             Some(candidates[0])
         };
 
-        let (mine, rest): (Vec<Comment>, Vec<Comment>) =
-            std::mem::take(&mut self.comments)
-                .into_iter()
-                .partition(|c| c.file == file);
+        let (mine, rest): (Vec<Comment>, Vec<Comment>) = std::mem::take(&mut self.comments)
+            .into_iter()
+            .partition(|c| c.file == file);
         self.comments = rest;
 
         for mut c in mine {
@@ -159,6 +344,16 @@ struct Persisted {
     comments: Vec<Comment>,
     #[serde(default)]
     orphaned: Vec<Comment>,
+    #[serde(default)]
+    replies: Vec<Reply>,
+    #[serde(default)]
+    file_comments: Vec<FileComment>,
+    #[serde(default)]
+    summary: Summary,
+    #[serde(default)]
+    reviewed: Option<String>,
+    #[serde(default)]
+    viewed: HashMap<String, String>,
 }
 
 /// Stable storage key for a review: an FNV-1a digest of the clone's shared git
@@ -169,7 +364,7 @@ pub fn storage_key(common_git_dir: &Path, scope_id: &str) -> String {
     fnv1a_hex(&format!("{}\0{}", common_git_dir.display(), scope_id))
 }
 
-fn fnv1a_hex(input: &str) -> String {
+pub(crate) fn fnv1a_hex(input: &str) -> String {
     let mut hash: u64 = 0xcbf29ce484222325;
     for byte in input.bytes() {
         hash ^= byte as u64;
@@ -196,6 +391,15 @@ pub fn load(key: &str) -> ReviewState {
         Ok(p) => ReviewState {
             comments: p.comments,
             orphaned: p.orphaned,
+            replies: p.replies,
+            file_comments: p.file_comments,
+            summary: p.summary,
+            reviewed: p.reviewed.and_then(|oid| Oid::from_str(&oid).ok()),
+            viewed: p
+                .viewed
+                .into_iter()
+                .filter_map(|(file, oid)| Some((file, Oid::from_str(&oid).ok()?)))
+                .collect(),
         },
         Err(_) => ReviewState::default(),
     }
@@ -216,6 +420,15 @@ pub fn save(key: &str, state: &ReviewState) -> std::io::Result<()> {
     let persisted = Persisted {
         comments: state.comments.clone(),
         orphaned: state.orphaned.clone(),
+        replies: state.replies.clone(),
+        file_comments: state.file_comments.clone(),
+        summary: state.summary.clone(),
+        reviewed: state.reviewed.map(|oid| oid.to_string()),
+        viewed: state
+            .viewed
+            .iter()
+            .map(|(file, oid)| (file.clone(), oid.to_string()))
+            .collect(),
     };
     let json = serde_json::to_string_pretty(&persisted)
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
@@ -257,6 +470,8 @@ mod tests {
             end_line: end,
             anchor_text: "x".into(),
             body: "note".into(),
+            suggestion: None,
+            handoff: None,
         }
     }
 
@@ -302,6 +517,49 @@ mod tests {
         assert!(marks.old.contains(&9));
     }
 
+    #[test]
+    fn set_reply_replaces_and_empty_removes() {
+        let mut s = ReviewState::default();
+        s.set_reply("T1", "first".into());
+        s.set_reply("T1", "second".into());
+        assert_eq!(s.replies.len(), 1);
+        assert_eq!(s.reply_for("T1").unwrap().body, "second");
+        s.set_reply("T1", "  ".into());
+        assert!(s.reply_for("T1").is_none());
+    }
+
+    #[test]
+    fn file_comments_count_per_file_and_empty_removes() {
+        let mut s = ReviewState::default();
+        assert!(s.is_empty());
+        s.add(comment("a.rs", Side::New, 1, 1));
+        s.set_file_comment("a.rs", "whole file".into());
+        s.set_file_comment("a.rs", "replaced".into());
+        assert_eq!(s.count_for_file("a.rs"), 2);
+        assert_eq!(s.file_comment("a.rs").unwrap().body, "replaced");
+        s.set_file_comment("a.rs", " ".into());
+        assert!(s.file_comment("a.rs").is_none());
+
+        let mut verdict_only = ReviewState::default();
+        verdict_only.summary.verdict = Verdict::Approve;
+        assert!(!verdict_only.is_empty());
+    }
+
+    #[test]
+    fn viewed_marks_reset_when_the_content_changes() {
+        let mut s = ReviewState::default();
+        let (v1, v2) = (
+            Oid::hash_object(git2::ObjectType::Blob, b"1").unwrap(),
+            Oid::hash_object(git2::ObjectType::Blob, b"2").unwrap(),
+        );
+        assert!(s.toggle_viewed("a.rs", v1));
+        assert!(s.is_viewed("a.rs", v1));
+        assert!(!s.is_viewed("a.rs", v2)); // changed since
+        assert!(s.toggle_viewed("a.rs", v2)); // re-marks at the new content
+        assert!(!s.toggle_viewed("a.rs", v2));
+        assert!(!s.is_viewed("a.rs", v1));
+    }
+
     #[test]
     fn storage_key_is_stable_and_scope_sensitive() {
         let dir = Path::new("/clones/gx/.git");
@@ -323,11 +581,26 @@ mod tests {
 
         let mut s = ReviewState::default();
         s.add(comment("a.rs", Side::New, 4, 4));
+        let tip = Oid::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
+        s.reviewed = Some(tip);
+        s.toggle_viewed("a.rs", tip);
+        s.set_file_comment("b.rs", "move this to util/".into());
+        s.summary = Summary {
+            verdict: Verdict::RequestChanges,
+            body: "split this PR".into(),
+        };
         save(&key, &s).expect("save");
 
         let loaded = load(&key);
-        assert_eq!(loaded.total(), 1);
+        assert_eq!(loaded.total(), 2); // the line comment and the file comment
         assert_eq!(loaded.comments[0].file, "a.rs");
+        assert_eq!(loaded.reviewed, Some(tip));
+        assert!(loaded.is_viewed("a.rs", tip));
+        assert_eq!(
+            loaded.file_comment("b.rs").unwrap().body,
+            "move this to util/"
+        );
+        assert_eq!(loaded.summary.verdict, Verdict::RequestChanges);
 
         reset(&key).expect("reset");
         assert_eq!(load(&key).total(), 0); // missing file -> empty
@@ -374,6 +647,8 @@ mod tests {
             end_line: 1,
             anchor_text: "alpha".into(),
             body: "keep".into(),
+            suggestion: None,
+            handoff: None,
         });
         // Moves: text "beta" now lives on new line 2, comment thinks line 5.
         s.add(Comment {
@@ -383,6 +658,8 @@ mod tests {
             end_line: 5,
             anchor_text: "beta".into(),
             body: "moved".into(),
+            suggestion: None,
+            handoff: None,
         });
         // Orphans: anchor_text gone.
         s.add(Comment {
@@ -392,6 +669,8 @@ mod tests {
             end_line: 9,
             anchor_text: "vanished".into(),
             body: "orphan".into(),
+            suggestion: None,
+            handoff: None,
         });
 
         s.reanchor_file("a.rs", &diff);
@@ -437,6 +716,8 @@ mod tests {
             end_line: 7,
             anchor_text: "dup".into(),
             body: "near8".into(),
+            suggestion: None,
+            handoff: None,
         });
         // Was at line 5 -> equidistant from 2 and 8 -> ambiguous -> orphan.
         s.add(Comment {
@@ -446,6 +727,8 @@ mod tests {
             end_line: 5,
             anchor_text: "dup".into(),
             body: "ambiguous".into(),
+            suggestion: None,
+            handoff: None,
         });
 
         s.reanchor_file("a.rs", &diff);
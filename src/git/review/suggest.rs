@@ -0,0 +1,295 @@
+//! Suggested changes: line comments that carry replacement text for the
+//! new-side lines they anchor to.
+//!
+//! A suggestion renders as a unified patch for the review blob and applies
+//! straight to the working tree (`gx review apply-suggestions`). Applying
+//! checks the anchored lines first: they still read as they did when the
+//! suggestion was written, or the suggestion is reported as a conflict rather
+//! than clobbering whatever replaced them. Lines that only moved (an edit
+//! above them) are found again, as long as they appear exactly once within
+//! [`DRIFT`] lines of where they were.
+
+use super::diff::FileDiff;
+use super::state::Suggestion;
+
+/// How far (in lines, either way) anchored lines are looked for once they
+/// are no longer where the suggestion was written.
+const DRIFT: usize = 30;
+
+/// What applying one suggestion did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Replaced the lines, now starting at this (1-based) line.
+    Applied(usize),
+    /// The lines already read as the replacement.
+    AlreadyApplied,
+    /// The lines changed since the suggestion was written (or moved somewhere
+    /// ambiguous); nothing was touched.
+    Conflict,
+}
+
+/// New-side lines `start..=end` of `diff`, newline-separated — what a
+/// suggestion on them starts from. `None` when some of them aren't shown.
+pub fn new_lines(diff: &FileDiff, start: usize, end: usize) -> Option<String> {
+    let lines: Vec<&str> = diff
+        .hunks
+        .iter()
+        .flat_map(|h| &h.rows)
+        .filter(|r| r.new_no.is_some_and(|n| n >= start && n <= end))
+        .map(|r| r.text.as_str())
+        .collect();
+    (lines.len() == end + 1 - start).then(|| lines.join("\n"))
+}
+
+/// A unified patch turning `original` into `replacement` at `start` (1-based)
+/// in `file`, for the review blob.
+pub fn patch(file: &str, start: usize, suggestion: &Suggestion) -> String {
+    let old = lines_of(&suggestion.original);
+    let new = lines_of(&suggestion.replacement);
+    // An empty side's range starts at the line before it, as in `git diff`.
+    let at = |count: usize| {
+        if count == 0 {
+            start.saturating_sub(1)
+        } else {
+            start
+        }
+    };
+
+    let mut out = format!(
+        "--- a/{file}\n+++ b/{file}\n@@ -{},{} +{},{} @@\n",
+        at(old.len()),
+        old.len(),
+        at(new.len()),
+        new.len()
+    );
+    for line in old {
+        out.push_str(&format!("-{line}\n"));
+    }
+    for line in new {
+        out.push_str(&format!("+{line}\n"));
+    }
+    out
+}
+
+/// A file's text as lines that keep their endings, so an applied suggestion
+/// leaves line endings and a missing final newline as they were.
+pub struct Text {
+    lines: Vec<String>,
+}
+
+impl Text {
+    pub fn new(content: &str) -> Self {
+        Text {
+            lines: content.split_inclusive('\n').map(str::to_string).collect(),
+        }
+    }
+
+    /// Apply `suggestion`, anchored at `start` (1-based). Apply a file's
+    /// suggestions bottom-up so an earlier one can't shift a later anchor.
+    pub fn apply(&mut self, start: usize, suggestion: &Suggestion) -> Outcome {
+        let original = lines_of(&suggestion.original);
+        let replacement = lines_of(&suggestion.replacement);
+        // When both sides read true at the anchor, one is a prefix of the
+        // other: a suggestion that appends lines has been applied, one that
+        // drops trailing lines has not.
+        let anchored = start.checked_sub(1);
+        let reads = |lines: &[&str]| anchored.is_some_and(|at| self.matches(at, lines));
+        if !replacement.is_empty()
+            && reads(&replacement)
+            && (replacement.len() > original.len() || !reads(&original))
+        {
+            return Outcome::AlreadyApplied;
+        }
+        let found = self.find(&suggestion.original, start);
+        let Some(at) = found.and_then(|line| line.checked_sub(1)) else {
+            return Outcome::Conflict;
+        };
+
+        let eol = line_ending(&self.lines[at]);
+        let last_eol = line_ending(&self.lines[at + original.len() - 1]);
+        let count = replacement.len();
+        let new_lines = replacement.iter().enumerate().map(|(i, line)| {
+            let ending = if i + 1 == count { last_eol } else { eol };
+            format!("{line}{ending}")
+        });
+        self.lines.splice(at..at + original.len(), new_lines);
+        Outcome::Applied(at + 1)
+    }
+
+    /// Where `lines` (newline-separated) start: at `start` (1-based) when they
+    /// are still there, else where they moved to within [`DRIFT`] lines.
+    /// `None` when they're gone, too far, or found more than once there, and
+    /// for a `start` of 0, which no line has.
+    pub fn find(&self, lines: &str, start: usize) -> Option<usize> {
+        let at = start.checked_sub(1)?;
+        let expected = lines_of(lines);
+        if expected.is_empty() {
+            return None;
+        }
+        if self.matches(at, &expected) {
+            Some(start)
+        } else {
+            self.moved(&expected, at).map(|at| at + 1)
+        }
+    }
+
+    /// Lines `start..=end` (1-based), newline-separated without their endings.
+    pub fn lines(&self, start: usize, end: usize) -> Option<String> {
+        let lines = self.lines.get(start.checked_sub(1)?..end)?;
+        Some(
+            lines
+                .iter()
+                .map(|l| trim_eol(l))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+
+    /// Whether the lines from `at` (0-based) read as `expected`.
+    fn matches(&self, at: usize, expected: &[&str]) -> bool {
+        self.lines
+            .get(at..at + expected.len())
+            .is_some_and(|lines| {
+                lines
+                    .iter()
+                    .zip(expected)
+                    .all(|(line, want)| trim_eol(line) == trim_eol(want))
+            })
+    }
+
+    /// The one match for `expected` within [`DRIFT`] lines of `origin`.
+    fn moved(&self, expected: &[&str], origin: usize) -> Option<usize> {
+        let window = origin.saturating_sub(DRIFT)..=origin + DRIFT;
+        let mut candidates = window.filter(|&at| self.matches(at, expected));
+        match (candidates.next(), candidates.next()) {
+            (Some(at), None) => Some(at),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Text {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.lines.iter().try_for_each(|line| f.write_str(line))
+    }
+}
+
+/// The lines of a newline-separated suggestion side; empty text has none.
+fn lines_of(text: &str) -> Vec<&str> {
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        text.split('\n').collect()
+    }
+}
+
+fn trim_eol(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+fn line_ending(line: &str) -> &'static str {
+    if line.ends_with("\r\n") {
+        "\r\n"
+    } else if line.ends_with('\n') {
+        "\n"
+    } else {
+        ""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggestion(original: &str, replacement: &str) -> Suggestion {
+        Suggestion {
+            original: original.into(),
+            replacement: replacement.into(),
+        }
+    }
+
+    fn apply(content: &str, start: usize, s: &Suggestion) -> (String, Outcome) {
+        let mut text = Text::new(content);
+        let outcome = text.apply(start, s);
+        (text.to_string(), outcome)
+    }
+
+    #[test]
+    fn test_patch_replaces_and_deletes() {
+        let s = suggestion("let x = 1;\nlet y = 2;", "let x = 3;");
+        assert_eq!(
+            patch("a.rs", 4, &s),
+            "--- a/a.rs\n+++ b/a.rs\n@@ -4,2 +4,1 @@\n-let x = 1;\n-let y = 2;\n+let x = 3;\n"
+        );
+        let s = suggestion("dead();", "");
+        assert!(patch("a.rs", 4, &s).contains("@@ -4,1 +3,0 @@\n-dead();\n"));
+    }
+
+    #[test]
+    fn test_apply_at_anchor_keeps_line_endings() {
+        let s = suggestion("b\nc", "B");
+        assert_eq!(
+            apply("a\nb\nc\nd\n", 2, &s),
+            ("a\nB\nd\n".into(), Outcome::Applied(2))
+        );
+        assert_eq!(
+            apply("a\r\nb\r\nc", 2, &s),
+            ("a\r\nB".into(), Outcome::Applied(2))
+        );
+        let delete = suggestion("b", "");
+        assert_eq!(apply("a\nb\nc\n", 2, &delete).0, "a\nc\n");
+    }
+
+    #[test]
+    fn test_apply_prefix_suggestions() {
+        // Dropping the selection's trailing lines: the replacement reads true
+        // at the anchor, but so does the longer original.
+        let drop = suggestion("b\nc", "b");
+        assert_eq!(
+            apply("a\nb\nc\nd\n", 2, &drop),
+            ("a\nb\nd\n".into(), Outcome::Applied(2))
+        );
+        assert_eq!(apply("a\nb\nd\n", 2, &drop).1, Outcome::AlreadyApplied);
+        // Appending lines: applied once, then already there.
+        let append = suggestion("b", "b\nc");
+        assert_eq!(
+            apply("a\nb\nd\n", 2, &append),
+            ("a\nb\nc\nd\n".into(), Outcome::Applied(2))
+        );
+        assert_eq!(apply("a\nb\nc\nd\n", 2, &append).1, Outcome::AlreadyApplied);
+    }
+
+    #[test]
+    fn test_line_zero_is_never_found() {
+        // A corrupt or hand-edited review may carry line 0; it must not be
+        // read as line 1, nor underflow.
+        let s = suggestion("a", "A");
+        assert_eq!(Text::new("a\nb\n").find("a", 0), None);
+        assert_eq!(apply("a\nb\n", 0, &s), ("a\nb\n".into(), Outcome::Conflict));
+        assert_eq!(apply("A\nb\n", 0, &s).1, Outcome::Conflict);
+    }
+
+    #[test]
+    fn test_apply_follows_moved_lines_and_detects_conflicts() {
+        let s = suggestion("b", "B");
+        // Two lines were added above: the anchor moved down.
+        assert_eq!(
+            apply("x\ny\na\nb\nc\n", 2, &s),
+            ("x\ny\na\nB\nc\n".into(), Outcome::Applied(4))
+        );
+        // Already applied.
+        assert_eq!(apply("a\nB\nc\n", 2, &s).1, Outcome::AlreadyApplied);
+        // Edited since: left alone.
+        assert_eq!(
+            apply("a\nbb\nc\n", 2, &s),
+            ("a\nbb\nc\n".into(), Outcome::Conflict)
+        );
+        // Moved, but two copies nearby: ambiguous, even if one is nearer.
+        assert_eq!(apply("b\nx\nb\n", 2, &s).1, Outcome::Conflict);
+        assert_eq!(apply("x\nb\ny\nz\nb\n", 3, &s).1, Outcome::Conflict);
+        // Moved too far to be the same lines.
+        let far = format!("a\n{}b\n", "x\n".repeat(DRIFT + 1));
+        assert_eq!(apply(&far, 2, &s), (far.clone(), Outcome::Conflict));
+    }
+}
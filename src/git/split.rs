@@ -0,0 +1,325 @@
+//! Git-side support for `gx commit --ai-split`: enumerating the uncommitted
+//! changes as individually addressable hunks (via the [`review::diff`] model)
+//! and staging an arbitrary subset of them.
+//!
+//! Staging never goes through `git apply`: each touched file's index content
+//! is rebuilt from its `HEAD` version with exactly the chosen hunks spliced in,
+//! so hunks of one file can land in different commits in any grouping without
+//! patch offsets drifting between them.
+//!
+//! [`review::diff`]: super::review::diff
+
+use super::review::diff::{self, ChangedFile, FileDiff, Hunk, RowKind};
+use super::review::range::{self, Endpoint};
+use super::status::FileStatus;
+use super::{GitError, get_repo};
+use git2::{IndexEntry, IndexTime};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// One uncommitted file and its built diff.
+pub struct SplitFile {
+    pub changed: ChangedFile,
+    pub diff: FileDiff,
+}
+
+/// A unit the split plan can assign: one hunk of a file, or a whole file when
+/// it has no line hunks (binary, too large, or a pure rename/mode change).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitHunk {
+    pub file: usize,
+    pub hunk: Option<usize>,
+}
+
+/// A commit in a split plan: its message and the ids (indices into
+/// [`WorkingChanges::hunks`]) of the hunks it contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedCommit {
+    pub message: String,
+    pub hunks: Vec<usize>,
+}
+
+/// A proposed split: the commits in order, plus hunks left out of every
+/// commit (they stay uncommitted in the working tree).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitPlan {
+    pub commits: Vec<PlannedCommit>,
+    pub unassigned: Vec<usize>,
+}
+
+/// Everything between `HEAD` and the working tree (staged, unstaged, and
+/// untracked), hunk-indexed.
+pub struct WorkingChanges {
+    pub files: Vec<SplitFile>,
+    pub hunks: Vec<SplitHunk>,
+}
+
+impl WorkingChanges {
+    pub fn load() -> Result<Self, GitError> {
+        let range = range::resolve_uncommitted()?;
+        let mut files = Vec::new();
+        let mut hunks = Vec::new();
+        for changed in diff::changed_files(&range)? {
+            let diff = changed.build(range.to)?;
+            let file = files.len();
+            if diff.hunks.is_empty() {
+                hunks.push(SplitHunk { file, hunk: None });
+            } else {
+                hunks.extend((0..diff.hunks.len()).map(|h| SplitHunk {
+                    file,
+                    hunk: Some(h),
+                }));
+            }
+            files.push(SplitFile { changed, diff });
+        }
+        Ok(Self { files, hunks })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hunks.is_empty()
+    }
+
+    fn hunk(&self, id: usize) -> (&SplitFile, Option<&Hunk>) {
+        let SplitHunk { file, hunk } = self.hunks[id];
+        let file = &self.files[file];
+        (file, hunk.map(|h| &file.diff.hunks[h]))
+    }
+
+    /// `path @@ -a,b +c,d @@`, or `path (whole file, <reason>)`.
+    pub fn label(&self, id: usize) -> String {
+        let (file, hunk) = self.hunk(id);
+        match hunk {
+            Some(hunk) => format!("{} {}", file.diff.path, hunk.header),
+            None => format!(
+                "{} (whole file, {})",
+                file.diff.path,
+                whole_file_reason(file)
+            ),
+        }
+    }
+
+    /// Added and removed line counts.
+    pub fn stats(&self, id: usize) -> (usize, usize) {
+        let (_, hunk) = self.hunk(id);
+        let rows = hunk.map(|h| h.rows.as_slice()).unwrap_or_default();
+        let count = |kind| rows.iter().filter(|r| r.kind == kind).count();
+        (count(RowKind::Added), count(RowKind::Removed))
+    }
+
+    /// The hunk body as unified-diff lines (` `, `+`, `-` prefixes).
+    pub fn patch_lines(&self, id: usize) -> Vec<String> {
+        let (_, hunk) = self.hunk(id);
+        hunk.map(|h| {
+            h.rows
+                .iter()
+                .map(|row| {
+                    let prefix = match row.kind {
+                        RowKind::Context => ' ',
+                        RowKind::Added => '+',
+                        RowKind::Removed => '-',
+                    };
+                    format!("{prefix}{}", row.text)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    /// Stage the files touched by `newly` so each holds its `HEAD` content
+    /// with every hunk in `applied` (which includes `newly` and all earlier
+    /// commits' hunks) spliced in. Files whose hunks are all applied are
+    /// staged straight from the working tree, which also carries mode changes.
+    pub fn stage(&self, applied: &HashSet<usize>, newly: &[usize]) -> Result<(), GitError> {
+        let repo = get_repo()?;
+        let head = repo.head()?.peel_to_tree()?;
+        let mut index = repo.index()?;
+
+        let touched: HashSet<usize> = newly.iter().map(|&id| self.hunks[id].file).collect();
+        let mut touched: Vec<usize> = touched.into_iter().collect();
+        touched.sort_unstable();
+
+        for file_idx in touched {
+            let file = &self.files[file_idx];
+            let ids: Vec<usize> = (0..self.hunks.len())
+                .filter(|&id| self.hunks[id].file == file_idx)
+                .collect();
+            let path = Path::new(&file.diff.path);
+            if let Some(old_path) = &file.diff.old_path {
+                index.remove_path(Path::new(old_path))?;
+            }
+
+            if ids.iter().all(|id| applied.contains(id)) {
+                let exists = repo
+                    .workdir()
+                    .is_some_and(|root| root.join(path).symlink_metadata().is_ok());
+                if exists {
+                    index.add_path(path)?;
+                } else {
+                    index.remove_path(path)?;
+                }
+                continue;
+            }
+
+            let hunks: Vec<&Hunk> = ids
+                .iter()
+                .filter(|id| applied.contains(id))
+                .filter_map(|&id| self.hunk(id).1)
+                .collect();
+            let (old, new) = file.changed.load_bytes(Endpoint::WorkingTree)?;
+            let content = apply_hunks(&old, &new, &hunks);
+
+            let head_path = file.diff.old_path.as_deref().unwrap_or(&file.diff.path);
+            let mode = head
+                .get_path(Path::new(head_path))
+                .map(|entry| entry.filemode() as u32)
+                .unwrap_or(0o100644);
+            add_blob(&repo, &mut index, &file.diff.path, &content, mode)?;
+        }
+
+        index.write()?;
+        Ok(())
+    }
+}
+
+/// Stage `content` as `path` in `index` (written to the object database
+/// first), without touching the working tree.
+pub(crate) fn add_blob(
+    repo: &git2::Repository,
+    index: &mut git2::Index,
+    path: &str,
+    content: &[u8],
+    mode: u32,
+) -> Result<(), GitError> {
+    let id = repo.blob(content)?;
+    index.add(&IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: content.len() as u32,
+        id,
+        flags: 0,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    })?;
+    Ok(())
+}
+
+fn whole_file_reason(file: &SplitFile) -> &'static str {
+    if file.diff.is_binary {
+        "binary"
+    } else if file.diff.too_large {
+        "too large to diff"
+    } else {
+        match file.diff.status {
+            FileStatus::Renamed => "rename",
+            FileStatus::Deleted => "deleted",
+            FileStatus::New => "empty file",
+            _ => "mode change",
+        }
+    }
+}
+
+/// Reset the index to `HEAD`, so a split starts from nothing staged. The
+/// working tree is untouched.
+pub fn reset_index() -> Result<(), GitError> {
+    let repo = get_repo()?;
+    let head = repo.head()?.peel_to_tree()?;
+    let mut index = repo.index()?;
+    index.read_tree(&head)?;
+    index.write()?;
+    Ok(())
+}
+
+/// Rebuild `old` with `hunks` (a subset of the old→new diff's hunks, in file
+/// order) applied. Each hunk's rows carry its old- and new-side line spans;
+/// the old span is replaced by the new span byte-for-byte, so line endings and
+/// a missing final newline survive.
+fn apply_hunks(old: &[u8], new: &[u8], hunks: &[&Hunk]) -> Vec<u8> {
+    let old_lines: Vec<&[u8]> = old.split_inclusive(|&b| b == b'\n').collect();
+    let new_lines: Vec<&[u8]> = new.split_inclusive(|&b| b == b'\n').collect();
+
+    let mut out = Vec::with_capacity(new.len());
+    let mut cursor = 0;
+    for hunk in hunks {
+        let (old_start, old_end) =
+            span(hunk.rows.iter().filter_map(|r| r.old_no)).unwrap_or((cursor, cursor));
+        let (new_start, new_end) =
+            span(hunk.rows.iter().filter_map(|r| r.new_no)).unwrap_or((0, 0));
+        for line in &old_lines[cursor..old_start] {
+            out.extend_from_slice(line);
+        }
+        for line in &new_lines[new_start..new_end] {
+            out.extend_from_slice(line);
+        }
+        cursor = old_end;
+    }
+    for line in &old_lines[cursor..] {
+        out.extend_from_slice(line);
+    }
+    out
+}
+
+/// 1-based line numbers → a 0-based half-open `(start, end)` span.
+fn span(numbers: impl Iterator<Item = usize>) -> Option<(usize, usize)> {
+    let mut bounds: Option<(usize, usize)> = None;
+    for n in numbers {
+        bounds = Some(match bounds {
+            None => (n, n),
+            Some((lo, hi)) => (lo.min(n), hi.max(n)),
+        });
+    }
+    bounds.map(|(lo, hi)| (lo - 1, hi))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::review::diff::build_hunks;
+
+    fn apply(old: &str, new: &str, pick: &[usize]) -> String {
+        let hunks = build_hunks(old, new);
+        let chosen: Vec<&Hunk> = pick.iter().map(|&i| &hunks[i]).collect();
+        String::from_utf8(apply_hunks(old.as_bytes(), new.as_bytes(), &chosen)).unwrap()
+    }
+
+    fn numbered(n: usize) -> String {
+        (1..=n).map(|i| format!("line {i}\n")).collect()
+    }
+
+    #[test]
+    fn test_apply_hunks_picks_a_subset() {
+        let old = numbered(30);
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 25\n", "line 25\nline 25b\n");
+        assert_eq!(build_hunks(&old, &new).len(), 2);
+
+        assert_eq!(apply(&old, &new, &[]), old);
+        assert_eq!(apply(&old, &new, &[0, 1]), new);
+        assert_eq!(
+            apply(&old, &new, &[0]),
+            old.replace("line 2\n", "line two\n")
+        );
+        assert_eq!(
+            apply(&old, &new, &[1]),
+            old.replace("line 25\n", "line 25\nline 25b\n")
+        );
+    }
+
+    #[test]
+    fn test_apply_hunks_new_and_deleted_files() {
+        assert_eq!(apply("", "a\nb\n", &[0]), "a\nb\n");
+        assert_eq!(apply("a\nb\n", "", &[0]), "");
+    }
+
+    #[test]
+    fn test_apply_hunks_keeps_crlf_and_missing_final_newline() {
+        let old = "a\r\nb\r\nc";
+        let new = "a\r\nB\r\nc";
+        assert_eq!(apply(old, new, &[0]), new);
+    }
+}
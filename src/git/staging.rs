@@ -108,7 +108,25 @@ fn diff_index_against(
 ) -> Result<String, GitError> {
     let mut diff_options = git2::DiffOptions::new();
     let diff = repo.diff_tree_to_index(old_tree, Some(&repo.index()?), Some(&mut diff_options))?;
+    patch_text(&diff)
+}
+
+/// Every uncommitted change (staged, unstaged, and untracked files) as one
+/// patch against `HEAD`.
+pub fn get_uncommitted_diff() -> Result<String, GitError> {
+    let repo = get_repo()?;
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let mut diff_options = git2::DiffOptions::new();
+    diff_options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    let diff = repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut diff_options))?;
+    patch_text(&diff)
+}
 
+/// Render `diff` as unified patch text.
+pub(crate) fn patch_text(diff: &git2::Diff) -> Result<String, GitError> {
     let mut diff_text = String::new();
     diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
         let content = std::str::from_utf8(line.content()).unwrap_or("");
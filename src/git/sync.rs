@@ -0,0 +1,171 @@
+//! The git side of `gx sync`: a pruning fetch, fast-forwarding a branch that
+//! is not checked out, and bringing the current branch up to date with its
+//! upstream.
+
+use super::git_exec::{ExecOptions, exec, map_git_error};
+use super::{GitError, get_repo};
+use std::process::Command;
+use std::str::FromStr;
+
+/// How the current branch takes in its upstream's new commits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Rebase,
+    Merge,
+    FfOnly,
+}
+
+impl Strategy {
+    /// The `git` invocation that applies `upstream` to the checked-out branch.
+    /// Both commands stash and restore local changes themselves.
+    fn args(self, upstream: &str) -> Vec<&str> {
+        match self {
+            Strategy::Rebase => vec!["rebase", "--autostash", upstream],
+            Strategy::Merge => vec!["merge", "--autostash", "--no-edit", upstream],
+            Strategy::FfOnly => vec!["merge", "--autostash", "--ff-only", upstream],
+        }
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "rebase" => Ok(Strategy::Rebase),
+            "merge" => Ok(Strategy::Merge),
+            "ff-only" | "ff_only" => Ok(Strategy::FfOnly),
+            other => Err(format!(
+                "unknown update strategy '{other}' (expected rebase, merge, or ff-only)"
+            )),
+        }
+    }
+}
+
+/// What a fetch changed among the remote-tracking refs.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FetchSummary {
+    /// New or moved remote-tracking refs (branches and tags).
+    pub updated: usize,
+    /// Remote-tracking refs removed because the remote branch is gone.
+    pub pruned: usize,
+}
+
+/// `git fetch --all --prune`, summarized from git's ref-update report.
+pub fn fetch_prune() -> Result<FetchSummary, GitError> {
+    // git reports ref updates on stderr, so `exec` (stdout only) can't be used.
+    let output = Command::new("git")
+        .args(["fetch", "--all", "--prune"])
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => GitError::NotFound(e),
+            _ => GitError::IoError(e),
+        })?;
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if !output.status.success() {
+        return Err(map_git_error(stderr, output.status.code()));
+    }
+    Ok(parse_fetch_report(&stderr))
+}
+
+/// Count the `<flag> <summary> <from> -> <to>` lines of a fetch report;
+/// `[deleted]` ones are prunes.
+fn parse_fetch_report(stderr: &str) -> FetchSummary {
+    let mut summary = FetchSummary::default();
+    for line in stderr.lines().filter(|l| l.contains(" -> ")) {
+        if line.contains("[deleted]") {
+            summary.pruned += 1;
+        } else {
+            summary.updated += 1;
+        }
+    }
+    summary
+}
+
+/// The remote-tracking branch `branch` follows (e.g. `origin/main`), or
+/// `None` when it has no upstream or the upstream ref no longer exists.
+pub fn tracking_branch(branch: &str) -> Result<Option<String>, GitError> {
+    let repo = get_repo()?;
+    let Ok(local) = repo.find_branch(branch, git2::BranchType::Local) else {
+        return Ok(None);
+    };
+    let Ok(upstream) = local.upstream() else {
+        return Ok(None);
+    };
+    Ok(upstream.name()?.map(str::to_string))
+}
+
+/// The result of fast-forwarding a branch that is not checked out.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FastForward {
+    UpToDate,
+    /// Moved forward by this many commits.
+    Advanced(usize),
+    /// The branch has commits its upstream lacks; left where it was.
+    Diverged {
+        ahead: usize,
+        behind: usize,
+    },
+}
+
+/// Move the local `branch` to `upstream` when that is a fast-forward. Only
+/// the ref is updated, so the caller must make sure no worktree has `branch`
+/// checked out.
+pub fn fast_forward_ref(branch: &str, upstream: &str) -> Result<FastForward, GitError> {
+    let repo = get_repo()?;
+    let mut local = repo.find_reference(&format!("refs/heads/{branch}"))?;
+    let local_oid = local.peel_to_commit()?.id();
+    let upstream_oid = repo.revparse_single(upstream)?.peel_to_commit()?.id();
+
+    let (ahead, behind) = repo.graph_ahead_behind(local_oid, upstream_oid)?;
+    Ok(match (ahead, behind) {
+        (0, 0) => FastForward::UpToDate,
+        (0, behind) => {
+            local.set_target(
+                upstream_oid,
+                &format!("gx sync: fast-forward {branch} to {upstream}"),
+            )?;
+            FastForward::Advanced(behind)
+        }
+        (ahead, behind) => FastForward::Diverged { ahead, behind },
+    })
+}
+
+/// Apply `upstream` to the checked-out branch with `strategy`.
+pub fn update_current(upstream: &str, strategy: Strategy) -> Result<(), GitError> {
+    exec(strategy.args(upstream), ExecOptions::silent())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategy_from_str() {
+        assert_eq!("rebase".parse::<Strategy>(), Ok(Strategy::Rebase));
+        assert_eq!(" Merge ".parse::<Strategy>(), Ok(Strategy::Merge));
+        assert_eq!("ff-only".parse::<Strategy>(), Ok(Strategy::FfOnly));
+        assert!("squash".parse::<Strategy>().is_err());
+    }
+
+    #[test]
+    fn test_parse_fetch_report_counts_updates_and_prunes() {
+        let stderr = "\
+Fetching origin
+From github.com:acme/repo
+ - [deleted]         (none)     -> origin/old-feature
+   1a2b3c4..5d6e7f8  main       -> origin/main
+ * [new branch]      feat/x     -> origin/feat/x
+ + 0a0a0a0...1b1b1b1 wip        -> origin/wip  (forced update)
+ * [new tag]         v1.2.0     -> v1.2.0";
+        assert_eq!(
+            parse_fetch_report(stderr),
+            FetchSummary {
+                updated: 4,
+                pruned: 1
+            }
+        );
+        assert_eq!(parse_fetch_report(""), FetchSummary::default());
+    }
+}
@@ -3,11 +3,13 @@ mod args;
 mod browser;
 mod clipboard;
 mod commands;
+mod commit_lint;
 mod config;
 mod git;
 mod output;
 mod repo_config;
 mod repo_setup;
+mod secret_scan;
 mod ui;
 
 use clap::Parser;
@@ -34,5 +36,8 @@ fn main() -> Result<()> {
     }
 
     let cli = args::Cli::parse();
+    if cli.no_cache {
+        ai::cache::bypass();
+    }
     cli.command.run()
 }
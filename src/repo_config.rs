@@ -41,6 +41,12 @@ pub struct RepoConfigFile {
 
     #[serde(default)]
     pub workspace: RepoWorkspaceSection,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<RepoCommitSection>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub land: Option<RepoLandSection>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
@@ -88,13 +94,45 @@ pub struct HooksSection {
     pub post_create: Option<Vec<String>>,
 }
 
-/// Branch protection list (consumed by section 1, not this task).
+/// Branch protection list, consumed by `gx push`'s protected-branch check.
 #[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub struct ProtectionSection {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub branches: Option<Vec<String>>,
 }
 
+/// Commit-message conventions (`[commit]`), resolved by
+/// `commit_lint::resolve`. Every field replaces the global `[commit]` value
+/// when set.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct RepoCommitSection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub types: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_subject_length: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ticket_pattern: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lint: Option<bool>,
+}
+
+/// `gx land` settings (`[land]`); each field replaces the global `[land]`
+/// value when set.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct RepoLandSection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_command: Option<String>,
+}
+
 /// The single resolved policy that callers consume. Built by [`resolve`] from
 /// all config layers; CLI flags are then applied on top by the caller.
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -525,6 +563,8 @@ mod tests {
                 update_strategy: Some("rebase".to_string()),
                 ..Default::default()
             },
+            commit: None,
+            land: None,
         };
         let local = RepoConfigFile {
             version: Some(1),
@@ -533,6 +573,8 @@ mod tests {
                 update_strategy: Some("merge".to_string()),
                 ..Default::default()
             },
+            commit: None,
+            land: None,
         };
         let global = config::Config::default();
         let personal = empty_personal(&main_root);
@@ -584,6 +584,18 @@ fn collect_candidates(
     Ok(())
 }
 
+/// Whether the repo-relative `path` is covered by the copy-files `pattern`:
+/// it matches the pattern itself or sits under a directory that does.
+pub fn copy_pattern_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim().trim_matches('/');
+    if pattern.is_empty() {
+        return false;
+    }
+    let pattern_parts: Vec<&str> = pattern.split('/').collect();
+    let path_parts: Vec<&str> = path.split('/').collect();
+    (1..=path_parts.len()).any(|n| glob_parts_match(&pattern_parts, &path_parts[..n]))
+}
+
 fn glob_parts_match(pattern: &[&str], text: &[&str]) -> bool {
     match (pattern.first(), text.first()) {
         (None, None) => true,
@@ -775,6 +787,15 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_copy_pattern_matches_files_and_directory_contents() {
+        assert!(copy_pattern_matches(".env*", ".env.local"));
+        assert!(copy_pattern_matches("**/.env", "apps/web/.env"));
+        assert!(copy_pattern_matches(".vscode", ".vscode/settings.json"));
+        assert!(!copy_pattern_matches(".env", "apps/.env"));
+        assert!(!copy_pattern_matches("", ".env"));
+    }
+
     #[test]
     fn test_copy_setup_files_never_copies_git() {
         let tmp =
@@ -94,3 +94,61 @@ fn render<W: Write>(message: &str, selected: usize, writer: &mut W) -> io::Resul
 
     writer.flush()
 }
+
+/// Single-key choice rendered on stderr: `message  [o]pen  [c]opy  (Esc to
+/// skip)`. Returns the chosen key, or `None` on Esc/Enter/`q`.
+pub fn choose_on_stderr(message: &str, options: &[(char, &str)]) -> miette::Result<Option<char>> {
+    let mut stderr = io::stderr();
+    enable_raw_mode().into_diagnostic()?;
+    let result = choose(message, options, &mut stderr);
+    disable_raw_mode().ok();
+    writeln!(stderr).ok();
+    result
+}
+
+fn choose<W: Write>(
+    message: &str,
+    options: &[(char, &str)],
+    writer: &mut W,
+) -> miette::Result<Option<char>> {
+    queue!(
+        writer,
+        MoveToColumn(0),
+        Clear(ClearType::CurrentLine),
+        SetForegroundColor(Color::Yellow),
+        Print(message),
+        ResetColor,
+    )
+    .into_diagnostic()?;
+    for (key, label) in options {
+        queue!(
+            writer,
+            Print("  "),
+            SetForegroundColor(Color::Green),
+            Print(format!("[{key}]")),
+            ResetColor,
+            Print(label),
+        )
+        .into_diagnostic()?;
+    }
+    queue!(
+        writer,
+        SetForegroundColor(Color::DarkGrey),
+        Print("  (Esc to skip)"),
+        ResetColor
+    )
+    .into_diagnostic()?;
+    writer.flush().into_diagnostic()?;
+
+    loop {
+        if let Event::Key(key) = event::read().into_diagnostic()? {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => return Ok(None),
+                KeyCode::Char(c) if options.iter().any(|(key, _)| *key == c) => {
+                    return Ok(Some(c));
+                }
+                _ => {}
+            }
+        }
+    }
+}
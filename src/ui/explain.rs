@@ -0,0 +1,192 @@
+//! The "explain" side panel shared by the log viewer and the review TUI: a
+//! commit's (or file's) diff goes to the `explain_agent` provider on a
+//! background thread, and the answer is shown in a scrollable panel.
+//!
+//! Answers are cached per key (a commit oid, or a file's diff in a review) for
+//! the life of the viewer, so revisiting a commit never re-runs the agent.
+
+use crate::ai::diff::{self, DiffBudget};
+use crate::ai::prompts::{self, Template};
+use crate::ai::{Feature, Provider};
+use crate::config::{self, AiConfig};
+use crate::output;
+use ratatui::prelude::*;
+use ratatui::widgets::*;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// The prompt inputs for one explanation.
+pub struct Request {
+    pub subject: String,
+    pub diff: String,
+}
+
+enum Explanation {
+    Loading,
+    Ready(String),
+    Failed(String),
+}
+
+pub struct Explainer {
+    provider: Result<Provider, String>,
+    model: String,
+    budget: DiffBudget,
+    template: String,
+    cache: HashMap<String, Explanation>,
+    pending: Vec<(String, Receiver<Result<String, String>>)>,
+    /// Whether the panel is visible.
+    pub open: bool,
+    scroll: u16,
+}
+
+impl Explainer {
+    /// Resolve the provider, model, budget and template once, up front. Explain
+    /// is optional, so a config or template that fails to load only disables
+    /// it (with a warning); that error, like a missing provider, is reported in
+    /// the panel when explain is first used.
+    pub fn load() -> Self {
+        let loaded = config::load().and_then(|config| {
+            let template = prompts::load(Template::Explain)?;
+            Ok((config.ai, template))
+        });
+        let (ai, provider, template) = match loaded {
+            Ok((ai, template)) => {
+                let provider = Provider::for_feature(&ai, Feature::Explain);
+                (ai, provider, template)
+            }
+            Err(e) => {
+                let e = format!("explain is unavailable: {e}");
+                output::warn(&e);
+                (AiConfig::default(), Err(e), String::new())
+            }
+        };
+        Explainer {
+            provider,
+            model: ai.model.clone(),
+            budget: DiffBudget::from_config(&ai),
+            template,
+            cache: HashMap::new(),
+            pending: Vec::new(),
+            open: false,
+            scroll: 0,
+        }
+    }
+
+    /// Show the panel for `key`. Unless it is cached or already running,
+    /// `request` builds the prompt inputs and the agent starts in the
+    /// background.
+    pub fn explain(&mut self, key: &str, request: impl FnOnce() -> Result<Request, String>) {
+        self.open = true;
+        self.scroll = 0;
+        if self.cache.contains_key(key) {
+            return;
+        }
+        let provider = match &self.provider {
+            Ok(provider) => provider.clone(),
+            Err(e) => {
+                self.cache
+                    .insert(key.to_string(), Explanation::Failed(e.clone()));
+                return;
+            }
+        };
+        let request = match request() {
+            Ok(request) => request,
+            Err(e) => {
+                self.cache.insert(key.to_string(), Explanation::Failed(e));
+                return;
+            }
+        };
+
+        let patch = diff::prepare(&request.diff, &self.budget);
+        let prompt = prompts::render(
+            &self.template,
+            &[("subject", &request.subject), ("diff", &patch)],
+        );
+        let input = (!prompts::mentions(&self.template, "diff")).then_some(patch);
+        let model = self.model.clone();
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let result = provider
+                .run_capturing(&model, &prompt, input.as_deref())
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+        self.cache.insert(key.to_string(), Explanation::Loading);
+        self.pending.push((key.to_string(), rx));
+    }
+
+    /// Collect finished answers. Returns true when one landed, so callers that
+    /// only redraw on input know to redraw.
+    pub fn poll(&mut self) -> bool {
+        let mut landed = false;
+        let cache = &mut self.cache;
+        self.pending.retain(|(key, rx)| match rx.try_recv() {
+            Ok(result) => {
+                let explanation = match result {
+                    Ok(text) => Explanation::Ready(text),
+                    Err(e) => Explanation::Failed(e),
+                };
+                cache.insert(key.clone(), explanation);
+                landed = true;
+                false
+            }
+            Err(mpsc::TryRecvError::Empty) => true,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                cache.insert(
+                    key.clone(),
+                    Explanation::Failed("the agent thread stopped".to_string()),
+                );
+                landed = true;
+                false
+            }
+        });
+        landed
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn scroll_by(&mut self, delta: i16) {
+        self.scroll = self.scroll.saturating_add_signed(delta);
+    }
+
+    /// Reset the scroll position when the selection the panel follows moves.
+    pub fn reset_scroll(&mut self) {
+        self.scroll = 0;
+    }
+
+    /// Render the panel for the current selection's `key`; `noun` names what
+    /// is selected ("commit", "file") in the hint shown before it is asked.
+    pub fn render(&self, f: &mut Frame, area: Rect, key: Option<&str>, noun: &str) {
+        let provider = match &self.provider {
+            Ok(provider) => provider.to_string(),
+            Err(_) => "agent".to_string(),
+        };
+        let dim = Style::default().fg(Color::DarkGray);
+        let text = match key.and_then(|k| self.cache.get(k)) {
+            None => Text::styled(format!("Press e to explain this {noun}."), dim),
+            Some(Explanation::Loading) => Text::styled(format!("Asking {provider}…"), dim),
+            Some(Explanation::Ready(text)) => Text::raw(text.clone()),
+            Some(Explanation::Failed(e)) => Text::styled(
+                format!("Explain failed: {e}"),
+                Style::default().fg(Color::Red),
+            ),
+        };
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::Cyan))
+                        .title(format!(" Explain ({provider}) ")),
+                ),
+            area,
+        );
+    }
+}
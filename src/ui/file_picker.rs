@@ -150,9 +150,7 @@ pub fn run(
                     selected_index = selected_index.saturating_sub(1);
                 }
                 (KeyCode::Down, _) | (KeyCode::Char('j'), _) => {
-                    if selected_index < all_files.len() - 1 {
-                        selected_index += 1;
-                    }
+                    selected_index = (selected_index + 1).min(all_files.len().saturating_sub(1));
                 }
                 (KeyCode::Char(' '), _) => {
                     if selected_files.contains(&selected_index) {
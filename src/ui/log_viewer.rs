@@ -1,3 +1,4 @@
+use super::explain::{Explainer, Request};
 use super::{Term, adjust_scroll, render_help_bar, truncate};
 use crate::git::log::{CommitDetails, LogGraph};
 use crossterm::event::{self, Event, KeyCode, KeyModifiers};
@@ -13,7 +14,11 @@ pub enum LogAction {
     Quit,
 }
 
-pub fn run(terminal: &mut Term, log: &LogGraph) -> miette::Result<LogAction> {
+pub fn run(
+    terminal: &mut Term,
+    log: &LogGraph,
+    explainer: &mut Explainer,
+) -> miette::Result<LogAction> {
     if log.entries.is_empty() {
         return Ok(LogAction::Quit);
     }
@@ -33,7 +38,9 @@ pub fn run(terminal: &mut Term, log: &LogGraph) -> miette::Result<LogAction> {
             pending_fetch = true;
             last_selection_change = Instant::now();
             details = None;
+            explainer.reset_scroll();
         }
+        explainer.poll();
 
         if pending_fetch && last_selection_change.elapsed() >= Duration::from_millis(DEBOUNCE_MS) {
             pending_fetch = false;
@@ -50,22 +57,44 @@ pub fn run(terminal: &mut Term, log: &LogGraph) -> miette::Result<LogAction> {
                     .constraints([Constraint::Min(0), Constraint::Length(3)])
                     .split(area);
 
+                // The explain panel takes the details pane's place, wider.
+                let side = if explainer.open { 50 } else { 30 };
                 let main_chunks = Layout::default()
                     .direction(Direction::Horizontal)
-                    .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+                    .constraints([
+                        Constraint::Percentage(100 - side),
+                        Constraint::Percentage(side),
+                    ])
                     .split(chunks[0]);
 
                 let visible_height = main_chunks[0].height.saturating_sub(2) as usize;
                 scroll_offset = adjust_scroll(selected_index, scroll_offset, visible_height);
 
                 render_log_list(f, main_chunks[0], log, selected_index, scroll_offset);
-                render_details_pane(f, main_chunks[1], details.as_ref());
+                if explainer.open {
+                    let key = current_oid.map(|oid| oid.to_string());
+                    explainer.render(f, main_chunks[1], key.as_deref(), "commit");
+                } else {
+                    render_details_pane(f, main_chunks[1], details.as_ref());
+                }
 
-                let help = render_help_bar(&[
-                    ("j/k", "navigate"),
-                    ("enter/c", "checkout"),
-                    ("q/esc", "quit"),
-                ]);
+                let help = if explainer.open {
+                    render_help_bar(&[
+                        ("j/k", "navigate"),
+                        ("e", "explain"),
+                        ("J/K", "scroll"),
+                        ("esc", "close panel"),
+                        ("enter/c", "checkout"),
+                        ("q", "quit"),
+                    ])
+                } else {
+                    render_help_bar(&[
+                        ("j/k", "navigate"),
+                        ("e", "explain"),
+                        ("enter/c", "checkout"),
+                        ("q/esc", "quit"),
+                    ])
+                };
                 f.render_widget(help, chunks[1]);
             })
             .into_diagnostic()?;
@@ -74,6 +103,7 @@ pub fn run(terminal: &mut Term, log: &LogGraph) -> miette::Result<LogAction> {
             && let Event::Key(key) = event::read().into_diagnostic()?
         {
             match (key.code, key.modifiers) {
+                (KeyCode::Esc, _) if explainer.open => explainer.close(),
                 (KeyCode::Esc, _)
                 | (KeyCode::Char('q'), _)
                 | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
@@ -83,9 +113,7 @@ pub fn run(terminal: &mut Term, log: &LogGraph) -> miette::Result<LogAction> {
                     selected_index = selected_index.saturating_sub(1);
                 }
                 (KeyCode::Down, _) | (KeyCode::Char('j'), _) => {
-                    if selected_index + 1 < log.entries.len() {
-                        selected_index += 1;
-                    }
+                    selected_index = (selected_index + 1).min(log.entries.len().saturating_sub(1));
                 }
                 (KeyCode::PageUp, _) => {
                     selected_index = selected_index.saturating_sub(10);
@@ -99,6 +127,13 @@ pub fn run(terminal: &mut Term, log: &LogGraph) -> miette::Result<LogAction> {
                 (KeyCode::End, _) | (KeyCode::Char('G'), _) => {
                     selected_index = log.entries.len().saturating_sub(1);
                 }
+                (KeyCode::Char('e'), _) => {
+                    if let Some(oid) = current_oid {
+                        explainer.explain(&oid.to_string(), || explain_request(oid));
+                    }
+                }
+                (KeyCode::Char('J'), _) => explainer.scroll_by(3),
+                (KeyCode::Char('K'), _) => explainer.scroll_by(-3),
                 (KeyCode::Enter, _) | (KeyCode::Char('c'), KeyModifiers::NONE) => {
                     if let Some(entry) = log.entries.get(selected_index) {
                         return Ok(LogAction::Checkout(entry.oid));
@@ -110,6 +145,18 @@ pub fn run(terminal: &mut Term, log: &LogGraph) -> miette::Result<LogAction> {
     }
 }
 
+/// The commit's message and patch for the explain panel.
+fn explain_request(oid: git2::Oid) -> Result<Request, String> {
+    let details = crate::git::log::get_commit_details(oid).map_err(|e| e.to_string())?;
+    let diff = crate::git::log::get_commit_patch(oid).map_err(|e| e.to_string())?;
+    let mut subject = format!("Commit {}\n\n{}", details.full_id, details.summary);
+    if let Some(body) = &details.body {
+        subject.push_str("\n\n");
+        subject.push_str(body);
+    }
+    Ok(Request { subject, diff })
+}
+
 fn render_log_list(
     f: &mut ratatui::Frame,
     area: Rect,
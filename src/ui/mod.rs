@@ -1,11 +1,13 @@
 pub mod branch_picker;
 pub mod clean_picker;
 pub mod confirm;
+pub mod explain;
 pub mod file_picker;
 pub mod log_viewer;
 pub mod pr_picker;
 pub mod review;
 pub mod setup_file_picker;
+pub mod split_plan;
 pub mod stash_picker;
 pub mod status;
 pub mod terminal;
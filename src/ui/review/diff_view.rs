@@ -13,10 +13,12 @@
 use crate::git::GitError;
 use crate::git::review::diff::{ChangedFile, FileDiff, Row, RowKind};
 use crate::git::review::range::Endpoint;
+use crate::git::review::stage::Selection;
 use crate::git::review::state::{Marks, Side};
 use ratatui::Frame;
 use ratatui::prelude::*;
 use ratatui::widgets::*;
+use std::collections::{HashMap, HashSet};
 
 use super::Appearance;
 use super::highlight::{Highlighter, Segment};
@@ -201,6 +203,47 @@ pub fn hunk_header_indices(rf: &RenderedFile, view: ViewMode) -> Vec<usize> {
     }
 }
 
+// --- Staging selections (cursor -> change rows) -----------------------------
+
+/// The changes shown on visual lines `[lo, hi]`, for staging a selection.
+pub fn selection_in(rf: &RenderedFile, view: ViewMode, lo: usize, hi: usize) -> Selection {
+    let (lo, hi) = (lo.min(hi), lo.max(hi));
+    let mut selection = Selection::default();
+    match view {
+        ViewMode::SideBySide => {
+            for line in side_lines(&rf.diff).iter().take(hi + 1).skip(lo) {
+                if let SideLine::Pair { left, right } = line {
+                    left.iter()
+                        .chain(right)
+                        .for_each(|row| selection.insert(row));
+                }
+            }
+        }
+        ViewMode::Unified => {
+            for line in uni_lines(&rf.diff).iter().take(hi + 1).skip(lo) {
+                if let UniLine::Row(row) = line {
+                    selection.insert(row);
+                }
+            }
+        }
+    }
+    selection
+}
+
+/// Every change in the hunk the cursor is in (its header included).
+pub fn hunk_selection(rf: &RenderedFile, view: ViewMode, cursor: usize) -> Selection {
+    let mut selection = Selection::default();
+    let hunk = hunk_header_indices(rf, view)
+        .iter()
+        .filter(|&&i| i <= cursor)
+        .count()
+        .checked_sub(1);
+    if let Some(hunk) = hunk.and_then(|i| rf.diff.hunks.get(i)) {
+        hunk.rows.iter().for_each(|row| selection.insert(row));
+    }
+    selection
+}
+
 // --- Anchoring (cursor -> comment target) -----------------------------------
 
 /// Where a comment would attach for a given cursor position.
@@ -226,6 +269,27 @@ pub fn anchor_at(rf: &RenderedFile, view: ViewMode, cursor: usize) -> Option<Anc
     }
 }
 
+/// The cursor's line number on each side, `(old, new)`; either is `None` where
+/// that side has no line (added/removed rows, gaps, hunk headers).
+pub fn lines_at(
+    rf: &RenderedFile,
+    view: ViewMode,
+    cursor: usize,
+) -> (Option<usize>, Option<usize>) {
+    match view {
+        ViewMode::SideBySide => match side_lines(&rf.diff).get(cursor) {
+            Some(SideLine::Pair { left, right }) => {
+                (left.and_then(|r| r.old_no), right.and_then(|r| r.new_no))
+            }
+            _ => (None, None),
+        },
+        ViewMode::Unified => match uni_lines(&rf.diff).get(cursor) {
+            Some(UniLine::Row(row)) => (row.old_no, row.new_no),
+            _ => (None, None),
+        },
+    }
+}
+
 fn pair_anchor(left: Option<&Row>, right: Option<&Row>) -> Option<Anchor> {
     if let Some(r) = right
         && let Some(line) = r.new_no
@@ -399,7 +463,9 @@ fn gutter_width(diff: &FileDiff) -> usize {
     let mut max = 0usize;
     for hunk in &diff.hunks {
         for row in &hunk.rows {
-            max = max.max(row.old_no.unwrap_or(0)).max(row.new_no.unwrap_or(0));
+            max = max
+                .max(row.old_no.unwrap_or(0))
+                .max(row.new_no.unwrap_or(0));
         }
     }
     max.to_string().len().max(3)
@@ -420,7 +486,7 @@ fn side_line_to_line<'a>(
     let body_width = width.saturating_sub(1);
     match line {
         SideLine::Header(h) => {
-            let mut spans = vec![marker_span(false, cursor, pal)];
+            let mut spans = vec![marker_span(Marker::None, cursor, pal)];
             spans.extend(header_spans(h, body_width, cursor, pal));
             Line::from(spans)
         }
@@ -429,7 +495,7 @@ fn side_line_to_line<'a>(
             let half = body_width.saturating_sub(sep_cols) / 2;
             let text_w = half.saturating_sub(gw + 1);
 
-            let mut spans = vec![marker_span(pair_marked(*left, *right, marks), cursor, pal)];
+            let mut spans = vec![marker_span(pair_marker(*left, *right, marks), cursor, pal)];
             spans.extend(cell(
                 *left,
                 segs(&rf.old_hl, left.and_then(|r| r.old_no)),
@@ -473,7 +539,7 @@ fn uni_line_to_line<'a>(
     let body_width = width.saturating_sub(1);
     match line {
         UniLine::Header(h) => {
-            let mut spans = vec![marker_span(false, cursor, pal)];
+            let mut spans = vec![marker_span(Marker::None, cursor, pal)];
             spans.extend(header_spans(h, body_width, cursor, pal));
             Line::from(spans)
         }
@@ -491,7 +557,7 @@ fn uni_line_to_line<'a>(
             };
 
             let mut spans = vec![
-                marker_span(uni_marked(row, marks), cursor, pal),
+                marker_span(uni_marker(row, marks), cursor, pal),
                 num_span(row.old_no, gw, bg, pal),
                 Span::styled(" ", Style::default().bg(bg)),
                 num_span(row.new_no, gw, bg, pal),
@@ -576,30 +642,75 @@ fn header_spans<'a>(header: &str, width: usize, cursor: bool, pal: Palette) -> V
     )]
 }
 
-fn marker_span<'a>(marked: bool, cursor: bool, pal: Palette) -> Span<'a> {
+/// What a line's 1-cell marker column shows, strongest first: your own
+/// comment (✓ once the agent has changed its lines), then an active imported
+/// thread, then a resolved/outdated one.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Marker {
+    None,
+    InactiveThread,
+    Thread,
+    /// A comment whose lines the agent changed after the hand-off.
+    Touched,
+    Comment,
+}
+
+fn marker_span<'a>(marker: Marker, cursor: bool, pal: Palette) -> Span<'a> {
     let bg = if cursor { pal.cursor_bg } else { Color::Reset };
-    let (ch, fg) = if marked {
-        ("●", Color::Magenta)
-    } else {
-        (" ", Color::Reset)
+    let (ch, fg) = match marker {
+        Marker::Comment => ("●", Color::Magenta),
+        Marker::Touched => ("✓", Color::Green),
+        Marker::Thread => ("◆", Color::Cyan),
+        Marker::InactiveThread => ("◇", Color::DarkGray),
+        Marker::None => (" ", Color::Reset),
     };
     // `ch` is a &'static str literal — no per-line allocation.
     Span::styled(ch, Style::default().fg(fg).bg(bg))
 }
 
-fn pair_marked(left: Option<&Row>, right: Option<&Row>, marks: &Marks) -> bool {
-    let l = left
-        .and_then(|r| r.old_no)
-        .is_some_and(|n| marks.old.contains(&n));
-    let r = right
-        .and_then(|r| r.new_no)
-        .is_some_and(|n| marks.new.contains(&n));
-    l || r
+fn side_marker(
+    line: Option<usize>,
+    mine: &HashSet<usize>,
+    touched: &HashSet<usize>,
+    threads: &HashMap<usize, bool>,
+) -> Marker {
+    let Some(n) = line else {
+        return Marker::None;
+    };
+    if touched.contains(&n) {
+        return Marker::Touched;
+    }
+    if mine.contains(&n) {
+        return Marker::Comment;
+    }
+    match threads.get(&n) {
+        Some(true) => Marker::Thread,
+        Some(false) => Marker::InactiveThread,
+        None => Marker::None,
+    }
+}
+
+fn pair_marker(left: Option<&Row>, right: Option<&Row>, marks: &Marks) -> Marker {
+    let none = HashSet::new();
+    let l = side_marker(
+        left.and_then(|r| r.old_no),
+        &marks.old,
+        &none,
+        &marks.threads_old,
+    );
+    let r = side_marker(
+        right.and_then(|r| r.new_no),
+        &marks.new,
+        &marks.touched,
+        &marks.threads_new,
+    );
+    l.max(r)
 }
 
-fn uni_marked(row: &Row, marks: &Marks) -> bool {
-    row.new_no.is_some_and(|n| marks.new.contains(&n))
-        || row.old_no.is_some_and(|n| marks.old.contains(&n))
+fn uni_marker(row: &Row, marks: &Marks) -> Marker {
+    let new = side_marker(row.new_no, &marks.new, &marks.touched, &marks.threads_new);
+    let old = side_marker(row.old_no, &marks.old, &HashSet::new(), &marks.threads_old);
+    new.max(old)
 }
 
 fn num_span<'a>(num: Option<usize>, gw: usize, bg: Color, pal: Palette) -> Span<'a> {
@@ -3,7 +3,8 @@
 //! The tree is derived from the changed-file paths on demand (cheap for the
 //! file counts a review touches); collapse state is keyed by directory path so
 //! it survives rebuilds. A non-empty filter narrows to fuzzy-matching files and
-//! shows them fully expanded.
+//! shows them fully expanded. Files can also be hidden outright (viewed
+//! files, on request).
 
 use crate::git::status::FileStatus;
 use fuzzy_matcher::FuzzyMatcher;
@@ -29,6 +30,8 @@ pub struct Row {
 pub struct FileTree {
     files: Vec<(String, FileStatus)>,
     collapsed: HashSet<String>,
+    /// Indices of files left out of `rows` entirely.
+    hidden: HashSet<usize>,
 }
 
 #[derive(Default)]
@@ -44,6 +47,7 @@ impl FileTree {
         FileTree {
             files: files.into_iter().collect(),
             collapsed: HashSet::new(),
+            hidden: HashSet::new(),
         }
     }
 
@@ -58,6 +62,11 @@ impl FileTree {
         self.collapsed.insert(dir_path.to_string());
     }
 
+    /// Leave the files at these indices out of the rows.
+    pub fn set_hidden(&mut self, hidden: HashSet<usize>) {
+        self.hidden = hidden;
+    }
+
     /// Visible rows in display order. With a non-empty `filter`, only
     /// fuzzy-matching files (and their ancestor directories) appear, fully
     /// expanded regardless of collapse state.
@@ -67,7 +76,9 @@ impl FileTree {
 
         let mut root = Node::default();
         for (idx, (path, status)) in self.files.iter().enumerate() {
-            if filtering && matcher.fuzzy_match(path, filter).is_none() {
+            if self.hidden.contains(&idx)
+                || (filtering && matcher.fuzzy_match(path, filter).is_none())
+            {
                 continue;
             }
             insert(&mut root, idx, path, *status);
@@ -176,6 +187,14 @@ mod tests {
         assert_eq!(names, vec!["a", "b", "c.rs"]);
     }
 
+    #[test]
+    fn hidden_files_drop_out_with_empty_dirs() {
+        let mut tree = tree(&["a/b.rs", "c.rs"]);
+        tree.set_hidden(HashSet::from([0]));
+        let names: Vec<String> = tree.rows("").into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["c.rs"]);
+    }
+
     #[test]
     fn toggle_flips_collapse() {
         let mut tree = tree(&["a/b.rs"]);
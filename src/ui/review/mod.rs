@@ -9,18 +9,27 @@ pub mod highlight;
 
 use crate::git::review::blob;
 use crate::git::review::diff::{self, ChangedFile};
+use crate::git::review::handoff;
+use crate::git::review::pull::{self, Submission, Thread};
 use crate::git::review::range::{self, Endpoint, ReviewRange};
-use crate::git::review::state::{self, Comment, ReviewState, Side};
+use crate::git::review::since;
+use crate::git::review::stage::{self, Action};
+use crate::git::review::state::{self, Comment, ReviewState, Side, Suggestion, Summary, Verdict};
+use crate::git::review::suggest;
+use crate::ui::explain::{Explainer, Request};
 use crate::ui::terminal::with_terminal;
 use crate::ui::{render_help_bar, status_char, status_color};
 use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use diff_view::{RenderedFile, ViewMode};
 use file_tree::{FileTree, NodeKind};
+use git2::Oid;
 use highlight::Highlighter;
 use miette::{IntoDiagnostic, Result};
 use ratatui::Frame;
 use ratatui::prelude::*;
 use ratatui::widgets::*;
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::time::Duration;
 
 const SIDEBAR_WIDTH: u16 = 32;
@@ -52,6 +61,8 @@ enum Mode {
     CommentPopup,
     OrphanedList,
     RangeSwitch,
+    FinishChoice,
+    Thread,
     Filter,
     Help,
 }
@@ -72,26 +83,58 @@ struct Popup {
     buffer: String,
     /// Index of the comment being edited, or `None` for a new comment.
     editing: Option<usize>,
+    kind: PopupKind,
+    /// A suggested replacement for the anchored lines (line comments only).
+    suggestion: Option<Suggestion>,
+    /// Whether typing goes to the suggestion rather than the note.
+    in_suggestion: bool,
+}
+
+impl Popup {
+    /// The text being typed into: the note or the suggestion.
+    fn text_mut(&mut self) -> &mut String {
+        match &mut self.suggestion {
+            Some(s) if self.in_suggestion => &mut s.replacement,
+            _ => &mut self.buffer,
+        }
+    }
+}
+
+/// What the popup's text is saved as.
+enum PopupKind {
+    /// A line comment (new, or the one `editing` points at).
+    Line,
+    /// A reply to the imported thread with this id.
+    Reply(String),
+    /// The whole-file comment on `file`.
+    File,
+    /// The review summary, with the verdict picked so far.
+    Summary(Verdict),
 }
 
 /// Launch the review TUI for an already-resolved range and changed-file list.
+/// Returns the review blob when the reviewer chose to hand it to the agent.
 pub fn run(
     range: ReviewRange,
     files: Vec<ChangedFile>,
     theme: &str,
     min_width: u16,
     appearance: Appearance,
-) -> Result<()> {
+) -> Result<Option<String>> {
     // `with_terminal` enters the alternate screen / raw mode and restores it
     // (even on panic, via its guard) before returning; the inner Result carries
     // the loop's outcome plus an optional message to print after teardown.
-    let message =
-        with_terminal(|terminal| run_loop(terminal, range, files, theme, min_width, appearance))
-            .into_diagnostic()??;
+    let explainer = Explainer::load();
+    let (message, handoff) = with_terminal(|terminal| {
+        run_loop(
+            terminal, range, files, theme, min_width, appearance, explainer,
+        )
+    })
+    .into_diagnostic()??;
     if let Some(msg) = message {
         println!("{msg}");
     }
-    Ok(())
+    Ok(handoff)
 }
 
 fn run_loop(
@@ -101,17 +144,16 @@ fn run_loop(
     theme: &str,
     min_width: u16,
     appearance: Appearance,
-) -> Result<Option<String>> {
-    let mut app = App::new(range, files, theme, min_width, appearance);
+    explainer: Explainer,
+) -> Result<(Option<String>, Option<String>)> {
+    let mut app = App::new(range, files, theme, min_width, appearance, explainer);
     let mut needs_redraw = true;
 
     loop {
         if needs_redraw {
             if let Err(e) = app.ensure_current_built() {
                 // Don't lose the in-progress review if building a file diff fails.
-                if let Some(key) = &app.key {
-                    let _ = state::save(key, &app.review);
-                }
+                app.save();
                 return Err(e);
             }
             terminal.draw(|f| app.draw(f)).into_diagnostic()?;
@@ -138,12 +180,13 @@ fn run_loop(
                 _ => {}
             }
         }
+        if app.explainer.poll() {
+            needs_redraw = true;
+        }
     }
     // Persist the review (best-effort) so it resumes next launch.
-    if let Some(key) = &app.key {
-        let _ = state::save(key, &app.review);
-    }
-    Ok(app.finish_message.take())
+    app.save();
+    Ok((app.finish_message.take(), app.handoff.take()))
 }
 
 struct App {
@@ -169,12 +212,33 @@ struct App {
     select_anchor: Option<usize>,
     status: Option<String>,
     finish_message: Option<String>,
+    /// The review blob to hand to the coding agent after teardown.
+    handoff: Option<String>,
     key: Option<String>,
     pending_reset: bool,
     editor_request: bool,
     pending_bracket: Option<char>,
     last_diff_height: usize,
     last_view: ViewMode,
+    explainer: Explainer,
+    /// Per file (parallel to `cache`), the explain cache key of its diff once
+    /// it has been explained.
+    explain_keys: Vec<Option<String>>,
+    /// Existing review threads of the pull request under review (read-only).
+    threads: Vec<Thread>,
+    /// Index into `threads` of the thread shown in `Mode::Thread`.
+    open_thread: Option<usize>,
+    /// The tip this scope was reviewed at last time, as loaded.
+    last_reviewed: Option<Oid>,
+    /// Files whose changes are the same as at the last review (dimmed).
+    unchanged: HashSet<String>,
+    /// While showing changes since the last review: the full range.
+    since_base: Option<ReviewRange>,
+    /// Per file (parallel to `files`), the content id its viewed mark is
+    /// keyed on.
+    content_ids: Vec<Oid>,
+    /// Leave viewed files out of the sidebar.
+    hide_viewed: bool,
 }
 
 impl App {
@@ -184,18 +248,35 @@ impl App {
         theme: &str,
         min_width: u16,
         appearance: Appearance,
+        explainer: Explainer,
     ) -> Self {
         let cache = (0..files.len()).map(|_| None).collect();
+        let explain_keys = vec![None; files.len()];
         let palette = diff_view::Palette::for_appearance(appearance);
         // Key persistence on the clone's shared git dir + the range scope, then
         // resume any saved review for this (clone, scope).
         let key = crate::git::worktree::common_git_dir()
             .ok()
             .map(|dir| state::storage_key(&dir, &range.scope_id));
-        let review = key.as_deref().map(state::load).unwrap_or_default();
+        let mut review = key.as_deref().map(state::load).unwrap_or_default();
+        let handoff_status = crate::git::worktree::current_worktree_root()
+            .ok()
+            .and_then(|root| match handoff::check(&mut review, &root) {
+                (0, _) => None,
+                (handed, 0) => Some(format!(
+                    "The agent hasn't changed the lines of the {handed} comment(s) handed to it"
+                )),
+                (handed, touched) => Some(format!(
+                    "The agent changed the lines of {touched} of {handed} comment(s) handed to \
+                     it (✓; o lists any no longer in the diff)"
+                )),
+            });
         let tree = FileTree::new(files.iter().map(|f| (f.path.clone(), f.status)));
+        let content_ids = files.iter().map(|f| f.content_id(range.to)).collect();
+        let (threads, status) = load_threads(&range);
+        let last_reviewed = review.reviewed;
 
-        App {
+        let mut app = App {
             range,
             files,
             selected: 0,
@@ -216,14 +297,61 @@ impl App {
             focus: Focus::Diff,
             popup: None,
             select_anchor: None,
-            status: None,
+            status,
             finish_message: None,
+            handoff: None,
             key,
             pending_reset: false,
             editor_request: false,
             pending_bracket: None,
             last_diff_height: 1,
             last_view: ViewMode::SideBySide,
+            explainer,
+            explain_keys,
+            threads,
+            open_thread: None,
+            last_reviewed,
+            unchanged: HashSet::new(),
+            since_base: None,
+            content_ids,
+            hide_viewed: false,
+        };
+        let offer = app.compare_with_last_review();
+        app.status = app.status.take().or(handoff_status).or(offer);
+        app
+    }
+
+    /// Save the review (best-effort).
+    fn save(&mut self) {
+        if let Some(key) = &self.key {
+            let _ = state::save(key, &self.review);
+        }
+    }
+
+    /// When this scope was last reviewed at an older tip, dim the files whose
+    /// changes are the same as then, and offer the interdiff (`s` `n`).
+    fn compare_with_last_review(&mut self) -> Option<String> {
+        self.unchanged.clear();
+        let reviewed = self.last_reviewed?;
+        if self.since_base.is_some() || self.range.to == Endpoint::Commit(reviewed) {
+            return None;
+        }
+        let result = since::resolve(&self.range, reviewed).and_then(|interdiff| {
+            let unchanged = since::unchanged_files(&self.files, &interdiff.range)?;
+            Ok((interdiff.changed, unchanged))
+        });
+        match result {
+            Ok((changed, unchanged)) => {
+                let message = format!(
+                    "Since your last review: {changed} new or rewritten commit(s), {} of {} file(s) \
+                     unchanged — s n shows only what changed",
+                    unchanged.len(),
+                    self.files.len()
+                );
+                self.unchanged = unchanged;
+                Some(message)
+            }
+            Err(e) => Some(format!("Can't compare with your last review: {e}")),
         }
     }
 
@@ -231,15 +359,16 @@ impl App {
         if self.files.is_empty() || self.cache[self.selected].is_some() {
             return Ok(());
         }
-        let rf = diff_view::render_file(
-            &self.files[self.selected],
-            self.range.to,
-            &self.highlighter,
-        )
-        .into_diagnostic()?;
+        let rf =
+            diff_view::render_file(&self.files[self.selected], self.range.to, &self.highlighter)
+                .into_diagnostic()?;
         // Re-anchor any resumed comments for this file against its current diff.
-        let path = self.files[self.selected].path.clone();
-        self.review.reanchor_file(&path, &rf.diff);
+        // Not against the interdiff: the comments belong to the full range,
+        // whose lines and old side it doesn't show.
+        if self.since_base.is_none() {
+            let path = self.files[self.selected].path.clone();
+            self.review.reanchor_file(&path, &rf.diff);
+        }
         self.cache[self.selected] = Some(rf);
         Ok(())
     }
@@ -305,6 +434,21 @@ impl App {
                 self.handle_rangeswitch_key(key);
                 false
             }
+            Mode::FinishChoice => self.handle_finish_key(key),
+            Mode::Thread => {
+                match key.code {
+                    KeyCode::Char('r') => {
+                        if let Some(idx) = self.open_thread {
+                            self.start_reply(idx);
+                        }
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+                        self.mode = Mode::Normal;
+                    }
+                    _ => {}
+                }
+                false
+            }
             Mode::Filter => {
                 self.handle_filter_key(key);
                 false
@@ -336,6 +480,7 @@ impl App {
         }
 
         match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) if self.explainer.open => self.explainer.close(),
             (KeyCode::Char('q'), _)
             | (KeyCode::Esc, _)
             | (KeyCode::Char('c'), KeyModifiers::CONTROL) => return true,
@@ -358,12 +503,7 @@ impl App {
             (KeyCode::Char('}'), _) => self.jump_hunk(true),
             (KeyCode::Char('{'), _) => self.jump_hunk(false),
 
-            (KeyCode::Tab, _) | (KeyCode::BackTab, _) => {
-                if self.show_sidebar {
-                    self.sync_tree_cursor_to_selected();
-                    self.focus = Focus::Sidebar;
-                }
-            }
+            (KeyCode::Tab, _) | (KeyCode::BackTab, _) => self.focus_sidebar(),
 
             (KeyCode::Left, _) | (KeyCode::Char('h'), _) => {
                 self.h_scroll = self.h_scroll.saturating_sub(4)
@@ -377,8 +517,19 @@ impl App {
                 self.mode = Mode::VisualSelect;
             }
             (KeyCode::Enter, _) => self.edit_comment_under_cursor(),
+            (KeyCode::Char('r'), _) => match self.thread_under_cursor() {
+                Some(idx) => self.start_reply(idx),
+                None => self.status = Some("No review thread on this line".into()),
+            },
             (KeyCode::Char('D'), _) => self.delete_comment_under_cursor(),
-            (KeyCode::Char('F'), _) => return self.finish(),
+            (KeyCode::Char('C'), _) => self.start_file_comment(),
+            (KeyCode::Char('R'), _) => self.start_summary(),
+            (KeyCode::Char('F'), _) => self.finish(),
+            (KeyCode::Char('m'), _) => self.toggle_viewed(self.selected),
+            (KeyCode::Char('H'), _) => self.toggle_hide_viewed(),
+            (KeyCode::Char('M'), _) => self.mark_reviewed(),
+            (KeyCode::Char('S'), _) => self.stage_changes(Action::Stage),
+            (KeyCode::Char('U'), _) => self.stage_changes(Action::Unstage),
 
             (KeyCode::Char('o'), _) => {
                 if self.review.orphaned.is_empty() {
@@ -396,6 +547,10 @@ impl App {
                 }
             }
 
+            (KeyCode::Char('e'), _) => self.explain_current(),
+            (KeyCode::Char('J'), _) => self.explainer.scroll_by(3),
+            (KeyCode::Char('K'), _) => self.explainer.scroll_by(-3),
+
             (KeyCode::Char('s'), _) => self.mode = Mode::RangeSwitch,
             (KeyCode::Char('v'), _) => self.toggle_view(),
             (KeyCode::Char('b'), _) => {
@@ -410,6 +565,26 @@ impl App {
         false
     }
 
+    /// Explain the current file's diff in the side panel. The cache key hashes
+    /// the patch, so an unchanged file is never explained twice, while a
+    /// working-tree file that changed since is asked about afresh.
+    fn explain_current(&mut self) {
+        let Some(rf) = self.current() else { return };
+        let patch = rf.diff.patch_text();
+        let mut hasher = DefaultHasher::new();
+        patch.hash(&mut hasher);
+        let key = format!("{}:{:016x}", rf.diff.path, hasher.finish());
+        let subject = format!("File {} in {}", rf.diff.path, self.range.label);
+
+        self.explainer.explain(&key, || {
+            Ok(Request {
+                subject,
+                diff: patch,
+            })
+        });
+        self.explain_keys[self.selected] = Some(key);
+    }
+
     fn handle_visual_key(&mut self, key: event::KeyEvent) {
         match key.code {
             KeyCode::Esc => {
@@ -419,6 +594,8 @@ impl App {
             KeyCode::Char('j') | KeyCode::Down => self.move_cursor(1),
             KeyCode::Char('k') | KeyCode::Up => self.move_cursor(-1),
             KeyCode::Char('c') => self.start_comment_selection(),
+            KeyCode::Char('S') => self.stage_changes(Action::Stage),
+            KeyCode::Char('U') => self.stage_changes(Action::Unstage),
             _ => {}
         }
     }
@@ -431,19 +608,28 @@ impl App {
             }
             (KeyCode::Char('s'), KeyModifiers::CONTROL) => self.save_comment(),
             (KeyCode::Char('e'), KeyModifiers::CONTROL) => self.editor_request = true,
+            (KeyCode::Char('g'), KeyModifiers::CONTROL) => self.toggle_suggestion(),
+            (KeyCode::Tab, _) => match self.popup.as_mut() {
+                Some(Popup {
+                    kind: PopupKind::Summary(verdict),
+                    ..
+                }) => *verdict = verdict.next(),
+                Some(p) if p.suggestion.is_some() => p.in_suggestion = !p.in_suggestion,
+                _ => {}
+            },
             (KeyCode::Enter, _) => {
                 if let Some(p) = self.popup.as_mut() {
-                    p.buffer.push('\n');
+                    p.text_mut().push('\n');
                 }
             }
             (KeyCode::Backspace, _) => {
                 if let Some(p) = self.popup.as_mut() {
-                    p.buffer.pop();
+                    p.text_mut().pop();
                 }
             }
             (KeyCode::Char(c), m) if !m.contains(KeyModifiers::CONTROL) => {
                 if let Some(p) = self.popup.as_mut() {
-                    p.buffer.push(c);
+                    p.text_mut().push(c);
                 }
             }
             _ => {}
@@ -487,6 +673,69 @@ impl App {
         }
     }
 
+    /// Stage or unstage the visual selection (in `VisualSelect`) or the hunk
+    /// under the cursor, then refresh the diff in place.
+    fn stage_changes(&mut self, action: Action) {
+        let visual = matches!(self.mode, Mode::VisualSelect);
+        self.mode = Mode::Normal;
+        let anchor = self.select_anchor.take();
+        let Some(rf) = self.current() else { return };
+        let selection = match anchor {
+            Some(lo) if visual => diff_view::selection_in(rf, self.last_view, lo, self.cursor),
+            _ => diff_view::hunk_selection(rf, self.last_view, self.cursor),
+        };
+        if selection.is_empty() {
+            self.status = Some("No changes under the cursor".into());
+            return;
+        }
+
+        let file = &self.files[self.selected];
+        if let Err(e) = stage::apply(&self.range, file, &rf.diff, &selection, action) {
+            self.status = Some(format!("Can't {}: {e}", action_verb(action)));
+            return;
+        }
+        self.refresh_files();
+        self.status = Some(format!(
+            "{} {} changed line(s)",
+            match action {
+                Action::Stage => "Staged",
+                Action::Unstage => "Unstaged",
+            },
+            selection.len()
+        ));
+    }
+
+    /// Re-enumerate the range's files after the index changed, staying on the
+    /// current file while it still has changes. Diffs rebuild lazily, and
+    /// comments re-anchor as they do.
+    fn refresh_files(&mut self) {
+        let path = self.current_path().map(str::to_string);
+        let files = match diff::changed_files(&self.range) {
+            Ok(files) => files,
+            Err(e) => {
+                self.status = Some(format!("Refreshing the diff failed: {e}"));
+                return;
+            }
+        };
+        self.cache = (0..files.len()).map(|_| None).collect();
+        self.explain_keys = vec![None; files.len()];
+        self.tree = FileTree::new(files.iter().map(|f| (f.path.clone(), f.status)));
+        self.content_ids = files.iter().map(|f| f.content_id(self.range.to)).collect();
+        self.files = files;
+        self.sync_hidden();
+        match path.and_then(|p| self.files.iter().position(|f| f.path == p)) {
+            Some(index) => self.selected = index,
+            None => {
+                self.selected = self.selected.min(self.files.len().saturating_sub(1));
+                self.cursor = 0;
+                self.v_scroll = 0;
+            }
+        }
+        // The sidebar may have lost rows too (or hide the selected file).
+        self.sync_tree_cursor_to_selected();
+        self.clamp_tree_cursor();
+    }
+
     /// Open a file in the diff pane and move focus there.
     fn open_file(&mut self, index: usize) {
         self.selected = index;
@@ -494,6 +743,7 @@ impl App {
         self.v_scroll = 0;
         self.h_scroll = 0;
         self.focus = Focus::Diff;
+        self.explainer.reset_scroll();
     }
 
     /// Place the tree cursor on the row of the currently-selected file.
@@ -507,6 +757,14 @@ impl App {
         }
     }
 
+    /// Move focus to the file sidebar, on the current file, when it is shown.
+    fn focus_sidebar(&mut self) {
+        if self.show_sidebar {
+            self.sync_tree_cursor_to_selected();
+            self.focus = Focus::Sidebar;
+        }
+    }
+
     fn clamp_tree_cursor(&mut self) {
         let len = self.tree.rows(&self.filter).len();
         if self.tree_cursor >= len {
@@ -523,9 +781,7 @@ impl App {
             (KeyCode::Char('q'), _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => return true,
             (KeyCode::Esc, _) | (KeyCode::Tab, _) => self.focus = Focus::Diff,
             (KeyCode::Char('j'), _) | (KeyCode::Down, _) => {
-                if len > 0 {
-                    self.tree_cursor = (self.tree_cursor + 1).min(len - 1);
-                }
+                self.tree_cursor = (self.tree_cursor + 1).min(len.saturating_sub(1));
             }
             (KeyCode::Char('k'), _) | (KeyCode::Up, _) => {
                 self.tree_cursor = self.tree_cursor.saturating_sub(1);
@@ -552,6 +808,14 @@ impl App {
                     }
                 }
             }
+            (KeyCode::Char('m'), _) => {
+                if let Some(NodeKind::File { index, .. }) =
+                    rows.get(self.tree_cursor).map(|r| &r.kind)
+                {
+                    self.toggle_viewed(*index);
+                }
+            }
+            (KeyCode::Char('H'), _) => self.toggle_hide_viewed(),
             (KeyCode::Char('/'), _) => self.mode = Mode::Filter,
             (KeyCode::Char('?'), _) => self.mode = Mode::Help,
             _ => {}
@@ -560,6 +824,58 @@ impl App {
         false
     }
 
+    fn is_viewed(&self, index: usize) -> bool {
+        match (self.files.get(index), self.content_ids.get(index)) {
+            (Some(file), Some(&id)) => self.review.is_viewed(&file.path, id),
+            _ => false,
+        }
+    }
+
+    fn viewed_count(&self) -> usize {
+        (0..self.files.len()).filter(|&i| self.is_viewed(i)).count()
+    }
+
+    /// Mark file `index` as viewed, or clear its mark.
+    fn toggle_viewed(&mut self, index: usize) {
+        let (Some(file), Some(&id)) = (self.files.get(index), self.content_ids.get(index)) else {
+            return;
+        };
+        let path = file.path.clone();
+        let viewed = self.review.toggle_viewed(&path, id);
+        self.status = Some(format!(
+            "{} {path} ({}/{} viewed)",
+            if viewed { "Viewed" } else { "Not viewed:" },
+            self.viewed_count(),
+            self.files.len()
+        ));
+        self.sync_hidden();
+        self.clamp_tree_cursor();
+    }
+
+    fn toggle_hide_viewed(&mut self) {
+        self.hide_viewed = !self.hide_viewed;
+        self.status = Some(if self.hide_viewed {
+            format!("Hiding {} viewed file(s)", self.viewed_count())
+        } else {
+            "Showing viewed files".into()
+        });
+        self.sync_hidden();
+        self.sync_tree_cursor_to_selected();
+        self.clamp_tree_cursor();
+    }
+
+    /// Hand the sidebar the files to leave out: the viewed ones, when hiding.
+    fn sync_hidden(&mut self) {
+        let hidden = if self.hide_viewed {
+            (0..self.files.len())
+                .filter(|&i| self.is_viewed(i))
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        self.tree.set_hidden(hidden);
+    }
+
     fn handle_filter_key(&mut self, key: event::KeyEvent) {
         match key.code {
             KeyCode::Esc => {
@@ -612,6 +928,15 @@ impl App {
     }
 
     fn open_popup(&mut self, side: Side, start: usize, end: usize, anchor_text: String) {
+        if side == Side::Old && self.since_base.is_some() {
+            self.mode = Mode::Normal;
+            self.status = Some(
+                "The old side here is a replayed tree — comment on the new side, or s b for the \
+                 whole branch"
+                    .into(),
+            );
+            return;
+        }
         let Some(file) = self.current_path().map(str::to_string) else {
             return;
         };
@@ -623,6 +948,9 @@ impl App {
             anchor_text,
             buffer: String::new(),
             editing: None,
+            kind: PopupKind::Line,
+            suggestion: None,
+            in_suggestion: false,
         });
         self.mode = Mode::CommentPopup;
     }
@@ -635,8 +963,19 @@ impl App {
         let Some(file) = self.current_path().map(str::to_string) else {
             return;
         };
-        let Some(idx) = self.review.index_at(&file, a.side, a.line) else {
-            self.status = Some("No comment on this line".into());
+        // Old-side lines of the interdiff aren't the full range's.
+        let index = match a.side {
+            Side::Old if self.since_base.is_some() => None,
+            side => self.review.index_at(&file, side, a.line),
+        };
+        let Some(idx) = index else {
+            match self.thread_under_cursor() {
+                Some(idx) => {
+                    self.open_thread = Some(idx);
+                    self.mode = Mode::Thread;
+                }
+                None => self.status = Some("No comment on this line".into()),
+            }
             return;
         };
         let c = &self.review.comments[idx];
@@ -648,10 +987,129 @@ impl App {
             anchor_text: c.anchor_text.clone(),
             buffer: c.body.clone(),
             editing: Some(idx),
+            kind: PopupKind::Line,
+            suggestion: c.suggestion.clone(),
+            in_suggestion: false,
+        });
+        self.mode = Mode::CommentPopup;
+    }
+
+    /// Index of the imported thread covering the cursor's line on either side.
+    fn thread_under_cursor(&self) -> Option<usize> {
+        let path = self.current_path()?;
+        let (old, new) = self
+            .current()
+            .map(|rf| diff_view::lines_at(rf, self.last_view, self.cursor))?;
+        self.threads.iter().position(|t| {
+            let line = match t.side {
+                Side::Old => old,
+                Side::New => new,
+            };
+            t.path == path && line.is_some_and(|l| l >= t.start_line && l <= t.end_line)
+        })
+    }
+
+    /// Compose (or edit) the reply to thread `idx`, posted on finish.
+    fn start_reply(&mut self, idx: usize) {
+        let thread = &self.threads[idx];
+        self.popup = Some(Popup {
+            file: thread.path.clone(),
+            side: thread.side,
+            start_line: thread.start_line,
+            end_line: thread.end_line,
+            anchor_text: String::new(),
+            buffer: self
+                .review
+                .reply_for(&thread.id)
+                .map(|r| r.body.clone())
+                .unwrap_or_default(),
+            editing: None,
+            kind: PopupKind::Reply(thread.id.clone()),
+            suggestion: None,
+            in_suggestion: false,
         });
         self.mode = Mode::CommentPopup;
     }
 
+    /// Open the popup on the current file's whole-file comment.
+    fn start_file_comment(&mut self) {
+        let Some(file) = self.current_path().map(str::to_string) else {
+            return;
+        };
+        let buffer = self
+            .review
+            .file_comment(&file)
+            .map(|c| c.body.clone())
+            .unwrap_or_default();
+        self.popup = Some(Popup {
+            file,
+            side: Side::New,
+            start_line: 0,
+            end_line: 0,
+            anchor_text: String::new(),
+            buffer,
+            editing: None,
+            kind: PopupKind::File,
+            suggestion: None,
+            in_suggestion: false,
+        });
+        self.mode = Mode::CommentPopup;
+    }
+
+    /// Open the popup on the review summary and its verdict.
+    fn start_summary(&mut self) {
+        let Summary { verdict, body } = self.review.summary.clone();
+        self.popup = Some(Popup {
+            file: String::new(),
+            side: Side::New,
+            start_line: 0,
+            end_line: 0,
+            anchor_text: String::new(),
+            buffer: body,
+            editing: None,
+            kind: PopupKind::Summary(verdict),
+            suggestion: None,
+            in_suggestion: false,
+        });
+        self.mode = Mode::CommentPopup;
+    }
+
+    /// Start a suggested change for the popup's lines, prefilled with them,
+    /// or drop the one being written.
+    fn toggle_suggestion(&mut self) {
+        let original = match &self.popup {
+            Some(p) if !matches!(p.kind, PopupKind::Line) => {
+                self.status = Some("Only line comments can suggest a change".into());
+                return;
+            }
+            Some(p) if p.suggestion.is_none() && p.side == Side::Old => {
+                self.status = Some("A suggestion replaces new-side lines".into());
+                return;
+            }
+            Some(p) if p.suggestion.is_none() => self
+                .current()
+                .and_then(|rf| suggest::new_lines(&rf.diff, p.start_line, p.end_line)),
+            Some(_) => None,
+            None => return,
+        };
+        let Some(p) = self.popup.as_mut() else { return };
+        if p.suggestion.take().is_some() {
+            p.in_suggestion = false;
+            self.status = Some("Suggestion dropped".into());
+            return;
+        }
+        match original {
+            Some(original) => {
+                p.suggestion = Some(Suggestion {
+                    replacement: original.clone(),
+                    original,
+                });
+                p.in_suggestion = true;
+            }
+            None => self.status = Some("Some of these lines aren't in the diff".into()),
+        }
+    }
+
     fn delete_comment_under_cursor(&mut self) {
         let anchor = self
             .current()
@@ -667,14 +1125,50 @@ impl App {
     }
 
     fn save_comment(&mut self) {
-        let Some(popup) = self.popup.take() else { return };
+        let Some(popup) = self.popup.take() else {
+            return;
+        };
         self.mode = Mode::Normal;
-        if popup.buffer.trim().is_empty() {
+        match &popup.kind {
+            PopupKind::Line => {}
+            PopupKind::Reply(thread_id) => {
+                self.status = Some(if popup.buffer.trim().is_empty() {
+                    "Reply removed".into()
+                } else {
+                    "Reply saved; it is posted when you finish".into()
+                });
+                self.review.set_reply(thread_id, popup.buffer);
+                return;
+            }
+            PopupKind::File => {
+                self.status = Some(if popup.buffer.trim().is_empty() {
+                    format!("File comment on {} removed", popup.file)
+                } else {
+                    format!("File comment on {} saved", popup.file)
+                });
+                self.review.set_file_comment(&popup.file, popup.buffer);
+                return;
+            }
+            &PopupKind::Summary(verdict) => {
+                self.status = Some(format!("Summary saved — verdict: {}", verdict.label()));
+                self.review.summary = Summary {
+                    verdict,
+                    body: popup.buffer,
+                };
+                return;
+            }
+        }
+        // A suggestion that leaves the lines as they are suggests nothing.
+        let suggestion = popup.suggestion.filter(|s| s.replacement != s.original);
+        if popup.buffer.trim().is_empty() && suggestion.is_none() {
             self.status = Some("Empty comment discarded".into());
             return;
         }
         match popup.editing {
-            Some(idx) => self.review.set_body(idx, popup.buffer),
+            Some(idx) => {
+                self.review.set_body(idx, popup.buffer);
+                self.review.set_suggestion(idx, suggestion);
+            }
             None => self.review.add(Comment {
                 file: popup.file,
                 side: popup.side,
@@ -682,45 +1176,65 @@ impl App {
                 end_line: popup.end_line,
                 anchor_text: popup.anchor_text,
                 body: popup.buffer,
+                suggestion,
+                handoff: None,
             }),
         }
     }
 
-    /// Build the wrapped review blob, copy it to the clipboard, and signal quit.
-    /// Returns false (does not quit) when there is nothing to finish.
-    fn finish(&mut self) -> bool {
-        let total = self.review.total();
-        if total == 0 {
+    /// Finish the review: ask whether to copy it to the clipboard or hand it
+    /// to the agent (or, for a pull request, send it to GitHub). Does nothing
+    /// when there is nothing to finish.
+    fn finish(&mut self) {
+        if self.review.is_empty() {
             self.status = Some("No comments yet — nothing to finish".into());
-            return false;
+            return;
         }
+        self.mode = Mode::FinishChoice;
+    }
 
-        // Gather blocks in (file, line) order; the inner scope drops the
-        // borrows of `review`/`files`/`cache` before we mutate `finish_message`.
-        let blocks = {
-            let mut comments: Vec<&Comment> = self.review.comments.iter().collect();
-            comments.sort_by(|a, b| a.file.cmp(&b.file).then(a.start_line.cmp(&b.start_line)));
-            comments
-                .iter()
-                .map(|c| {
-                    let snippet = self
-                        .files
-                        .iter()
-                        .position(|f| f.path == c.file)
-                        .and_then(|i| self.cache[i].as_ref())
-                        .map(|rf| blob::context_lines(&rf.diff, c.side, c.start_line, c.end_line))
-                        .unwrap_or_default();
-                    blob::CommentBlock {
-                        file: c.file.clone(),
-                        location: blob::location(c.side, c.start_line, c.end_line),
-                        snippet,
-                        body: c.body.clone(),
-                    }
-                })
-                .collect::<Vec<_>>()
-        };
+    fn handle_finish_key(&mut self, key: event::KeyEvent) -> bool {
+        let pull = self.range.pull.is_some();
+        match key.code {
+            KeyCode::Char('c') => self.copy_review(),
+            KeyCode::Char('a') => self.hand_to_agent(),
+            KeyCode::Char('p') if pull => self.submit_review(Submission::Pending),
+            KeyCode::Char('s') if pull => self.submit_review(Submission::Submit),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.mode = Mode::Normal;
+                false
+            }
+            _ => false,
+        }
+    }
 
-        let text = blob::build(&self.range.label, &blocks);
+    /// The wrapped review blob, or `None` (with a status) when there is only
+    /// thread replies to put in it.
+    fn review_blob(&mut self) -> Option<String> {
+        if self.review.total() == 0 && self.review.summary.is_empty() {
+            self.mode = Mode::Normal;
+            self.status = Some("Only thread replies — send them with p or s".into());
+            return None;
+        }
+        let blocks = blob::blocks(&self.review.comments, |file| {
+            let index = self.files.iter().position(|f| f.path == file)?;
+            self.cache[index].as_ref().map(|rf| &rf.diff)
+        });
+        Some(blob::build(
+            &self.range.label,
+            &self.review.summary,
+            &self.review.file_comments,
+            &blocks,
+        ))
+    }
+
+    /// Build the wrapped review blob, copy it to the clipboard, and signal quit.
+    fn copy_review(&mut self) -> bool {
+        let Some(text) = self.review_blob() else {
+            return false;
+        };
+        self.stamp_reviewed();
+        let total = self.review.total();
         self.finish_message = Some(match crate::clipboard::copy(&text) {
             Ok(()) => format!("✓ Copied review ({total} comment(s)) to the clipboard."),
             Err(_) => format!("Clipboard tool unavailable — here is the review blob:\n\n{text}"),
@@ -728,10 +1242,95 @@ impl App {
         true
     }
 
+    /// Signal quit with the review blob to hand to the coding agent once the
+    /// terminal is restored, recording each comment's lines so the next open
+    /// can show which ones the agent changed.
+    fn hand_to_agent(&mut self) -> bool {
+        let Some(text) = self.review_blob() else {
+            return false;
+        };
+        if let Ok(root) = crate::git::worktree::current_worktree_root() {
+            handoff::mark(&mut self.review, &self.files, self.range.to, &root);
+        }
+        self.stamp_reviewed();
+        self.handoff = Some(text);
+        true
+    }
+
+    /// Record the tip the review covers, so the next one can show what changed
+    /// since. Only a finished review, or one marked with `M`, counts.
+    fn stamp_reviewed(&mut self) {
+        if let Endpoint::Commit(tip) = self.range.to {
+            self.review.reviewed = Some(tip);
+        }
+    }
+
+    fn mark_reviewed(&mut self) {
+        if !matches!(self.range.to, Endpoint::Commit(_)) {
+            self.status = Some("Only a range ending at a commit can be marked reviewed".into());
+            return;
+        }
+        self.stamp_reviewed();
+        self.save();
+        self.status =
+            Some("Marked reviewed at this tip — next time, s n shows what changed since".into());
+    }
+
+    /// Send the comments to the pull request under review as a GitHub review,
+    /// and signal quit. A failure stays in the TUI with the comments intact.
+    fn submit_review(&mut self, submission: Submission) -> bool {
+        let Some(pr) = self.range.pull.clone() else {
+            return false;
+        };
+        self.mode = Mode::Normal;
+        let total = self.review.total();
+        let replies = self.review.replies.len();
+        let url = match pull::submit(&pr, &self.review, submission) {
+            Ok(url) => url,
+            Err(e) => {
+                self.status = Some(format!("Submitting the review failed: {e}"));
+                return false;
+            }
+        };
+
+        let mut message = match submission {
+            Submission::Pending => format!(
+                "✓ Added {total} comment(s) to a pending review on #{} — submit it on GitHub: {url}",
+                pr.number
+            ),
+            Submission::Submit => format!(
+                "✓ Submitted a review ({}) with {total} comment(s) on #{}: {url}",
+                self.review.summary.verdict.label(),
+                pr.number
+            ),
+        };
+        if replies > 0 {
+            message.push_str(&format!("\nIncludes {replies} thread reply(ies)."));
+        }
+        let orphaned = self.review.orphaned.len();
+        if orphaned > 0 {
+            message.push_str(&format!(
+                "\n{orphaned} orphaned comment(s) were not sent; they stay in the review."
+            ));
+        }
+        self.stamp_reviewed();
+        // What was sent lives on GitHub now; drop it so a relaunch does not
+        // send it twice. Orphaned comments (never sent) and viewed marks stay,
+        // and are saved on the way out.
+        self.review = ReviewState {
+            orphaned: std::mem::take(&mut self.review.orphaned),
+            viewed: std::mem::take(&mut self.review.viewed),
+            reviewed: self.review.reviewed,
+            ..ReviewState::default()
+        };
+        self.finish_message = Some(message);
+        true
+    }
+
     /// Pop out to `$EDITOR` (or `$VISUAL`) to compose the current comment. The
     /// loop calls this because it owns the terminal needed to suspend the TUI.
     fn run_editor(&mut self, terminal: &mut crate::ui::Term) {
-        let Some(buffer) = self.popup.as_ref().map(|p| p.buffer.clone()) else {
+        let Some(buffer) = self.popup.as_mut().map(|p| p.text_mut().clone()) else {
             return;
         };
 
@@ -777,7 +1376,7 @@ impl App {
                 if let Ok(contents) = std::fs::read_to_string(&path)
                     && let Some(p) = self.popup.as_mut()
                 {
-                    p.buffer = contents.trim_end_matches('\n').to_string();
+                    *p.text_mut() = contents.trim_end_matches('\n').to_string();
                 }
             }
             _ => self.status = Some("$EDITOR did not save; kept your draft".into()),
@@ -799,8 +1398,14 @@ impl App {
                 self.mode = Mode::Normal;
                 return;
             }
+            KeyCode::Char('n') => {
+                self.show_since_last_review();
+                return;
+            }
             KeyCode::Char('b') => range::resolve_branch(None),
             KeyCode::Char('u') => range::resolve_uncommitted(),
+            KeyCode::Char('i') => range::resolve_staged(),
+            KeyCode::Char('w') => range::resolve_unstaged(),
             KeyCode::Char('t') => range::resolve_branch(None).map(|mut r| {
                 r.to = Endpoint::WorkingTree;
                 r.label = format!("{} +worktree", r.label);
@@ -812,7 +1417,10 @@ impl App {
             _ => return,
         };
         match resolved {
-            Ok(range) => self.switch_range(range),
+            Ok(range) => {
+                self.since_base = None;
+                self.switch_range(range);
+            }
             Err(e) => {
                 self.status = Some(format!("Range switch failed: {e}"));
                 self.mode = Mode::Normal;
@@ -820,21 +1428,54 @@ impl App {
         }
     }
 
+    /// Switch to the interdiff between the tip last reviewed and the current one.
+    fn show_since_last_review(&mut self) {
+        self.mode = Mode::Normal;
+        if self.since_base.is_some() {
+            self.status = Some("Already showing the changes since your last review".into());
+            return;
+        }
+        let Some(reviewed) = self.last_reviewed else {
+            self.status = Some("No earlier review of this range to compare with".into());
+            return;
+        };
+        if self.range.to == Endpoint::Commit(reviewed) {
+            self.status = Some("Nothing new since your last review".into());
+            return;
+        }
+        match since::resolve(&self.range, reviewed) {
+            Ok(interdiff) => {
+                self.since_base = Some(self.range.clone());
+                if self.switch_range(interdiff.range) {
+                    self.status = Some(format!(
+                        "Changes since your last review: {} new or rewritten commit(s), {} unchanged \
+                         — s b for the whole branch",
+                        interdiff.changed, interdiff.unchanged
+                    ));
+                } else {
+                    self.since_base = None;
+                }
+            }
+            Err(e) => self.status = Some(format!("Can't compare with your last review: {e}")),
+        }
+    }
+
     /// Re-resolve to `new_range`, rebuild the file list and diffs, and load the
     /// (separately-keyed) review for the new scope. The current scope's review
-    /// is saved first.
-    fn switch_range(&mut self, new_range: ReviewRange) {
-        if let Some(key) = &self.key {
-            let _ = state::save(key, &self.review);
-        }
+    /// is saved first. Returns whether the switch happened.
+    fn switch_range(&mut self, new_range: ReviewRange) -> bool {
+        self.save();
         match diff::changed_files(&new_range) {
             Ok(files) => {
                 self.cache = (0..files.len()).map(|_| None).collect();
+                self.explain_keys = vec![None; files.len()];
                 // Rebuild the sidebar tree for the new file set — otherwise its
                 // file indices point into the old list and selecting one can
                 // index past `files`.
                 self.tree = FileTree::new(files.iter().map(|f| (f.path.clone(), f.status)));
+                self.content_ids = files.iter().map(|f| f.content_id(new_range.to)).collect();
                 self.files = files;
+                let same_scope = new_range.scope_id == self.range.scope_id;
                 self.range = new_range;
                 self.selected = 0;
                 self.cursor = 0;
@@ -848,16 +1489,28 @@ impl App {
                     .ok()
                     .map(|d| state::storage_key(&d, &self.range.scope_id));
                 self.review = self.key.as_deref().map(state::load).unwrap_or_default();
-                self.status = Some(if self.files.is_empty() {
+                // Within one scope keep comparing against the review this
+                // session started from, even after an `M`.
+                if !same_scope {
+                    self.last_reviewed = self.review.reviewed;
+                }
+                self.sync_hidden();
+                let (threads, thread_status) = load_threads(&self.range);
+                self.threads = threads;
+                self.open_thread = None;
+                let offer = self.compare_with_last_review();
+                self.status = thread_status.or(offer).or(Some(if self.files.is_empty() {
                     format!("Switched to {} — no changes", self.range.label)
                 } else {
                     format!("Switched to {}", self.range.label)
-                });
+                }));
                 self.mode = Mode::Normal;
+                true
             }
             Err(e) => {
                 self.status = Some(format!("Range switch failed: {e}"));
                 self.mode = Mode::Normal;
+                false
             }
         }
     }
@@ -883,6 +1536,15 @@ impl App {
         } else {
             main
         };
+        let (diff_area, explain_area) = if self.explainer.open {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Percentage(40)])
+                .split(diff_area);
+            (cols[0], Some(cols[1]))
+        } else {
+            (diff_area, None)
+        };
 
         let view = self.view_mode(diff_area.width);
         self.last_view = view;
@@ -896,7 +1558,13 @@ impl App {
 
         let marks = self
             .current_path()
-            .map(|p| self.review.marks_for(p))
+            .map(|p| {
+                let mut marks = self.review.marks_for(p);
+                for t in self.threads.iter().filter(|t| t.path == p) {
+                    marks.add_thread(t.side, t.start_line, t.end_line, !t.resolved && !t.outdated);
+                }
+                marks
+            })
             .unwrap_or_default();
 
         if self.files.is_empty() {
@@ -916,6 +1584,14 @@ impl App {
             );
         }
 
+        if let Some(area) = explain_area {
+            let key = self
+                .explain_keys
+                .get(self.selected)
+                .and_then(Option::as_deref);
+            self.explainer.render(f, area, key, "file");
+        }
+
         f.render_widget(self.help_bar(), help_area);
 
         match self.mode {
@@ -923,6 +1599,8 @@ impl App {
             Mode::CommentPopup => self.draw_popup(f, area),
             Mode::OrphanedList => self.draw_orphans(f, area),
             Mode::RangeSwitch => self.draw_rangeswitch(f, area),
+            Mode::FinishChoice => self.draw_finish_choice(f, area),
+            Mode::Thread => self.draw_thread(f, area),
             _ => {}
         }
     }
@@ -931,19 +1609,24 @@ impl App {
         let lines = vec![
             Line::from(Span::styled(
                 "Switch range",
-                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
             )),
             Line::raw(""),
             Line::raw("b   branch vs base (committed)"),
             Line::raw("t   branch vs base + working tree"),
             Line::raw("u   uncommitted (working tree vs HEAD)"),
+            Line::raw("i   staged only (index vs HEAD)"),
+            Line::raw("w   unstaged only (working tree vs index)"),
+            Line::raw("n   changes since your last review"),
             Line::raw(""),
             Line::from(Span::styled(
                 "esc to cancel",
                 Style::default().fg(Color::DarkGray),
             )),
         ];
-        let popup = centered_rect(50, 45, area);
+        let popup = centered_rect(50, 55, area);
         f.render_widget(Clear, popup);
         let block = Block::default()
             .borders(Borders::ALL)
@@ -951,24 +1634,127 @@ impl App {
         f.render_widget(Paragraph::new(lines).block(block), popup);
     }
 
+    fn draw_finish_choice(&self, f: &mut Frame, area: Rect) {
+        let heading = match &self.range.pull {
+            Some(pr) => format!("Finish the review of #{}", pr.number),
+            None => format!("Finish the review of {}", self.range.label),
+        };
+        let title = vec![
+            Line::from(Span::styled(
+                heading,
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::raw(""),
+        ];
+        let mut lines = title;
+        if self.range.pull.is_some() {
+            lines.push(Line::raw(
+                "p   add to a pending GitHub review (submit it on GitHub)",
+            ));
+            lines.push(Line::raw(format!(
+                "s   submit a GitHub review ({}; R to change)",
+                self.review.summary.verdict.label()
+            )));
+        }
+        lines.extend([
+            Line::raw("c   copy to the clipboard for an agent"),
+            Line::raw("a   hand to the agent now (launches it here)"),
+            Line::raw(""),
+            Line::from(Span::styled(
+                "esc to cancel",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ]);
+        let popup = centered_rect(60, 45, area);
+        f.render_widget(Clear, popup);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Finish — {} comment(s) ", self.review.total()));
+        f.render_widget(Paragraph::new(lines).block(block), popup);
+    }
+
+    fn draw_thread(&self, f: &mut Frame, area: Rect) {
+        let Some(t) = self.open_thread.and_then(|i| self.threads.get(i)) else {
+            return;
+        };
+        let mut state = vec![if t.resolved { "resolved" } else { "open" }];
+        if t.outdated {
+            state.push("outdated");
+        }
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!(
+                    "{} {} · {}",
+                    t.path,
+                    blob::location(t.side, t.start_line, t.end_line),
+                    state.join(", ")
+                ),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::raw(""),
+        ];
+        for c in &t.comments {
+            lines.push(Line::from(Span::styled(
+                format!("@{}", c.author),
+                Style::default().fg(Color::Yellow),
+            )));
+            lines.extend(c.body.lines().map(|l| Line::raw(l.to_string())));
+            lines.push(Line::raw(""));
+        }
+        if let Some(reply) = self.review.reply_for(&t.id) {
+            lines.push(Line::from(Span::styled(
+                "Your reply (posted on finish)",
+                Style::default().fg(Color::Magenta),
+            )));
+            lines.extend(reply.body.lines().map(|l| Line::raw(l.to_string())));
+            lines.push(Line::raw(""));
+        }
+        lines.push(Line::from(Span::styled(
+            "r reply · esc close",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let popup = centered_rect(70, 70, area);
+        f.render_widget(Clear, popup);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Review thread ");
+        f.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: false }),
+            popup,
+        );
+    }
+
     fn draw_orphans(&self, f: &mut Frame, area: Rect) {
         let mut lines = vec![
             Line::from(Span::styled(
                 "Orphaned comments (no longer resolve to a diff line)",
-                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
             )),
             Line::raw(""),
         ];
         for c in &self.review.orphaned {
             let loc = blob::location(c.side, c.start_line, c.end_line);
-            lines.push(Line::from(vec![
+            let mut spans = vec![
                 Span::styled(
                     format!("{} {loc}", c.file),
                     Style::default().fg(Color::Yellow),
                 ),
                 Span::raw("  "),
-                Span::raw(c.body.lines().next().unwrap_or("").to_string()),
-            ]));
+            ];
+            if c.handoff.as_ref().is_some_and(|h| h.touched) {
+                spans.push(Span::styled("✓ ", Style::default().fg(Color::Green)));
+            }
+            spans.push(Span::raw(c.body.lines().next().unwrap_or("").to_string()));
+            lines.push(Line::from(spans));
         }
         lines.push(Line::raw(""));
         lines.push(Line::from(Span::styled(
@@ -1001,13 +1787,20 @@ impl App {
 
     fn draw_sidebar(&self, f: &mut Frame, area: Rect) {
         let total = self.review.total();
-        let title = if matches!(self.mode, Mode::Filter) || !self.filter.is_empty() {
+        let viewed = self.viewed_count();
+        let mut title = if matches!(self.mode, Mode::Filter) || !self.filter.is_empty() {
             format!(" Files · /{} ", self.filter)
-        } else if total > 0 {
-            format!(" Files ({}) · {total}c ", self.files.len())
+        } else if viewed > 0 || self.hide_viewed {
+            format!(" Files {viewed}/{} ✓ ", self.files.len())
         } else {
             format!(" Files ({}) ", self.files.len())
         };
+        if self.hide_viewed {
+            title.push_str("· hiding ✓ ");
+        }
+        if total > 0 {
+            title.push_str(&format!("· {total}c "));
+        }
         let border_style = if self.focus == Focus::Sidebar {
             Style::default().fg(Color::Cyan)
         } else {
@@ -1043,7 +1836,10 @@ impl App {
                         if on_cursor {
                             style = style.bg(self.palette.select_bg);
                         }
-                        Line::from(Span::styled(format!("{indent}{arrow} {}/", row.name), style))
+                        Line::from(Span::styled(
+                            format!("{indent}{arrow} {}/", row.name),
+                            style,
+                        ))
                     }
                     NodeKind::File { index, status } => {
                         let icon = status_char(*status);
@@ -1052,6 +1848,9 @@ impl App {
                         if *index == self.selected {
                             name_style = name_style.add_modifier(Modifier::BOLD);
                         }
+                        if self.unchanged.contains(&row.path) {
+                            name_style = name_style.fg(Color::DarkGray);
+                        }
                         if on_cursor {
                             name_style = name_style.bg(self.palette.select_bg);
                         }
@@ -1060,6 +1859,9 @@ impl App {
                             Span::styled(format!("{icon} "), Style::default().fg(color)),
                             Span::styled(row.name.clone(), name_style),
                         ];
+                        if self.is_viewed(*index) {
+                            spans.push(Span::styled(" ✓", Style::default().fg(Color::Green)));
+                        }
                         let count = self.review.count_for_file(&row.path);
                         if count > 0 {
                             spans.push(Span::styled(
@@ -1089,6 +1891,8 @@ impl App {
                 ("j/k", "move"),
                 ("⏎", "open/toggle"),
                 ("h", "collapse"),
+                ("m", "viewed"),
+                ("H", "hide viewed"),
                 ("/", "filter"),
                 ("Tab", "diff"),
                 ("q", "quit"),
@@ -1104,13 +1908,19 @@ impl App {
                 ("]c", "hunk"),
                 ("Tab", "file"),
                 ("s", "range"),
+                ("e", "explain"),
                 ("v", "view"),
                 ("F", "finish"),
                 ("X", "reset"),
                 ("?", "help"),
                 ("q", "quit"),
             ],
-            Mode::VisualSelect => &[("j/k", "extend"), ("c", "comment"), ("esc", "cancel")],
+            Mode::VisualSelect => &[
+                ("j/k", "extend"),
+                ("c", "comment"),
+                ("S/U", "stage/unstage"),
+                ("esc", "cancel"),
+            ],
             Mode::CommentPopup => &[
                 ("type", "comment"),
                 ("C-s", "save"),
@@ -1119,7 +1929,12 @@ impl App {
                 ("esc", "cancel"),
             ],
             Mode::OrphanedList => &[("esc", "close")],
-            Mode::RangeSwitch => &[("b/t/u", "pick"), ("esc", "cancel")],
+            Mode::RangeSwitch => &[("b/t/u/i/w/n", "pick"), ("esc", "cancel")],
+            Mode::FinishChoice if self.range.pull.is_some() => {
+                &[("p/s/c/a", "pick"), ("esc", "cancel")]
+            }
+            Mode::FinishChoice => &[("c/a", "pick"), ("esc", "cancel")],
+            Mode::Thread => &[("r", "reply"), ("esc", "close")],
             Mode::Filter => &[("type", "filter"), ("⏎", "apply"), ("esc", "clear")],
             Mode::Help => &[("esc", "close")],
         };
@@ -1134,8 +1949,25 @@ impl App {
         } else {
             p.start_line.to_string()
         };
-        let verb = if p.editing.is_some() { "Edit" } else { "Comment" };
-        let title = format!(" {verb} {name}:{lines} ");
+        let title = match p.kind {
+            PopupKind::Line if p.editing.is_some() => format!(" Edit {name}:{lines} "),
+            PopupKind::Line => format!(" Comment {name}:{lines} "),
+            PopupKind::Reply(_) => format!(" Reply on {name}:{lines} "),
+            PopupKind::File => format!(" Comment on all of {name} "),
+            PopupKind::Summary(verdict) => format!(" Review summary — {} ", verdict.label()),
+        };
+        let hint = match p.kind {
+            PopupKind::Summary(_) => {
+                "Tab verdict · Ctrl-s save · Ctrl-e $EDITOR · ⏎ newline · esc cancel"
+            }
+            PopupKind::Line if p.suggestion.is_some() => {
+                "Tab note/suggestion · Ctrl-g drop suggestion · Ctrl-s save · Ctrl-e $EDITOR · esc cancel"
+            }
+            PopupKind::Line => {
+                "Ctrl-g suggest a change · Ctrl-s save · Ctrl-e $EDITOR · ⏎ newline · esc cancel"
+            }
+            _ => "Ctrl-s save · Ctrl-e $EDITOR · ⏎ newline · esc cancel",
+        };
 
         let popup_area = centered_rect(60, 50, area);
         f.render_widget(Clear, popup_area);
@@ -1151,15 +1983,39 @@ impl App {
             .constraints([Constraint::Min(1), Constraint::Length(1)])
             .split(inner);
 
-        let mut text = p.buffer.clone();
-        text.push('▏'); // simple insertion caret
-        f.render_widget(
-            Paragraph::new(text).wrap(Wrap { trim: false }),
-            body_help[0],
-        );
+        let caret = |text: &str, focused: bool| {
+            let mut text = text.to_string();
+            if focused {
+                text.push('▏'); // simple insertion caret
+            }
+            Paragraph::new(text).wrap(Wrap { trim: false })
+        };
+        match &p.suggestion {
+            None => f.render_widget(caret(&p.buffer, true), body_help[0]),
+            Some(suggestion) => {
+                let [note, replacement] = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                    .areas(body_help[0]);
+                f.render_widget(caret(&p.buffer, !p.in_suggestion), note);
+                let border = if p.in_suggestion {
+                    Color::Green
+                } else {
+                    Color::DarkGray
+                };
+                let block = Block::default()
+                    .borders(Borders::TOP)
+                    .border_style(Style::default().fg(border))
+                    .title(" Suggested change ");
+                f.render_widget(
+                    caret(&suggestion.replacement, p.in_suggestion).block(block),
+                    replacement,
+                );
+            }
+        }
         f.render_widget(
             Paragraph::new(Line::from(Span::styled(
-                "Ctrl-s save · Ctrl-e $EDITOR · ⏎ newline · esc cancel",
+                hint,
                 Style::default().fg(Color::DarkGray),
             ))),
             body_help[1],
@@ -1170,7 +2026,9 @@ impl App {
         let lines = vec![
             Line::from(Span::styled(
                 "gx review — keys",
-                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
             )),
             Line::raw(""),
             Line::raw("j / k          move cursor"),
@@ -1178,15 +2036,31 @@ impl App {
             Line::raw("g / G          top / bottom"),
             Line::raw("]c / [c        next / prev hunk  (also } / {)"),
             Line::raw("Tab            focus the file sidebar (j/k move, ⏎ open, / filter)"),
-            Line::raw("s              switch range (branch / +worktree / uncommitted)"),
+            Line::raw(
+                "s              switch range (branch, +worktree, uncommitted, staged, unstaged, since last review)",
+            ),
             Line::raw("h / l  ← →     scroll horizontally"),
             Line::raw("c              comment on the current line"),
             Line::raw("V              start a multi-line selection, then c"),
-            Line::raw("⏎              edit the comment under the cursor"),
+            Line::raw("⏎              edit the comment (or open the PR thread) under the cursor"),
+            Line::raw("r              reply to the PR review thread under the cursor"),
             Line::raw("D              delete the comment under the cursor"),
-            Line::raw("F              finish: copy the review to the clipboard"),
+            Line::raw("Ctrl-g         in a line comment: suggest replacement lines (Tab switches)"),
+            Line::raw("C              comment on the whole file"),
+            Line::raw(
+                "R              review summary and verdict (approve / request changes / comment)",
+            ),
+            Line::raw("m / H          mark the file viewed / hide viewed files in the sidebar"),
+            Line::raw("M              mark the range reviewed at its current tip"),
+            Line::raw("S / U          stage / unstage the hunk (or V selection) under the cursor"),
+            Line::raw(
+                "F              finish: copy the review or hand it to the agent (a PR: or send it to GitHub)",
+            ),
             Line::raw("o              list orphaned comments (after a diff change)"),
             Line::raw("X              discard the saved review (press twice)"),
+            Line::raw(
+                "e              explain this file's diff with the agent (J/K scroll, esc close)",
+            ),
             Line::raw("v              toggle split / unified"),
             Line::raw("b              toggle sidebar"),
             Line::raw("? / esc        close this help"),
@@ -1199,6 +2073,28 @@ impl App {
     }
 }
 
+fn action_verb(action: Action) -> &'static str {
+    match action {
+        Action::Stage => "stage",
+        Action::Unstage => "unstage",
+    }
+}
+
+/// The pull request's existing review threads, plus a status line when they
+/// could not be loaded (the review itself still works without them).
+fn load_threads(range: &ReviewRange) -> (Vec<Thread>, Option<String>) {
+    match &range.pull {
+        None => (Vec::new(), None),
+        Some(pr) => match pull::threads(pr) {
+            Ok(threads) => (threads, None),
+            Err(e) => (
+                Vec::new(),
+                Some(format!("Could not load review threads: {e}")),
+            ),
+        },
+    }
+}
+
 /// A centered rectangle `percent_x`% × `percent_y`% of `area`.
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let vertical = Layout::default()
@@ -114,9 +114,7 @@ pub fn run(
                     selected_index = selected_index.saturating_sub(1);
                 }
                 (KeyCode::Down, _) | (KeyCode::Char('j'), _) => {
-                    if selected_index < candidates.len() - 1 {
-                        selected_index += 1;
-                    }
+                    selected_index = (selected_index + 1).min(candidates.len().saturating_sub(1));
                 }
                 (KeyCode::Char(' '), _) => {
                     let path = &candidates[selected_index].path;
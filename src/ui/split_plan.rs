@@ -0,0 +1,297 @@
+//! The `gx commit --ai-split` plan review: the proposed commits with their
+//! hunks on the left, the selected hunk's diff (or commit's message) on the
+//! right. Hunks can be moved between commits and into a trailing
+//! "not committed" bucket before confirming.
+
+use super::{Term, adjust_scroll, render_help_bar, truncate};
+use crate::git::split::{PlannedCommit, SplitPlan, WorkingChanges};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use miette::IntoDiagnostic;
+use ratatui::prelude::*;
+use ratatui::widgets::*;
+
+/// A commit in the plan; the last group is the bucket of hunks left out of
+/// every commit and has no message.
+struct Group {
+    message: Option<String>,
+    hunks: Vec<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Header(usize),
+    Hunk(usize, usize),
+}
+
+fn rows(groups: &[Group]) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for (g, group) in groups.iter().enumerate() {
+        rows.push(Row::Header(g));
+        rows.extend(group.hunks.iter().map(|&id| Row::Hunk(g, id)));
+    }
+    rows
+}
+
+/// Move hunk `id` from group `from` to the neighbouring group in direction
+/// `step` (-1 or +1), keeping each group's hunks in file order. Returns the
+/// group it landed in.
+fn move_hunk(groups: &mut [Group], from: usize, id: usize, step: isize) -> usize {
+    let Some(to) = from
+        .checked_add_signed(step)
+        .filter(|&to| to < groups.len())
+    else {
+        return from;
+    };
+    groups[from].hunks.retain(|&h| h != id);
+    let hunks = &mut groups[to].hunks;
+    let at = hunks.partition_point(|&h| h < id);
+    hunks.insert(at, id);
+    to
+}
+
+pub fn run(
+    terminal: &mut Term,
+    changes: &WorkingChanges,
+    plan: SplitPlan,
+) -> miette::Result<Option<SplitPlan>> {
+    let mut groups: Vec<Group> = plan
+        .commits
+        .into_iter()
+        .map(|c| Group {
+            message: Some(c.message),
+            hunks: c.hunks,
+        })
+        .collect();
+    groups.push(Group {
+        message: None,
+        hunks: plan.unassigned,
+    });
+
+    let mut selected = 0;
+    let mut scroll_offset = 0;
+    let mut preview_scroll: u16 = 0;
+
+    loop {
+        let all_rows = rows(&groups);
+        selected = selected.min(all_rows.len() - 1);
+        let commit_count = groups.iter().filter(|g| g.message.is_some()).count();
+
+        terminal
+            .draw(|f| {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), Constraint::Length(3)])
+                    .split(f.area());
+                let panes = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+                    .split(chunks[0]);
+
+                let visible_height = panes[0].height.saturating_sub(2) as usize;
+                scroll_offset = adjust_scroll(selected, scroll_offset, visible_height);
+                let width = panes[0].width.saturating_sub(4) as usize;
+
+                let items: Vec<ListItem> = all_rows
+                    .iter()
+                    .enumerate()
+                    .skip(scroll_offset)
+                    .take(visible_height)
+                    .map(|(i, row)| {
+                        let line = match *row {
+                            Row::Header(g) => {
+                                let group = &groups[g];
+                                let count = group.hunks.len();
+                                match &group.message {
+                                    Some(message) => {
+                                        let subject = message.lines().next().unwrap_or("");
+                                        Line::from(vec![
+                                            Span::styled(
+                                                format!("{}. ", g + 1),
+                                                Style::default().fg(Color::Yellow),
+                                            ),
+                                            Span::styled(
+                                                truncate(subject, width.saturating_sub(16)),
+                                                Style::default().bold(),
+                                            ),
+                                            Span::styled(
+                                                format!(" ({count} hunks)"),
+                                                Style::default().fg(Color::DarkGray),
+                                            ),
+                                        ])
+                                    }
+                                    None => Line::from(Span::styled(
+                                        format!("Not committed ({count} hunks)"),
+                                        Style::default().fg(Color::DarkGray).bold(),
+                                    )),
+                                }
+                            }
+                            Row::Hunk(_, id) => {
+                                let (added, removed) = changes.stats(id);
+                                Line::from(vec![
+                                    Span::styled(
+                                        format!("   h{} ", id + 1),
+                                        Style::default().fg(Color::Cyan),
+                                    ),
+                                    Span::raw(truncate(
+                                        &changes.label(id),
+                                        width.saturating_sub(16),
+                                    )),
+                                    Span::styled(
+                                        format!(" +{added}"),
+                                        Style::default().fg(Color::Green),
+                                    ),
+                                    Span::styled(
+                                        format!(" -{removed}"),
+                                        Style::default().fg(Color::Red),
+                                    ),
+                                ])
+                            }
+                        };
+                        if i == selected {
+                            ListItem::new(line).style(Style::default().bg(Color::DarkGray))
+                        } else {
+                            ListItem::new(line)
+                        }
+                    })
+                    .collect();
+
+                let title = format!(" Split plan ({commit_count} commits) ");
+                f.render_widget(
+                    List::new(items).block(Block::default().borders(Borders::ALL).title(title)),
+                    panes[0],
+                );
+
+                let (title, lines): (String, Vec<Line>) = match all_rows[selected] {
+                    Row::Header(g) => match &groups[g].message {
+                        Some(message) => (
+                            format!(" Commit {} ", g + 1),
+                            message.lines().map(|l| Line::raw(l.to_string())).collect(),
+                        ),
+                        None => (
+                            " Not committed ".to_string(),
+                            vec![Line::styled(
+                                "Hunks here stay in the working tree.",
+                                Style::default().fg(Color::DarkGray),
+                            )],
+                        ),
+                    },
+                    Row::Hunk(_, id) => (
+                        format!(" {} ", changes.label(id)),
+                        changes
+                            .patch_lines(id)
+                            .into_iter()
+                            .map(|l| {
+                                let color = match l.chars().next() {
+                                    Some('+') => Color::Green,
+                                    Some('-') => Color::Red,
+                                    _ => Color::Reset,
+                                };
+                                Line::styled(l, Style::default().fg(color))
+                            })
+                            .collect(),
+                    ),
+                };
+                f.render_widget(
+                    Paragraph::new(lines)
+                        .scroll((preview_scroll, 0))
+                        .block(Block::default().borders(Borders::ALL).title(title)),
+                    panes[1],
+                );
+
+                f.render_widget(
+                    render_help_bar(&[
+                        ("j/k", "navigate"),
+                        ("h/l", "move hunk to prev/next commit"),
+                        ("J/K", "scroll diff"),
+                        ("enter", "create commits"),
+                        ("esc", "cancel"),
+                    ]),
+                    chunks[1],
+                );
+            })
+            .into_diagnostic()?;
+
+        if let Event::Key(key) = event::read().into_diagnostic()? {
+            match (key.code, key.modifiers) {
+                (KeyCode::Esc, _)
+                | (KeyCode::Char('q'), _)
+                | (KeyCode::Char('c'), KeyModifiers::CONTROL) => return Ok(None),
+                (KeyCode::Enter, _) => {
+                    let unassigned = groups.pop().map(|g| g.hunks).unwrap_or_default();
+                    let commits = groups
+                        .into_iter()
+                        .map(|g| PlannedCommit {
+                            message: g.message.unwrap_or_default(),
+                            hunks: g.hunks,
+                        })
+                        .collect();
+                    return Ok(Some(SplitPlan {
+                        commits,
+                        unassigned,
+                    }));
+                }
+                (KeyCode::Up, _) | (KeyCode::Char('k'), _) => {
+                    selected = selected.saturating_sub(1);
+                    preview_scroll = 0;
+                }
+                (KeyCode::Down, _) | (KeyCode::Char('j'), _) => {
+                    selected = (selected + 1).min(all_rows.len() - 1);
+                    preview_scroll = 0;
+                }
+                (KeyCode::Char('J'), _) | (KeyCode::PageDown, _) => {
+                    preview_scroll = preview_scroll.saturating_add(5);
+                }
+                (KeyCode::Char('K'), _) | (KeyCode::PageUp, _) => {
+                    preview_scroll = preview_scroll.saturating_sub(5);
+                }
+                (KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('<'), _)
+                | (KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('>'), _) => {
+                    if let Row::Hunk(g, id) = all_rows[selected] {
+                        let step = match key.code {
+                            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('<') => -1,
+                            _ => 1,
+                        };
+                        let to = move_hunk(&mut groups, g, id, step);
+                        // Keep the cursor on the hunk that moved.
+                        selected = rows(&groups)
+                            .iter()
+                            .position(|&r| r == Row::Hunk(to, id))
+                            .unwrap_or(selected);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups(layout: &[&[usize]]) -> Vec<Group> {
+        layout
+            .iter()
+            .map(|hunks| Group {
+                message: Some(String::new()),
+                hunks: hunks.to_vec(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_move_hunk_keeps_file_order_and_stops_at_edges() {
+        let mut gs = groups(&[&[0, 2], &[1, 3], &[]]);
+        assert_eq!(move_hunk(&mut gs, 0, 2, 1), 1);
+        assert_eq!(gs[0].hunks, vec![0]);
+        assert_eq!(gs[1].hunks, vec![1, 2, 3]);
+
+        // Already in the first group: nothing moves.
+        assert_eq!(move_hunk(&mut gs, 0, 0, -1), 0);
+        assert_eq!(gs[0].hunks, vec![0]);
+
+        assert_eq!(move_hunk(&mut gs, 1, 3, 1), 2);
+        assert_eq!(move_hunk(&mut gs, 2, 3, 1), 2);
+        assert_eq!(gs[2].hunks, vec![3]);
+    }
+}
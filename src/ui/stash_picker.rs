@@ -141,9 +141,7 @@ pub fn run(
                         selected_index = selected_index.saturating_sub(1);
                     }
                     (KeyCode::Down, _) | (KeyCode::Char('j'), _) => {
-                        if selected_index + 1 < stashes.len() {
-                            selected_index += 1;
-                        }
+                        selected_index = (selected_index + 1).min(stashes.len().saturating_sub(1));
                     }
                     (KeyCode::Enter, _) => {
                         mode = Mode::Action;
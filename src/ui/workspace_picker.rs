@@ -765,9 +765,8 @@ pub fn run(
                             selected_index = selected_index.saturating_sub(1);
                         }
                         (KeyCode::Down, _) | (KeyCode::Char('j'), KeyModifiers::NONE) => {
-                            if selected_index + 1 < filtered.len() {
-                                selected_index += 1;
-                            }
+                            selected_index =
+                                (selected_index + 1).min(filtered.len().saturating_sub(1));
                         }
                         (KeyCode::PageUp, _) => {
                             selected_index = selected_index.saturating_sub(10);